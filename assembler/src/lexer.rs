@@ -8,6 +8,8 @@ pub struct Lexer<'a> {
     stream: &'a str,
     line: usize,
     column: usize,
+    token_line: usize,
+    token_column: usize,
 }
 
 impl<'a> Lexer<'a> {
@@ -16,6 +18,8 @@ impl<'a> Lexer<'a> {
             stream,
             line: 1,
             column: 1,
+            token_line: 1,
+            token_column: 1,
         }
     }
 
@@ -31,13 +35,18 @@ impl<'a> Lexer<'a> {
         self.column
     }
 
+    /// Retorna a linha e a coluna em que o último *token* lido começa.
+    pub fn token_position(&self) -> (usize, usize) {
+        (self.token_line, self.token_column)
+    }
+
     fn increment_line(&mut self) {
         self.line += 1;
         self.column = 1;
     }
 
     fn next_line(&mut self) {
-        self.stream = match self.stream.split_once(|c| c == '\n') {
+        self.stream = match self.stream.split_once('\n') {
             Some((_, s)) => {
                 self.increment_line();
                 s
@@ -47,18 +56,12 @@ impl<'a> Lexer<'a> {
     }
 
     fn trim_left(&mut self) {
-        let mut n = 0;
+        let mut n = self.stream.len();
 
         for (i, c) in self.stream.char_indices() {
             match c {
-                '\n' => {
-                    n = i;
-                    self.increment_line();
-                }
-                c if c.is_whitespace() => {
-                    n = i;
-                    self.column += 1;
-                }
+                '\n' => self.increment_line(),
+                c if c.is_whitespace() => self.column += 1,
                 _ => {
                     n = i;
                     break;
@@ -76,56 +79,57 @@ impl<'a> Lexer<'a> {
         }
     }
 
-    fn string_item(s: &str) -> String {
-        let mut iter = s.chars();
-        let mut ret_val = String::with_capacity(s.len());
-        let mut count = 0;
-        while let Some(c) = iter.next() {
+    /// Retorna o item delimitado por `quote` presente no início de `s`, incluindo os
+    /// delimitadores. Caso o item não seja fechado, retorna `s` por inteiro.
+    fn quoted_item(s: &str, quote: char) -> &str {
+        let mut escaped = false;
+
+        for (i, c) in s.char_indices().skip(1) {
             match c {
-                '\\' => match iter.next() {
-                    Some(c) => ret_val.push(c),
-                    None => break,
-                },
-                '"' => {
-                    count += count;
-                    ret_val.push(c);
-                    if count == 2 {
-                        break;
-                    }
-                }
-                _ => ret_val.push(c),
+                '\\' if !escaped => escaped = true,
+                c if c == quote && !escaped => return &s[..i + c.len_utf8()],
+                _ => escaped = false,
             }
         }
 
-        ret_val
+        s
+    }
+
+    fn consume(&mut self, len: usize) -> String {
+        let item = &self.stream[..len];
+        self.column += item.chars().count();
+        self.stream = &self.stream[len..];
+        item.to_string()
     }
 
     fn next_item(&mut self) -> Option<String> {
         self.trim_left();
         self.skip_commentary();
 
+        self.token_line = self.line;
+        self.token_column = self.column;
+
         match self.stream.chars().next() {
             Some(c) => match c {
-                '"' => {
-                    let s = Self::string_item(self.stream);
-                    self.stream = &self.stream[s.len()..];
-                    Some(s)
-                }
-                c if c == '+' || c == '#' || c == ',' || c == ':' => {
-                    self.stream = &self.stream[1..];
-                    Some(c.to_string())
+                '"' | '\'' => {
+                    let len = Self::quoted_item(self.stream, c).len();
+                    Some(self.consume(len))
                 }
+                '+' | '#' | ',' | ':' => Some(self.consume(1)),
                 _ => {
-                    let str = self
+                    let len = self
                         .stream
-                        .chars()
-                        .take_while(|&c| {
-                            !c.is_whitespace() && c != '+' && c != '#' && c != ',' && c != ':'
+                        .find(|c: char| {
+                            c.is_whitespace()
+                                || c == '+'
+                                || c == '#'
+                                || c == ','
+                                || c == ':'
+                                || c == COMMENTATY_BEGIN
                         })
-                        .collect::<String>();
+                        .unwrap_or(self.stream.len());
 
-                    self.stream = &self.stream[str.len()..];
-                    Some(str)
+                    Some(self.consume(len))
                 }
             },
             None => None,
@@ -133,10 +137,7 @@ impl<'a> Lexer<'a> {
     }
 
     pub fn next_token(&mut self) -> Option<Result<Token, TokenError>> {
-        match self.next_item() {
-            Some(s) => Some(Token::from_str(&s)),
-            None => None,
-        }
+        self.next_item().map(|s| Token::from_str(&s))
     }
 }
 
@@ -217,6 +218,32 @@ mod tests {
         )
     }
 
+    #[test]
+    fn test_quoted_items() {
+        let mut lex = Lexer::new("string \"a, \\\"b\\\"\" #' ' ; fim");
+        assert_eq!(Token::String, lex.next_token().unwrap().unwrap());
+        assert_eq!(
+            Token::LiteralStr("a, \"b\"".to_string()),
+            lex.next_token().unwrap().unwrap()
+        );
+        assert_eq!(Token::Pound, lex.next_token().unwrap().unwrap());
+        assert_eq!(Token::LiteralChar(' '), lex.next_token().unwrap().unwrap());
+        assert_eq!(None, lex.next_token());
+    }
+
+    #[test]
+    fn test_token_position() {
+        let mut lex = Lexer::new("main:\n  LOADN R0, #1\n");
+        lex.next_token();
+        assert_eq!((1, 1), lex.token_position());
+        lex.next_token();
+        assert_eq!((1, 5), lex.token_position());
+        lex.next_token();
+        assert_eq!((2, 3), lex.token_position());
+        lex.next_token();
+        assert_eq!((2, 9), lex.token_position());
+    }
+
     #[test]
     fn test_token_identifier() {
         let mut lex = Lexer::new("foo_1:");
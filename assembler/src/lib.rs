@@ -2,111 +2,783 @@
 
 use std::{collections::HashMap, fs::File};
 
-use isa::Instruction;
+use isa::{Instruction, MemoryCell, OperandKind};
 use lexer::Lexer;
+use object::{ObjectModule, Relocation, SectionKind, SourceLine, Symbol};
 use thiserror::Error;
-use token::{Token, TokenError, TokenType};
+use token::TokenType;
 
 mod lexer;
+pub mod linker;
+pub mod object;
+pub mod patch;
 mod token;
 
+pub use token::{Token, TokenError, TokenKind};
+
 #[derive(Error, Debug, PartialEq)]
 pub enum AssemblerError {
-    #[error("Esperado {expected:?}, Recebido: {received:?}")]
+    #[error("{line}:{column}: Esperado {expected}, Recebido: {received:?}")]
     UnexpectedToken {
-        expected: Option<Token>,
+        line: usize,
+        column: usize,
+        expected: String,
         received: Option<Token>,
     },
 
-    #[error("{0}")]
-    InvalidToken(TokenError),
+    #[error("{line}:{column}: {error}")]
+    InvalidToken {
+        line: usize,
+        column: usize,
+        error: TokenError,
+    },
+
+    #[error("{line}:{column}: O rótulo \"{label}\" já foi definido.")]
+    DuplicateLabel {
+        line: usize,
+        column: usize,
+        label: String,
+    },
+
+    #[error("{line}:{column}: O rótulo \"{label}\" não foi definido neste módulo.")]
+    UndefinedLabel {
+        line: usize,
+        column: usize,
+        label: String,
+    },
+
+    #[error("{line}:{column}: O valor {value} está fora do intervalo permitido (0 a {max}).")]
+    ValueOutOfRange {
+        line: usize,
+        column: usize,
+        value: usize,
+        max: usize,
+    },
+}
+
+impl AssemblerError {
+    /// Retorna a linha e a coluna do código em que o erro foi encontrado.
+    pub fn position(&self) -> (usize, usize) {
+        match self {
+            AssemblerError::UnexpectedToken { line, column, .. }
+            | AssemblerError::InvalidToken { line, column, .. }
+            | AssemblerError::DuplicateLabel { line, column, .. }
+            | AssemblerError::UndefinedLabel { line, column, .. }
+            | AssemblerError::ValueOutOfRange { line, column, .. } => (*line, *column),
+        }
+    }
+}
+
+/// *Token* do código com a sua posição, gerado por [`tokenize`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpannedToken {
+    pub line: usize,
+    pub column: usize,
+    /// Tamanho do *token*, em caracteres.
+    pub length: usize,
+    pub token: Result<Token, TokenError>,
+}
+
+/// Separa o código `src` em *tokens*, sem montá-lo. Os comentários não geram *tokens*, e os
+/// *tokens* inválidos são mantidos com o seu erro, de modo que o resultado cubra todo o código.
+///
+/// # Exemplo
+///
+/// ```
+/// use assembler::{tokenize, TokenKind};
+///
+/// let kinds: Vec<_> = tokenize("main: INC R0 ; fim")
+///     .into_iter()
+///     .map(|t| (t.column, t.length, t.token.unwrap().kind()))
+///     .collect();
+///
+/// assert_eq!(
+///     vec![
+///         (1, 4, TokenKind::Identifier),
+///         (5, 1, TokenKind::Punctuation),
+///         (7, 3, TokenKind::Instruction),
+///         (11, 2, TokenKind::Register),
+///     ],
+///     kinds
+/// );
+/// ```
+pub fn tokenize(src: &str) -> Vec<SpannedToken> {
+    let mut lex = Lexer::new(src);
+    let mut tokens = Vec::new();
+
+    while let Some(token) = lex.next_token() {
+        let (line, column) = lex.token_position();
+        tokens.push(SpannedToken {
+            line,
+            column,
+            length: lex.column() - column,
+            token,
+        });
+    }
+
+    tokens
+}
+
+/// Operando que ocupa uma palavra inteira da memória: um valor conhecido ou o endereço de um
+/// rótulo, resolvido somente na ligação.
+enum Operand {
+    Value(MemoryCell),
+    Label(String),
+}
+
+/// Inicialização feita pela diretiva `static label + #offset, #valor`.
+struct StaticInit {
+    line: usize,
+    column: usize,
+    label: String,
+    offset: MemoryCell,
+    value: Operand,
 }
 
 pub struct Assembler<'a> {
-    current_address: usize,
-    labels: HashMap<String, usize>,
     lex: Lexer<'a>,
-    stream_in: String,
-    stream_out: Vec<usize>,
+    module: ObjectModule,
+    labels: HashMap<String, (SectionKind, usize)>,
+    pending_labels: Vec<(String, usize, usize)>,
+    statics: Vec<StaticInit>,
+}
+
+/// Monta o código `src`, gerando o módulo relocável `name`.
+///
+/// # Exemplo
+///
+/// ```
+/// let module = assembler::assemble("main", "main: LOADN R0, #1\nJMP main\n").unwrap();
+/// assert_eq!(vec![0b1110000000000000, 1, 0b0000100000000000, 0], module.code.words);
+/// assert_eq!("main", module.relocations[0].symbol);
+/// ```
+pub fn assemble(name: &str, src: &str) -> Result<ObjectModule, AssemblerError> {
+    Assembler::new(name, src).assemble()
+}
+
+/// Retorna a palavra da instrução com todos os campos variáveis da máscara zerados.
+fn base_word(instruction: Instruction) -> MemoryCell {
+    instruction
+        .mask()
+        .chars()
+        .fold(0, |acc, c| (acc << 1) | (c == '1') as MemoryCell)
 }
 
-impl Assembler<'_> {
+/// Retorna o índice do registrador representado por `token`.
+fn register(token: &Token) -> Option<MemoryCell> {
+    match token {
+        Token::R0 => Some(0),
+        Token::R1 => Some(1),
+        Token::R2 => Some(2),
+        Token::R3 => Some(3),
+        Token::R4 => Some(4),
+        Token::R5 => Some(5),
+        Token::R6 => Some(6),
+        Token::R7 => Some(7),
+        _ => None,
+    }
+}
+
+impl<'a> Assembler<'a> {
+    pub fn new(name: &str, src: &'a str) -> Self {
+        Self {
+            lex: Lexer::new(src),
+            module: ObjectModule::new(name),
+            labels: HashMap::new(),
+            pending_labels: Vec::new(),
+            statics: Vec::new(),
+        }
+    }
+
+    /// Monta todo o código, consumindo o montador.
+    ///
+    /// # Erros
+    ///
+    /// Retorna o primeiro [`AssemblerError`] encontrado no código.
+    pub fn assemble(mut self) -> Result<ObjectModule, AssemblerError> {
+        while let Some(token) = self.next()? {
+            self.statement(token)?;
+        }
+
+        self.define_pending_labels(SectionKind::Code);
+        self.write_statics()?;
+
+        let mut imports: Vec<String> = self
+            .module
+            .relocations
+            .iter()
+            .filter(|r| !self.labels.contains_key(&r.symbol))
+            .map(|r| r.symbol.clone())
+            .collect();
+        imports.sort();
+        imports.dedup();
+        self.module.imports = imports;
+
+        let mut exports: Vec<Symbol> = self
+            .labels
+            .into_iter()
+            .map(|(name, (section, offset))| Symbol {
+                name,
+                section,
+                offset,
+            })
+            .collect();
+        exports.sort_by(|a, b| a.name.cmp(&b.name));
+        self.module.exports = exports;
+
+        Ok(self.module)
+    }
+
+    fn position(&self) -> (usize, usize) {
+        self.lex.token_position()
+    }
+
+    fn next(&mut self) -> Result<Option<Token>, AssemblerError> {
+        match self.lex.next_token() {
+            Some(Ok(t)) => Ok(Some(t)),
+            Some(Err(error)) => {
+                let (line, column) = self.position();
+                Err(AssemblerError::InvalidToken {
+                    line,
+                    column,
+                    error,
+                })
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn unexpected<T>(&self, expected: &str, received: Option<Token>) -> Result<T, AssemblerError> {
+        let (line, column) = self.position();
+        Err(AssemblerError::UnexpectedToken {
+            line,
+            column,
+            expected: expected.to_string(),
+            received,
+        })
+    }
+
+    fn check_range(&self, value: usize, max: usize) -> Result<MemoryCell, AssemblerError> {
+        match value <= max {
+            true => Ok(value),
+            false => {
+                let (line, column) = self.position();
+                Err(AssemblerError::ValueOutOfRange {
+                    line,
+                    column,
+                    value,
+                    max,
+                })
+            }
+        }
+    }
+
+    fn expect(&mut self, expected: Token) -> Result<(), AssemblerError> {
+        match self.next()? {
+            Some(t) if t == expected => Ok(()),
+            t => self.unexpected(&format!("\"{}\"", expected), t),
+        }
+    }
+
+    fn expect_register(&mut self) -> Result<MemoryCell, AssemblerError> {
+        match self.next()? {
+            Some(t) if register(&t).is_some() => Ok(register(&t).unwrap()),
+            t => self.unexpected("um registrador (R0-R7)", t),
+        }
+    }
+
+    /// Lê um endereço: um número ou um rótulo.
+    fn expect_address(&mut self) -> Result<Operand, AssemblerError> {
+        match self.next()? {
+            Some(Token::Number(n)) => {
+                Ok(Operand::Value(self.check_range(n, isa::MAX_VALUE_MEMORY)?))
+            }
+            Some(Token::Identifier(id)) => Ok(Operand::Label(id)),
+            t => self.unexpected("um endereço ou rótulo", t),
+        }
+    }
+
+    /// Lê um valor imediato: `#` seguido de um número, um *char* ou um rótulo.
+    fn expect_immediate(&mut self) -> Result<Operand, AssemblerError> {
+        self.expect(Token::Pound)?;
+        match self.next()? {
+            Some(Token::Number(n)) => {
+                Ok(Operand::Value(self.check_range(n, isa::MAX_VALUE_MEMORY)?))
+            }
+            Some(Token::LiteralChar(c)) => Ok(Operand::Value(
+                self.check_range(c as usize, isa::MAX_VALUE_MEMORY)?,
+            )),
+            Some(Token::Identifier(id)) => Ok(Operand::Label(id)),
+            t => self.unexpected("um número, char ou rótulo", t),
+        }
+    }
+
+    /// Lê a quantidade de bits de um deslocamento ou rotação, com `#` opcional.
+    fn expect_shift(&mut self) -> Result<MemoryCell, AssemblerError> {
+        let token = match self.next()? {
+            Some(Token::Pound) => self.next()?,
+            t => t,
+        };
+        match token {
+            Some(Token::Number(n)) => self.check_range(n, 0b1111),
+            t => self.unexpected("a quantidade de bits (0-15)", t),
+        }
+    }
+
+    fn push(&mut self, kind: SectionKind, word: MemoryCell) {
+        self.module.section_mut(kind).words.push(word);
+    }
+
+    fn push_operand(&mut self, kind: SectionKind, operand: Operand) {
+        match operand {
+            Operand::Value(v) => self.push(kind, v),
+            Operand::Label(symbol) => {
+                let offset = self.module.section(kind).words.len();
+                self.module.relocations.push(Relocation {
+                    section: kind,
+                    offset,
+                    symbol,
+                    addend: 0,
+                });
+                self.push(kind, 0);
+            }
+        }
+    }
+
+    fn define_pending_labels(&mut self, kind: SectionKind) {
+        let offset = self.module.section(kind).words.len();
+        for (label, _, _) in self.pending_labels.drain(..) {
+            self.labels.insert(label, (kind, offset));
+        }
+    }
+
+    fn statement(&mut self, token: Token) -> Result<(), AssemblerError> {
+        match token {
+            Token::Identifier(label) => {
+                let (line, column) = self.position();
+                if self.labels.contains_key(&label)
+                    || self.pending_labels.iter().any(|(l, _, _)| *l == label)
+                {
+                    return Err(AssemblerError::DuplicateLabel {
+                        line,
+                        column,
+                        label,
+                    });
+                }
+                self.expect(Token::Colon)?;
+                self.pending_labels.push((label, line, column));
+            }
+
+            Token::Instruction(i) => {
+                self.define_pending_labels(SectionKind::Code);
+                let (line, _) = self.position();
+                self.module.lines.push(SourceLine {
+                    offset: self.module.code.words.len(),
+                    line,
+                });
+                self.write_instruction(i)?;
+            }
+
+            Token::Var => {
+                self.define_pending_labels(SectionKind::Data);
+                self.expect(Token::Pound)?;
+                match self.next()? {
+                    Some(Token::Number(n)) => {
+                        let n = self.check_range(n, *SectionKind::Data.region().end())?;
+                        for _ in 0..n {
+                            self.push(SectionKind::Data, 0);
+                        }
+                    }
+                    t => return self.unexpected("a quantidade de palavras", t),
+                }
+            }
+
+            Token::String => {
+                self.define_pending_labels(SectionKind::Data);
+                match self.next()? {
+                    Some(Token::LiteralStr(s)) => {
+                        for c in s.chars() {
+                            let c = self.check_range(c as usize, isa::MAX_VALUE_MEMORY)?;
+                            self.push(SectionKind::Data, c);
+                        }
+                        self.push(SectionKind::Data, 0);
+                    }
+                    t => return self.unexpected("uma string", t),
+                }
+            }
+
+            Token::Static => {
+                let (line, column) = self.position();
+                let label = match self.next()? {
+                    Some(Token::Identifier(id)) => id,
+                    t => return self.unexpected("um rótulo", t),
+                };
+                self.expect(Token::Plus)?;
+                let offset = match self.expect_immediate()? {
+                    Operand::Value(v) => v,
+                    Operand::Label(_) => {
+                        let (line, column) = self.position();
+                        return Err(AssemblerError::UnexpectedToken {
+                            line,
+                            column,
+                            expected: "um deslocamento numérico".to_string(),
+                            received: None,
+                        });
+                    }
+                };
+                self.expect(Token::Comma)?;
+                let value = self.expect_immediate()?;
+                self.statics.push(StaticInit {
+                    line,
+                    column,
+                    label,
+                    offset,
+                    value,
+                });
+            }
+
+            t => return self.unexpected("uma instrução, diretiva ou rótulo", Some(t)),
+        }
+
+        Ok(())
+    }
+
+    /// Escreve os valores das diretivas `static` nas seções, já com todos os rótulos do
+    /// módulo conhecidos.
+    fn write_statics(&mut self) -> Result<(), AssemblerError> {
+        for s in std::mem::take(&mut self.statics) {
+            let (kind, base) = match self.labels.get(&s.label) {
+                Some(&l) => l,
+                None => {
+                    return Err(AssemblerError::UndefinedLabel {
+                        line: s.line,
+                        column: s.column,
+                        label: s.label,
+                    })
+                }
+            };
+
+            let offset = base + s.offset;
+            let len = self.module.section(kind).words.len();
+            if offset >= len {
+                return Err(AssemblerError::ValueOutOfRange {
+                    line: s.line,
+                    column: s.column,
+                    value: s.offset,
+                    max: (len - base).saturating_sub(1),
+                });
+            }
+
+            self.module
+                .relocations
+                .retain(|r| r.section != kind || r.offset != offset);
+            match s.value {
+                Operand::Value(v) => self.module.section_mut(kind).words[offset] = v,
+                Operand::Label(symbol) => {
+                    self.module.section_mut(kind).words[offset] = 0;
+                    self.module.relocations.push(Relocation {
+                        section: kind,
+                        offset,
+                        symbol,
+                        addend: 0,
+                    });
+                }
+            }
+        }
+
+        Ok(())
+    }
+
     fn write_instruction(&mut self, instruction: Instruction) -> Result<(), AssemblerError> {
-        match instruction {
-            Instruction::LOAD => {
-                // let register = self.expected_token::<Keyword>()?;
-                todo!()
-            }
-            Instruction::LOADN => todo!(),
-            Instruction::LOADI => todo!(),
-            Instruction::STORE => todo!(),
-            Instruction::STOREN => todo!(),
-            Instruction::STOREI => todo!(),
-            Instruction::MOV => todo!(),
-            Instruction::INPUT => todo!(),
-            Instruction::OUTPUT => todo!(),
-            Instruction::OUTCHAR => todo!(),
-            Instruction::INCHAR => todo!(),
-            Instruction::SOUND => todo!(),
-            Instruction::ADD => todo!(),
-            Instruction::ADDC => todo!(),
-            Instruction::SUB => todo!(),
-            Instruction::SUBC => todo!(),
-            Instruction::MUL => todo!(),
-            Instruction::DIV => todo!(),
-            Instruction::INC => todo!(),
-            Instruction::DEC => todo!(),
-            Instruction::MOD => todo!(),
-            Instruction::AND => todo!(),
-            Instruction::OR => todo!(),
-            Instruction::XOR => todo!(),
-            Instruction::NOT => todo!(),
-            Instruction::SHIFTL0 => todo!(),
-            Instruction::SHIFTL1 => todo!(),
-            Instruction::SHIFTR0 => todo!(),
-            Instruction::SHIFTR1 => todo!(),
-            Instruction::ROTL => todo!(),
-            Instruction::ROTR => todo!(),
-            Instruction::CMP => todo!(),
-            Instruction::JMP => todo!(),
-            Instruction::JEQ => todo!(),
-            Instruction::JNE => todo!(),
-            Instruction::JZ => todo!(),
-            Instruction::JNZ => todo!(),
-            Instruction::JC => todo!(),
-            Instruction::JNC => todo!(),
-            Instruction::JGR => todo!(),
-            Instruction::JLE => todo!(),
-            Instruction::JEG => todo!(),
-            Instruction::JEL => todo!(),
-            Instruction::JOV => todo!(),
-            Instruction::JNO => todo!(),
-            Instruction::JDZ => todo!(),
-            Instruction::JN => todo!(),
-            Instruction::CALL => todo!(),
-            Instruction::CEQ => todo!(),
-            Instruction::CNE => todo!(),
-            Instruction::CZ => todo!(),
-            Instruction::CNZ => todo!(),
-            Instruction::CC => todo!(),
-            Instruction::CNC => todo!(),
-            Instruction::CGR => todo!(),
-            Instruction::CLE => todo!(),
-            Instruction::CEG => todo!(),
-            Instruction::CEL => todo!(),
-            Instruction::COV => todo!(),
-            Instruction::CNO => todo!(),
-            Instruction::CDZ => todo!(),
-            Instruction::CN => todo!(),
-            Instruction::RTS => todo!(),
-            Instruction::RTI => todo!(),
-            Instruction::PUSH => todo!(),
-            Instruction::POP => todo!(),
-            Instruction::NOP => todo!(),
-            Instruction::HALT => todo!(),
-            Instruction::CLEARC => todo!(),
-            Instruction::SETC => todo!(),
-            Instruction::BREAKP => todo!(),
+        const CODE: SectionKind = SectionKind::Code;
+        let mut word = base_word(instruction);
+        let mut operands = Vec::new();
+
+        for (i, kind) in instruction.operands().iter().enumerate() {
+            if i > 0 {
+                self.expect(Token::Comma)?;
+            }
+
+            match kind {
+                OperandKind::Rx | OperandKind::Ry | OperandKind::Rz => {
+                    word |= self.expect_register()? << kind.bits().map_or(0, |r| *r.start())
+                }
+                OperandKind::Shift => word |= self.expect_shift()?,
+                OperandKind::Immediate => operands.push(self.expect_immediate()?),
+                OperandKind::Address => operands.push(self.expect_address()?),
+                OperandKind::MovRegisters => word |= self.expect_mov_registers()?,
+                OperandKind::StackRegister => word |= self.expect_stack_register()?,
+            }
+        }
+
+        self.push(CODE, word);
+        for operand in operands {
+            self.push_operand(CODE, operand);
+        }
+
+        Ok(())
+    }
+
+    /// Lê os operandos de [`Instruction::MOV`], retornando os bits que os representam.
+    fn expect_mov_registers(&mut self) -> Result<MemoryCell, AssemblerError> {
+        match self.next()? {
+            Some(Token::SP) => {
+                self.expect(Token::Comma)?;
+                Ok((self.expect_register()? << 7) | 0b11)
+            }
+            Some(t) => {
+                let rx = match register(&t) {
+                    Some(r) => r << 7,
+                    None => return self.unexpected("um registrador ou SP", Some(t)),
+                };
+                self.expect(Token::Comma)?;
+                match self.next()? {
+                    Some(Token::SP) => Ok(rx | 0b01),
+                    Some(t) => match register(&t) {
+                        Some(r) => Ok(rx | (r << 4)),
+                        None => self.unexpected("um registrador ou SP", Some(t)),
+                    },
+                    None => self.unexpected("um registrador ou SP", None),
+                }
+            }
+            None => self.unexpected("um registrador ou SP", None),
+        }
+    }
+
+    /// Lê o operando de [`Instruction::PUSH`] e [`Instruction::POP`], retornando os bits que o
+    /// representam.
+    fn expect_stack_register(&mut self) -> Result<MemoryCell, AssemblerError> {
+        match self.next()? {
+            Some(Token::FR) => Ok(1 << 6),
+            Some(t) => match register(&t) {
+                Some(r) => Ok(r << 7),
+                None => self.unexpected("um registrador ou FR", Some(t)),
+            },
+            None => self.unexpected("um registrador ou FR", None),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::linker::Linker;
+
+    #[test]
+    fn test_tokenize() {
+        let tokens = tokenize("; início\n  STORE 0x10, R1\nx: string \"a;b\" ?\n");
+
+        assert_eq!(
+            vec![
+                (2, 3, 5),
+                (2, 9, 4),
+                (2, 13, 1),
+                (2, 15, 2),
+                (3, 1, 1),
+                (3, 2, 1),
+                (3, 4, 6),
+                (3, 11, 5),
+                (3, 17, 1),
+            ],
+            tokens
+                .iter()
+                .map(|t| (t.line, t.column, t.length))
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(Ok(Token::LiteralStr("a;b".to_string())), tokens[7].token);
+        assert_eq!(Err(TokenError::Invalid("?".to_string())), tokens[8].token);
+    }
+
+    #[test]
+    fn test_error_position() {
+        let e = assemble("test", "main:\n  JMP\n").unwrap_err();
+        assert_eq!((3, 1), e.position());
+    }
+
+    #[test]
+    fn test_assemble_instructions() {
+        let module = assemble(
+            "test",
+            "LOAD R1, 0x10\nSTOREI R2, R3\nMOV SP, R4\nMOV R5, SP\nADDC R1, R2, R3\n\
+             SHIFTL1 R7, #9\nPUSH FR\nPOP R6\nRTI\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            vec![
+                0b1100000010000000,
+                0x10,
+                0b1111010100110000,
+                0b1100111000000011,
+                0b1100111010000001,
+                0b1000000010100111,
+                0b0100001110011001,
+                0b0001010001000000,
+                0b0001101100000000,
+                0b0001000000000001,
+            ],
+            module.code.words
+        );
+        assert!(module.relocations.is_empty());
+    }
+
+    #[test]
+    fn test_assemble_data() {
+        let module = assemble(
+            "test",
+            "; variáveis\nmsg: string \"Oi\"\nx : var #2\nstatic x + #1, #'A'\nLOADN R0, #x\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            vec!['O' as usize, 'i' as usize, 0, 0, 'A' as usize],
+            module.data.words
+        );
+        assert_eq!(
+            Some(&Symbol {
+                name: "x".to_string(),
+                section: SectionKind::Data,
+                offset: 3
+            }),
+            module.export("x")
+        );
+        assert_eq!(1, module.relocations[0].offset);
+        assert!(module.imports.is_empty());
+    }
+
+    #[test]
+    fn test_assemble_imports() {
+        let module = assemble("test", "CALL print\nJMP fim\nfim: HALT\n").unwrap();
+        assert_eq!(vec!["print".to_string()], module.imports);
+        assert_eq!(2, module.relocations.len());
+        assert_eq!(
+            vec![
+                SourceLine { offset: 0, line: 1 },
+                SourceLine { offset: 2, line: 2 },
+                SourceLine { offset: 4, line: 3 },
+            ],
+            module.lines
+        );
+    }
+
+    #[test]
+    fn test_assemble_errors() {
+        assert_eq!(
+            Err(AssemblerError::UnexpectedToken {
+                line: 2,
+                column: 11,
+                expected: "um registrador (R0-R7)".to_string(),
+                received: Some(Token::SP)
+            }),
+            assemble("test", "NOP\nLOADI R0, SP\n")
+        );
+
+        assert!(matches!(
+            assemble("test", "a: NOP\na: NOP\n"),
+            Err(AssemblerError::DuplicateLabel { line: 2, .. })
+        ));
+
+        assert!(matches!(
+            assemble("test", "ROTL R0, 16\n"),
+            Err(AssemblerError::ValueOutOfRange { value: 16, .. })
+        ));
+    }
+
+    /// Gerador *xorshift*, suficiente para sortear programas de teste reproduzíveis.
+    struct Rng(u64);
+
+    impl Rng {
+        fn next(&mut self) -> u64 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            self.0
+        }
+
+        fn below(&mut self, n: usize) -> usize {
+            (self.next() % n as u64) as usize
+        }
+
+        fn word(&mut self) -> MemoryCell {
+            self.next() as MemoryCell & isa::MAX_VALUE_MEMORY
+        }
+    }
+
+    /// Sorteia uma palavra válida para `instruction`, preenchendo os bits `-` da máscara.
+    fn random_word(rng: &mut Rng, instruction: Instruction) -> MemoryCell {
+        let noise = rng.word();
+        instruction
+            .mask()
+            .chars()
+            .enumerate()
+            .fold(0, |word, (i, c)| {
+                let bit = match c {
+                    '-' => (noise >> (isa::BITS_ADDRESS - 1 - i)) & 1,
+                    c => c.to_digit(2).unwrap() as MemoryCell,
+                };
+                (word << 1) | bit
+            })
+    }
+
+    /// Sorteia um programa com `repeat` ocorrências de cada instrução, em ordem aleatória, com
+    /// rótulos e saltos para rótulos já definidos.
+    fn random_program(rng: &mut Rng, repeat: usize) -> String {
+        let mut sequence: Vec<Instruction> = Instruction::ALL
+            .iter()
+            .flat_map(|&i| std::iter::repeat_n(i, repeat))
+            .collect();
+        for i in (1..sequence.len()).rev() {
+            sequence.swap(i, rng.below(i + 1));
+        }
+
+        let mut labels = 0;
+        let mut src = String::new();
+        for instruction in sequence {
+            if rng.below(4) == 0 {
+                src.push_str(&format!("l{}: ", labels));
+                labels += 1;
+            }
+
+            let words = [random_word(rng, instruction), rng.word(), rng.word()];
+            let decoded = isa::disasm::decode(&words).unwrap();
+            assert_eq!(instruction, decoded.instruction);
+
+            let only_address = decoded.registers.is_empty() && decoded.immediate.is_none();
+            match decoded.address {
+                Some(_) if only_address && labels > 0 && rng.below(2) == 0 => {
+                    src.push_str(&format!("{} l{}\n", instruction, rng.below(labels)))
+                }
+                _ => src.push_str(&format!("{}\n", decoded)),
+            }
+        }
+
+        src
+    }
+
+    fn link(src: &str) -> (Vec<MemoryCell>, usize) {
+        let module = assemble("round_trip", src).unwrap();
+        let len = module.code.words.len();
+        (Linker::new().add(module).link().unwrap().memory, len)
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let mut rng = Rng(0x1c3c_2024_0bad_cafe);
+
+        for _ in 0..8 {
+            let src = random_program(&mut rng, 4);
+            let (image, len) = link(&src);
+
+            let text = isa::disasm::disassemble(&image[..len])
+                .map(|(addr, d)| match d {
+                    Ok(d) => format!("{}\n", d),
+                    Err(e) => panic!("{}: {}\n{}", addr, e, src),
+                })
+                .collect::<String>();
+
+            let (reassembled, reassembled_len) = link(&text);
+            assert_eq!(len, reassembled_len, "{}", src);
+            assert_eq!(image, reassembled, "{}", src);
         }
     }
 }
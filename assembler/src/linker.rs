@@ -0,0 +1,391 @@
+//! Ligação de vários [`ObjectModule`] em uma única imagem de memória.
+//!
+//! As seções de código são colocadas em [`isa::memory::layout::ADDR_PROG_AND_VAR`] e as de
+//! dados em [`isa::memory::layout::ADDR_STATIC_DATA`], na ordem em que os módulos foram
+//! adicionados. Seções com `origin` definido são colocadas primeiro, no endereço pedido, e as
+//! demais ocupam o primeiro espaço livre da região.
+
+use std::collections::HashMap;
+use std::ops::RangeInclusive;
+
+use isa::MemoryCell;
+use thiserror::Error;
+
+use crate::object::{ObjectModule, SectionKind};
+
+/// Tamanho da imagem gerada pelo *linker*, cobrindo todo o mapa de memória.
+pub const IMAGE_SIZE: usize = *isa::memory::layout::ADDR_INTERRUPTIONS.end() + 1;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum LinkError {
+    #[error("O símbolo \"{name}\" foi definido nos módulos \"{first}\" e \"{second}\".")]
+    DuplicateSymbol {
+        name: String,
+        first: String,
+        second: String,
+    },
+
+    #[error("O símbolo \"{name}\", usado no módulo \"{module}\", não foi definido.")]
+    UndefinedSymbol { name: String, module: String },
+
+    #[error(
+        "A seção {section} do módulo \"{module}\" ({start:#06x}..={end:#06x}) não cabe na região \
+        {region:?}."
+    )]
+    OutOfRegion {
+        module: String,
+        section: SectionKind,
+        start: MemoryCell,
+        end: MemoryCell,
+        region: RangeInclusive<MemoryCell>,
+    },
+
+    #[error(
+        "A seção {first_section} do módulo \"{first}\" e a seção {second_section} do módulo \
+        \"{second}\" se sobrepõem em {addr:#06x}."
+    )]
+    Overlap {
+        first: String,
+        first_section: SectionKind,
+        second: String,
+        second_section: SectionKind,
+        addr: MemoryCell,
+    },
+
+    #[error("A relocação em {offset} da seção {section} do módulo \"{module}\" é inválida.")]
+    InvalidRelocation {
+        module: String,
+        section: SectionKind,
+        offset: usize,
+    },
+}
+
+/// Posição final de uma seção de um módulo na imagem.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Placement {
+    pub module: String,
+    pub section: SectionKind,
+    pub range: RangeInclusive<MemoryCell>,
+}
+
+/// Linha do código-fonte de um módulo.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourceLocation {
+    pub module: String,
+    pub line: usize,
+}
+
+/// Resultado da ligação: a memória completa do processador, o endereço final de cada
+/// símbolo e a linha do código-fonte de cada instrução.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Image {
+    pub memory: Vec<MemoryCell>,
+    pub symbols: HashMap<String, MemoryCell>,
+    pub placements: Vec<Placement>,
+    pub lines: HashMap<MemoryCell, SourceLocation>,
+}
+
+impl Image {
+    /// Retorna o símbolo definido no endereço `addr`, caso exista.
+    pub fn symbol_at(&self, addr: MemoryCell) -> Option<&str> {
+        self.symbols
+            .iter()
+            .filter(|(_, &a)| a == addr)
+            .map(|(s, _)| s.as_str())
+            .min()
+    }
+}
+
+#[derive(Default)]
+pub struct Linker {
+    modules: Vec<ObjectModule>,
+}
+
+impl Linker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adiciona um módulo à ligação. O primeiro módulo de código adicionado sem `origin` é
+    /// colocado no início da área de programa, onde o processador inicia a execução.
+    pub fn add(&mut self, module: ObjectModule) -> &mut Self {
+        self.modules.push(module);
+        self
+    }
+
+    pub fn modules(&self) -> &[ObjectModule] {
+        &self.modules
+    }
+
+    /// Liga os módulos adicionados em uma única [`Image`].
+    ///
+    /// # Erros
+    ///
+    /// - [`LinkError::OutOfRegion`] caso alguma seção não caiba na sua região.
+    /// - [`LinkError::Overlap`] caso duas seções ocupem o mesmo endereço.
+    /// - [`LinkError::DuplicateSymbol`] caso um símbolo seja exportado por dois módulos.
+    /// - [`LinkError::UndefinedSymbol`] caso um símbolo usado não seja exportado por nenhum
+    ///   módulo.
+    /// - [`LinkError::InvalidRelocation`] caso uma relocação aponte para fora da sua seção.
+    pub fn link(&self) -> Result<Image, LinkError> {
+        let placements = self.place()?;
+
+        let base = |module: usize, kind: SectionKind| -> MemoryCell {
+            *placements
+                .iter()
+                .find(|(m, p)| *m == module && p.section == kind)
+                .map(|(_, p)| p.range.start())
+                .unwrap_or(kind.region().start())
+        };
+
+        let mut symbols: HashMap<String, (MemoryCell, usize)> = HashMap::new();
+        for (i, m) in self.modules.iter().enumerate() {
+            for s in m.exports.iter() {
+                if let Some((_, first)) = symbols.get(&s.name) {
+                    return Err(LinkError::DuplicateSymbol {
+                        name: s.name.clone(),
+                        first: self.modules[*first].name.clone(),
+                        second: m.name.clone(),
+                    });
+                }
+                symbols.insert(s.name.clone(), (base(i, s.section) + s.offset, i));
+            }
+        }
+
+        let mut memory = vec![0; IMAGE_SIZE];
+        for (i, p) in placements.iter() {
+            let words = &self.modules[*i].section(p.section).words;
+            memory[p.range.clone()].copy_from_slice(words);
+        }
+
+        for (i, m) in self.modules.iter().enumerate() {
+            for r in m.relocations.iter() {
+                let (addr, _) =
+                    symbols
+                        .get(&r.symbol)
+                        .ok_or_else(|| LinkError::UndefinedSymbol {
+                            name: r.symbol.clone(),
+                            module: m.name.clone(),
+                        })?;
+
+                if r.offset >= m.section(r.section).words.len() {
+                    return Err(LinkError::InvalidRelocation {
+                        module: m.name.clone(),
+                        section: r.section,
+                        offset: r.offset,
+                    });
+                }
+
+                memory[base(i, r.section) + r.offset] = (addr + r.addend) & isa::MAX_VALUE_MEMORY;
+            }
+        }
+
+        let lines = self
+            .modules
+            .iter()
+            .enumerate()
+            .flat_map(|(i, m)| {
+                let base = base(i, SectionKind::Code);
+                m.lines.iter().map(move |l| {
+                    (
+                        base + l.offset,
+                        SourceLocation {
+                            module: m.name.clone(),
+                            line: l.line,
+                        },
+                    )
+                })
+            })
+            .collect();
+
+        Ok(Image {
+            memory,
+            symbols: symbols.into_iter().map(|(s, (a, _))| (s, a)).collect(),
+            placements: placements.into_iter().map(|(_, p)| p).collect(),
+            lines,
+        })
+    }
+
+    /// Decide o endereço de cada seção não vazia, verificando regiões e sobreposições.
+    fn place(&self) -> Result<Vec<(usize, Placement)>, LinkError> {
+        let mut placed: Vec<(usize, Placement)> = Vec::new();
+
+        let sections = || {
+            self.modules.iter().enumerate().flat_map(|(i, m)| {
+                [SectionKind::Code, SectionKind::Data]
+                    .into_iter()
+                    .map(move |k| (i, m, k))
+                    .filter(|(_, m, k)| !m.section(*k).words.is_empty())
+            })
+        };
+
+        // Seções com endereço fixo.
+        for (i, m, kind) in sections() {
+            if let Some(origin) = m.section(kind).origin {
+                let range = origin..=origin + m.section(kind).words.len() - 1;
+                self.check_region(m, kind, &range)?;
+                Self::check_overlap(&self.modules, &placed, i, kind, &range)?;
+                placed.push((
+                    i,
+                    Placement {
+                        module: m.name.clone(),
+                        section: kind,
+                        range,
+                    },
+                ));
+            }
+        }
+
+        // Seções relocáveis, no primeiro espaço livre da região.
+        for (i, m, kind) in sections() {
+            if m.section(kind).origin.is_none() {
+                let len = m.section(kind).words.len();
+                let mut start = *kind.region().start();
+
+                while let Some((_, p)) = placed.iter().find(|(_, p)| {
+                    p.section == kind && *p.range.start() < start + len && start <= *p.range.end()
+                }) {
+                    start = p.range.end() + 1;
+                }
+
+                let range = start..=start + len - 1;
+                self.check_region(m, kind, &range)?;
+                placed.push((
+                    i,
+                    Placement {
+                        module: m.name.clone(),
+                        section: kind,
+                        range,
+                    },
+                ));
+            }
+        }
+
+        Ok(placed)
+    }
+
+    fn check_region(
+        &self,
+        module: &ObjectModule,
+        kind: SectionKind,
+        range: &RangeInclusive<MemoryCell>,
+    ) -> Result<(), LinkError> {
+        let region = kind.region();
+        match region.contains(range.start()) && region.contains(range.end()) {
+            true => Ok(()),
+            false => Err(LinkError::OutOfRegion {
+                module: module.name.clone(),
+                section: kind,
+                start: *range.start(),
+                end: *range.end(),
+                region,
+            }),
+        }
+    }
+
+    fn check_overlap(
+        modules: &[ObjectModule],
+        placed: &[(usize, Placement)],
+        module: usize,
+        kind: SectionKind,
+        range: &RangeInclusive<MemoryCell>,
+    ) -> Result<(), LinkError> {
+        match placed
+            .iter()
+            .find(|(_, p)| *p.range.start() <= *range.end() && *range.start() <= *p.range.end())
+        {
+            Some((other, p)) => Err(LinkError::Overlap {
+                first: modules[*other].name.clone(),
+                first_section: p.section,
+                second: modules[module].name.clone(),
+                second_section: kind,
+                addr: *range.start().max(p.range.start()),
+            }),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assemble;
+
+    #[test]
+    fn test_link_modules() {
+        let main = assemble("main", "CALL print\nHALT\n").unwrap();
+        let print = assemble("print", "print: LOAD R0, msg\nRTS\nmsg: string \"A\"\n").unwrap();
+
+        let image = Linker::new().add(main).add(print).link().unwrap();
+
+        assert_eq!(3, image.symbols["print"]);
+        assert_eq!(
+            *isa::memory::layout::ADDR_STATIC_DATA.start(),
+            image.symbols["msg"]
+        );
+        assert_eq!(3, image.memory[1]);
+        assert_eq!(image.symbols["msg"], image.memory[4]);
+        assert_eq!('A' as MemoryCell, image.memory[image.symbols["msg"]]);
+        assert_eq!(
+            Some(&SourceLocation {
+                module: "print".to_string(),
+                line: 2
+            }),
+            image.lines.get(&5)
+        );
+    }
+
+    #[test]
+    fn test_link_undefined_symbol() {
+        let main = assemble("main", "JMP nowhere\n").unwrap();
+        assert_eq!(
+            Err(LinkError::UndefinedSymbol {
+                name: "nowhere".to_string(),
+                module: "main".to_string()
+            }),
+            Linker::new().add(main).link()
+        );
+    }
+
+    #[test]
+    fn test_link_duplicate_symbol() {
+        let a = assemble("a", "start: NOP\n").unwrap();
+        let b = assemble("b", "start: NOP\n").unwrap();
+        assert!(matches!(
+            Linker::new().add(a).add(b).link(),
+            Err(LinkError::DuplicateSymbol { .. })
+        ));
+    }
+
+    #[test]
+    fn test_link_overlap() {
+        let a = assemble("a", "NOP\nNOP\nNOP\n").unwrap();
+        let mut b = assemble("b", "NOP\n").unwrap();
+        b.set_origin(SectionKind::Code, 2);
+        let mut c = assemble("c", "NOP\nNOP\n").unwrap();
+        c.set_origin(SectionKind::Code, 1);
+
+        // Seções fixas não podem se sobrepor.
+        assert!(matches!(
+            Linker::new().add(b.clone()).add(c).link(),
+            Err(LinkError::Overlap { addr: 2, .. })
+        ));
+
+        // Seções relocáveis desviam das fixas.
+        let image = Linker::new().add(a).add(b).link().unwrap();
+        assert_eq!(3..=5, image.placements[1].range);
+    }
+
+    #[test]
+    fn test_link_out_of_region() {
+        let mut a = assemble("a", "NOP\nNOP\n").unwrap();
+        a.set_origin(
+            SectionKind::Code,
+            *isa::memory::layout::ADDR_PROG_AND_VAR.end(),
+        );
+        assert!(matches!(
+            Linker::new().add(a).link(),
+            Err(LinkError::OutOfRegion { .. })
+        ));
+    }
+}
@@ -0,0 +1,328 @@
+//! Formato de objeto relocável gerado pelo montador.
+//!
+//! Um [`ObjectModule`] guarda o código e os dados de um único arquivo de montagem com os
+//! endereços ainda relativos ao início de cada seção. Os símbolos definidos no módulo são
+//! exportados, as referências a símbolos não definidos são importadas e cada palavra que
+//! guarda o endereço de um símbolo recebe uma [`Relocation`], resolvida pelo
+//! [`crate::linker::Linker`].
+
+use std::{
+    fmt::Display,
+    io::{BufRead, Write},
+    ops::RangeInclusive,
+    str::FromStr,
+};
+
+use isa::MemoryCell;
+use thiserror::Error;
+
+/// Cabeçalho presente na primeira linha de todo arquivo de objeto.
+pub const OBJECT_MAGIC: &str = "ICMC-OBJ";
+
+/// Versão atual do formato de objeto. Arquivos da versão 1, sem os registros `line`, continuam
+/// sendo lidos.
+pub const OBJECT_VERSION: usize = 2;
+
+#[derive(Error, Debug)]
+pub enum ObjectError {
+    #[error("Falha ao acessar o arquivo de objeto: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("O arquivo não é um objeto do Processador ICMC.")]
+    InvalidHeader,
+
+    #[error("Versão {0} do formato de objeto não suportada.")]
+    UnsupportedVersion(usize),
+
+    #[error("Linha {line} do objeto mal formatada: {content}")]
+    BadFormat { line: usize, content: String },
+}
+
+/// Seções presentes em um [`ObjectModule`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SectionKind {
+    /// Instruções do programa, ligadas em [`isa::memory::layout::ADDR_PROG_AND_VAR`].
+    Code,
+    /// Variáveis (`var`) e *strings*, ligadas em [`isa::memory::layout::ADDR_STATIC_DATA`].
+    Data,
+}
+
+impl SectionKind {
+    /// Retorna a região da memória em que a seção deve ser ligada.
+    pub fn region(&self) -> RangeInclusive<MemoryCell> {
+        match self {
+            SectionKind::Code => isa::memory::layout::ADDR_PROG_AND_VAR,
+            SectionKind::Data => isa::memory::layout::ADDR_STATIC_DATA,
+        }
+    }
+}
+
+impl Display for SectionKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SectionKind::Code => write!(f, "code"),
+            SectionKind::Data => write!(f, "data"),
+        }
+    }
+}
+
+impl FromStr for SectionKind {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "code" => Ok(SectionKind::Code),
+            "data" => Ok(SectionKind::Data),
+            _ => Err(()),
+        }
+    }
+}
+
+/// Conteúdo de uma seção. Se `origin` for `None`, o *linker* escolhe o endereço inicial.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Section {
+    pub origin: Option<MemoryCell>,
+    pub words: Vec<MemoryCell>,
+}
+
+/// Símbolo exportado por um módulo.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Symbol {
+    pub name: String,
+    pub section: SectionKind,
+    pub offset: usize,
+}
+
+/// Palavra de uma seção que deve receber o endereço final de `symbol` somado a `addend`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Relocation {
+    pub section: SectionKind,
+    pub offset: usize,
+    pub symbol: String,
+    pub addend: MemoryCell,
+}
+
+/// Linha do código-fonte que gerou a instrução em `offset` da seção de código.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SourceLine {
+    pub offset: usize,
+    pub line: usize,
+}
+
+/// Módulo relocável resultante da montagem de um único arquivo.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ObjectModule {
+    pub name: String,
+    pub code: Section,
+    pub data: Section,
+    pub exports: Vec<Symbol>,
+    pub imports: Vec<String>,
+    pub relocations: Vec<Relocation>,
+    pub lines: Vec<SourceLine>,
+}
+
+impl ObjectModule {
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// Retorna a seção `kind` do módulo.
+    pub fn section(&self, kind: SectionKind) -> &Section {
+        match kind {
+            SectionKind::Code => &self.code,
+            SectionKind::Data => &self.data,
+        }
+    }
+
+    /// Retorna a seção `kind` do módulo para alteração.
+    pub fn section_mut(&mut self, kind: SectionKind) -> &mut Section {
+        match kind {
+            SectionKind::Code => &mut self.code,
+            SectionKind::Data => &mut self.data,
+        }
+    }
+
+    /// Fixa o endereço inicial da seção `kind`, impedindo que o *linker* a realoque.
+    pub fn set_origin(&mut self, kind: SectionKind, addr: MemoryCell) {
+        self.section_mut(kind).origin = Some(addr);
+    }
+
+    /// Retorna o símbolo exportado com o nome `name`, caso exista.
+    pub fn export(&self, name: &str) -> Option<&Symbol> {
+        self.exports.iter().find(|s| s.name == name)
+    }
+
+    /// Escreve o módulo no formato textual de objeto.
+    pub fn write_to<W: Write>(&self, w: &mut W) -> Result<(), ObjectError> {
+        writeln!(w, "{} {}", OBJECT_MAGIC, OBJECT_VERSION)?;
+        writeln!(w, "module {}", self.name)?;
+
+        for kind in [SectionKind::Code, SectionKind::Data] {
+            let section = self.section(kind);
+            match section.origin {
+                Some(o) => write!(w, "{} {:#06x}", kind, o)?,
+                None => write!(w, "{} -", kind)?,
+            }
+            for word in section.words.iter() {
+                write!(w, " {:04x}", word)?;
+            }
+            writeln!(w)?;
+        }
+
+        for s in self.exports.iter() {
+            writeln!(w, "export {} {} {}", s.name, s.section, s.offset)?;
+        }
+
+        for s in self.imports.iter() {
+            writeln!(w, "import {}", s)?;
+        }
+
+        for r in self.relocations.iter() {
+            writeln!(
+                w,
+                "reloc {} {} {} {}",
+                r.section, r.offset, r.symbol, r.addend
+            )?;
+        }
+
+        for l in self.lines.iter() {
+            writeln!(w, "line {} {}", l.offset, l.line)?;
+        }
+
+        Ok(())
+    }
+
+    /// Lê um módulo escrito por [`ObjectModule::write_to`].
+    ///
+    /// # Erros
+    ///
+    /// - [`ObjectError::InvalidHeader`] caso a primeira linha não seja o cabeçalho esperado.
+    /// - [`ObjectError::UnsupportedVersion`] caso a versão seja maior que [`OBJECT_VERSION`].
+    /// - [`ObjectError::BadFormat`] caso alguma linha esteja mal formatada.
+    pub fn read_from<R: BufRead>(r: R) -> Result<Self, ObjectError> {
+        let mut lines = r.lines().enumerate();
+        let mut module = ObjectModule::default();
+
+        match lines.next() {
+            Some((_, header)) => {
+                let header = header?;
+                match header.split_once(' ') {
+                    Some((OBJECT_MAGIC, v)) => match v.trim().parse::<usize>() {
+                        Ok(1..=OBJECT_VERSION) => (),
+                        Ok(v) => return Err(ObjectError::UnsupportedVersion(v)),
+                        Err(_) => return Err(ObjectError::InvalidHeader),
+                    },
+                    _ => return Err(ObjectError::InvalidHeader),
+                }
+            }
+            None => return Err(ObjectError::InvalidHeader),
+        }
+
+        for (i, line) in lines {
+            let line = line?;
+            let bad_format = || ObjectError::BadFormat {
+                line: i + 1,
+                content: line.clone(),
+            };
+
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            match fields.as_slice() {
+                [] => (),
+                ["module", name] => module.name = name.to_string(),
+                [kind, origin, words @ ..] if SectionKind::from_str(kind).is_ok() => {
+                    let section = module.section_mut(SectionKind::from_str(kind).unwrap());
+                    section.origin = match *origin {
+                        "-" => None,
+                        o => Some(parse_hex(o).ok_or_else(bad_format)?),
+                    };
+                    section.words = words
+                        .iter()
+                        .map(|w| parse_hex(w))
+                        .collect::<Option<Vec<_>>>()
+                        .ok_or_else(bad_format)?;
+                }
+                ["export", name, kind, offset] => module.exports.push(Symbol {
+                    name: name.to_string(),
+                    section: SectionKind::from_str(kind).map_err(|_| bad_format())?,
+                    offset: offset.parse().map_err(|_| bad_format())?,
+                }),
+                ["import", name] => module.imports.push(name.to_string()),
+                ["reloc", kind, offset, symbol, addend] => module.relocations.push(Relocation {
+                    section: SectionKind::from_str(kind).map_err(|_| bad_format())?,
+                    offset: offset.parse().map_err(|_| bad_format())?,
+                    symbol: symbol.to_string(),
+                    addend: addend.parse().map_err(|_| bad_format())?,
+                }),
+                ["line", offset, line] => module.lines.push(SourceLine {
+                    offset: offset.parse().map_err(|_| bad_format())?,
+                    line: line.parse().map_err(|_| bad_format())?,
+                }),
+                _ => return Err(bad_format()),
+            }
+        }
+
+        Ok(module)
+    }
+}
+
+fn parse_hex(s: &str) -> Option<MemoryCell> {
+    MemoryCell::from_str_radix(s.trim_start_matches("0x"), 16).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_write_read() {
+        let mut module = ObjectModule::new("main");
+        module.code.words = vec![0b0000100000000000, 0];
+        module.data.words = vec![65, 0];
+        module.set_origin(SectionKind::Data, 20000);
+        module.exports.push(Symbol {
+            name: "msg".to_string(),
+            section: SectionKind::Data,
+            offset: 0,
+        });
+        module.imports.push("print".to_string());
+        module.relocations.push(Relocation {
+            section: SectionKind::Code,
+            offset: 1,
+            symbol: "print".to_string(),
+            addend: 0,
+        });
+        module.lines.push(SourceLine { offset: 0, line: 3 });
+
+        let mut buf = Vec::new();
+        module.write_to(&mut buf).unwrap();
+        assert_eq!(module, ObjectModule::read_from(buf.as_slice()).unwrap());
+    }
+
+    #[test]
+    fn test_read_version_1() {
+        let module =
+            ObjectModule::read_from("ICMC-OBJ 1\nmodule main\ncode - 0000\n".as_bytes()).unwrap();
+        assert_eq!("main", module.name);
+        assert_eq!(vec![0], module.code.words);
+        assert!(module.lines.is_empty());
+    }
+
+    #[test]
+    fn test_read_invalid_header() {
+        assert!(matches!(
+            ObjectModule::read_from("ICMC-OBJ 0\n".as_bytes()),
+            Err(ObjectError::UnsupportedVersion(0))
+        ));
+        assert!(matches!(
+            ObjectModule::read_from("ICMC-OBJ 99\n".as_bytes()),
+            Err(ObjectError::UnsupportedVersion(99))
+        ));
+        assert!(matches!(
+            ObjectModule::read_from("module main\n".as_bytes()),
+            Err(ObjectError::InvalidHeader)
+        ));
+    }
+}
@@ -0,0 +1,114 @@
+//! Montagem de instruções avulsas, escritas diretamente na memória do processador.
+//!
+//! Diferente de [`crate::assemble`], o resultado não passa pelo [`crate::linker::Linker`]: as
+//! palavras já saem com os endereços finais, calculados a partir do endereço de destino e dos
+//! símbolos do programa carregado.
+
+use std::collections::HashMap;
+
+use isa::MemoryCell;
+use thiserror::Error;
+
+use crate::{assemble, object::SectionKind, AssemblerError};
+
+#[derive(Error, Debug, PartialEq)]
+pub enum PatchError {
+    #[error(transparent)]
+    Assembler(#[from] AssemblerError),
+
+    #[error("O símbolo \"{0}\" não foi definido.")]
+    UndefinedSymbol(String),
+
+    #[error("Dados (var, string e static) não podem ser escritos diretamente na memória.")]
+    DataSection,
+
+    #[error("Nenhuma instrução para montar.")]
+    Empty,
+}
+
+/// Monta `src` para ser escrito a partir do endereço `origin`. Rótulos definidos no próprio
+/// `src` são relativos a `origin`; os demais são procurados em `symbols`.
+///
+/// # Erros
+///
+/// Além dos erros de montagem, retorna [`PatchError::UndefinedSymbol`] para símbolos
+/// desconhecidos, [`PatchError::DataSection`] caso `src` defina dados e [`PatchError::Empty`]
+/// caso não haja instruções.
+///
+/// # Exemplo
+///
+/// ```
+/// use std::collections::HashMap;
+///
+/// let symbols = HashMap::from([("print".to_string(), 0x40)]);
+/// let words = assembler::patch::assemble_patch("CALL print", 0x10, &symbols).unwrap();
+/// assert_eq!(vec![0b0000110000000000, 0x40], words);
+/// ```
+pub fn assemble_patch(
+    src: &str,
+    origin: MemoryCell,
+    symbols: &HashMap<String, MemoryCell>,
+) -> Result<Vec<MemoryCell>, PatchError> {
+    let module = assemble("patch", src)?;
+    if !module.data.words.is_empty() {
+        return Err(PatchError::DataSection);
+    }
+
+    let mut words = module.code.words;
+    if words.is_empty() {
+        return Err(PatchError::Empty);
+    }
+
+    let local: HashMap<&str, MemoryCell> = module
+        .exports
+        .iter()
+        .filter(|s| s.section == SectionKind::Code)
+        .map(|s| (s.name.as_str(), origin + s.offset))
+        .collect();
+
+    for r in module.relocations {
+        let addr = local
+            .get(r.symbol.as_str())
+            .or_else(|| symbols.get(&r.symbol))
+            .ok_or_else(|| PatchError::UndefinedSymbol(r.symbol.clone()))?;
+        words[r.offset] = addr + r.addend;
+    }
+
+    Ok(words)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_assemble_patch() {
+        let symbols = HashMap::new();
+
+        assert_eq!(
+            Ok(vec![0b1110000000000000, 5]),
+            assemble_patch("LOADN R0, #5", 0, &symbols)
+        );
+        // Rótulos locais são relativos ao destino.
+        assert_eq!(
+            Ok(vec![0b0000100000000000, 0x20]),
+            assemble_patch("loop: JMP loop", 0x20, &symbols)
+        );
+        assert_eq!(
+            Err(PatchError::UndefinedSymbol("nowhere".to_string())),
+            assemble_patch("JMP nowhere", 0, &symbols)
+        );
+        assert_eq!(
+            Err(PatchError::DataSection),
+            assemble_patch("x: var #1", 0, &symbols)
+        );
+        assert_eq!(
+            Err(PatchError::Empty),
+            assemble_patch("; nada", 0, &symbols)
+        );
+        assert!(matches!(
+            assemble_patch("JMP", 0, &symbols),
+            Err(PatchError::Assembler(_))
+        ));
+    }
+}
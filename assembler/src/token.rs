@@ -3,7 +3,7 @@ use std::{any::Any, error, fmt::Display, option::Option, str::FromStr};
 use isa::Instruction;
 use thiserror::Error;
 
-#[derive(Error, Debug, PartialEq)]
+#[derive(Error, Debug, Clone, PartialEq)]
 pub enum TokenError {
     #[error("A string \"{0}\" está mal formatada.")]
     StringBadFormat(String),
@@ -51,6 +51,23 @@ pub enum Token {
     Plus,
 }
 
+/// Categoria de um [`Token`], usada no realce de sintaxe.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TokenKind {
+    /// Mnemônico de uma instrução.
+    Instruction,
+    /// Rótulo, na sua definição ou como operando.
+    Identifier,
+    /// Número, *char* ou *string*.
+    Literal,
+    /// Diretivas `string`, `static` e `var`.
+    Directive,
+    /// Registradores de uso geral, SP e FR.
+    Register,
+    /// `#`, `,`, `:` e `+`.
+    Punctuation,
+}
+
 impl TokenType for Token {
     fn same_type(&self, other: Self) -> bool {
         self.type_id() == other.type_id()
@@ -87,45 +104,76 @@ impl Display for Token {
 }
 
 impl Token {
+    pub fn kind(&self) -> TokenKind {
+        match self {
+            Self::Instruction(_) => TokenKind::Instruction,
+            Self::Identifier(_) => TokenKind::Identifier,
+            Self::Number(_) | Self::LiteralChar(_) | Self::LiteralStr(_) => TokenKind::Literal,
+            Self::String | Self::Static | Self::Var => TokenKind::Directive,
+            Self::R0
+            | Self::R1
+            | Self::R2
+            | Self::R3
+            | Self::R4
+            | Self::R5
+            | Self::R6
+            | Self::R7
+            | Self::SP
+            | Self::FR => TokenKind::Register,
+            Self::Pound | Self::Comma | Self::Colon | Self::Plus => TokenKind::Punctuation,
+        }
+    }
+
     fn word(s: &str) -> Result<Self, TokenError> {
-        if s.len() == 0 {
+        if s.is_empty() {
             Err(TokenError::Invalid("Tamanho nulo.".to_string()))
         } else if let Ok(inst) = Instruction::from_str(s) {
             Ok(Self::Instruction(inst))
         } else {
-            match s {
-                s if s.eq_ignore_ascii_case(&Self::String.to_string()) => Ok(Self::String),
-                s if s.eq_ignore_ascii_case(&Self::Var.to_string()) => Ok(Self::Var),
-                s if s.eq_ignore_ascii_case(&Self::Static.to_string()) => Ok(Self::Static),
-                _ => Ok(Self::Identifier(s.to_string())),
+            const KEYWORDS: [Token; 13] = [
+                Token::String,
+                Token::Var,
+                Token::Static,
+                Token::R0,
+                Token::R1,
+                Token::R2,
+                Token::R3,
+                Token::R4,
+                Token::R5,
+                Token::R6,
+                Token::R7,
+                Token::SP,
+                Token::FR,
+            ];
+
+            match KEYWORDS
+                .into_iter()
+                .find(|k| s.eq_ignore_ascii_case(&k.to_string()))
+            {
+                Some(k) => Ok(k),
+                None => Ok(Self::Identifier(s.to_string())),
             }
         }
     }
 
     fn number(s: &str) -> Result<Self, TokenError> {
-        let re = regex::RegexSet::new(&[
+        let re = regex::RegexSet::new([
             r"^[[:digit:]]{1,6}$",
             r"^0[xX][[:xdigit:]]{1,4}$",
-            r"^0[bB][[0-1]{1,16}$]",
+            r"^0[bB][01]{1,16}$",
         ])
         .unwrap();
 
-        match re.is_match(s) {
-            true => {
-                let ret_val = if let Ok(n) = usize::from_str_radix(&s, 10) {
-                    n
-                } else if let Ok(n) = usize::from_str_radix(&s[2..], 2) {
-                    n
-                } else if let Ok(n) = usize::from_str_radix(&s[2..], 16) {
-                    n
-                } else {
-                    return Err(TokenError::NumberBadFormat(s.to_string()));
-                };
-
-                Ok(Self::Number(ret_val))
-            }
-            false => Err(TokenError::NumberBadFormat(s.to_string())),
-        }
+        let ret_val = match re.matches(s).iter().next() {
+            Some(0) => s.parse::<usize>(),
+            Some(1) => usize::from_str_radix(&s[2..], 16),
+            Some(2) => usize::from_str_radix(&s[2..], 2),
+            _ => return Err(TokenError::NumberBadFormat(s.to_string())),
+        };
+
+        ret_val
+            .map(Self::Number)
+            .map_err(|_| TokenError::NumberBadFormat(s.to_string()))
     }
 
     fn string(s: &str) -> Result<Self, TokenError> {
@@ -163,7 +211,7 @@ impl Token {
             // '123'
             let c = &s[1..s.len() - 1];
 
-            if c.len() >= 1 && c.len() <= 2 {
+            if !c.is_empty() && c.len() <= 2 {
                 let mut iter = c.chars();
                 match iter.next() {
                     Some(ch) => match ch {
@@ -209,15 +257,15 @@ impl FromStr for Token {
     type Err = TokenError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        if s.len() == 0 {
+        if s.is_empty() {
             Err(TokenError::Invalid("Tamanho nulo.".to_string()))
         } else {
             match s.chars().next().unwrap() {
-                c if c.is_alphabetic() => Self::word(&s),
-                c if c.is_numeric() => Self::number(&s),
-                c if c == '"' => Self::string(&s),
-                c if c == '\'' => Self::char(&s),
-                c if c == '+' || c == '#' || c == ',' || c == ':' => Self::punctuation(s),
+                c if c.is_alphabetic() || c == '_' => Self::word(s),
+                c if c.is_numeric() => Self::number(s),
+                '"' => Self::string(s),
+                '\'' => Self::char(s),
+                '+' | '#' | ',' | ':' => Self::punctuation(s),
                 _ => Err(TokenError::Invalid(s.to_string())),
             }
         }
@@ -294,10 +342,22 @@ mod tests {
 
         {
             let s = "0x10";
-            assert_eq!(Token::Number(2), Token::from_str(s).unwrap())
+            assert_eq!(Token::Number(16), Token::from_str(s).unwrap())
+        }
+
+        {
+            let s = "0b10000000000000000";
+            assert!(Token::from_str(s).is_err())
         }
     }
 
+    #[test]
+    fn test_register() {
+        assert_eq!(Token::R3, Token::from_str("r3").unwrap());
+        assert_eq!(Token::SP, Token::from_str("SP").unwrap());
+        assert_eq!(Token::FR, Token::from_str("fr").unwrap());
+    }
+
     #[test]
     fn test_string() {
         {
@@ -0,0 +1,69 @@
+//! Compara a decodificação por tabela de [`Instruction::get_instruction`] com a comparação das
+//! máscaras como *strings*, usada anteriormente.
+//!
+//! Execute com `cargo bench -p isa`.
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use isa::{Instruction, MemoryCell, MAX_VALUE_MEMORY};
+
+const ROUNDS: usize = 5;
+
+/// Decodificação original: formata a palavra em binário e compara com cada máscara.
+fn decode_by_mask(value: MemoryCell) -> Option<Instruction> {
+    let value_string = format!("{:016b}", value);
+
+    Instruction::ALL.iter().copied().find(|inst| {
+        let test_value: String = value_string
+            .chars()
+            .zip(inst.mask().chars())
+            .map(|(v, x)| if x == '-' { '-' } else { v })
+            .collect();
+        test_value == inst.mask()
+    })
+}
+
+fn measure(name: &str, decode: impl Fn(MemoryCell) -> Option<Instruction>) -> Duration {
+    // Primeira passada fora da medição, para gerar a tabela.
+    for word in 0..=MAX_VALUE_MEMORY {
+        black_box(decode(black_box(word)));
+    }
+
+    let start = Instant::now();
+    for _ in 0..ROUNDS {
+        for word in 0..=MAX_VALUE_MEMORY {
+            black_box(decode(black_box(word)));
+        }
+    }
+    let elapsed = start.elapsed();
+
+    let per_word = elapsed / (ROUNDS * (MAX_VALUE_MEMORY + 1)) as u32;
+    println!(
+        "{:<10} {:>10.2?} ao todo, {:>8.2?} por palavra",
+        name, elapsed, per_word
+    );
+    elapsed
+}
+
+fn main() {
+    for word in 0..=MAX_VALUE_MEMORY {
+        assert_eq!(
+            decode_by_mask(word),
+            Instruction::get_instruction(word).ok(),
+            "{:016b}",
+            word
+        );
+    }
+    println!(
+        "Resultados idênticos para as {} palavras.",
+        MAX_VALUE_MEMORY + 1
+    );
+
+    let mask = measure("máscaras", decode_by_mask);
+    let table = measure("tabela", |w| Instruction::get_instruction(w).ok());
+    println!(
+        "Ganho: {:.1}x",
+        mask.as_secs_f64() / table.as_secs_f64().max(f64::EPSILON)
+    );
+}
@@ -0,0 +1,254 @@
+//! Decodificação das palavras da memória em instruções estruturadas e em texto de montagem.
+//!
+//! O texto gerado por [`DecodedInstruction`] segue a sintaxe aceita pelo montador, de modo que
+//! uma imagem desmontada possa ser montada novamente.
+
+use std::fmt::Display;
+
+use thiserror::Error;
+
+use crate::{bits, Instruction, InvalidInstruction, MemoryCell, OperandKind};
+
+#[derive(Error, Debug, PartialEq)]
+pub enum DisasmError {
+    #[error("Nenhuma palavra para decodificar.")]
+    Empty,
+
+    #[error("{0}")]
+    InvalidInstruction(#[from] InvalidInstruction),
+
+    #[error("A instrução {instruction} precisa de {expected} palavras, mas apenas {received} foram fornecidas.")]
+    MissingOperand {
+        instruction: Instruction,
+        expected: usize,
+        received: usize,
+    },
+}
+
+/// Registrador referenciado por uma instrução.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Register {
+    /// Registrador de uso geral `R0`-`R7`.
+    General(MemoryCell),
+    /// *Stack Pointer*, usado apenas pela instrução [`Instruction::MOV`].
+    SP,
+    /// *Flag Register*, usado apenas por [`Instruction::PUSH`] e [`Instruction::POP`].
+    FR,
+}
+
+impl Display for Register {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Register::General(n) => write!(f, "R{}", n),
+            Register::SP => write!(f, "SP"),
+            Register::FR => write!(f, "FR"),
+        }
+    }
+}
+
+/// Instrução decodificada a partir de uma palavra da memória e das suas palavras de operando.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DecodedInstruction {
+    pub instruction: Instruction,
+    /// Registradores na ordem em que aparecem no texto da instrução.
+    pub registers: Vec<Register>,
+    /// Valor imediato (`#NR`) ou quantidade de bits dos deslocamentos e rotações.
+    pub immediate: Option<MemoryCell>,
+    /// Endereço (`END`) usado pela instrução.
+    pub address: Option<MemoryCell>,
+    /// Número de palavras ocupadas pela instrução, incluindo os operandos.
+    pub length: usize,
+}
+
+/// Lê o registrador de uso geral guardado no campo de `operand`.
+fn field(word: MemoryCell, operand: &OperandKind) -> Register {
+    Register::General(operand.bits().map(|r| bits(word, r)).unwrap_or(0))
+}
+
+/// Decodifica a instrução presente em `words[0]`, lendo os operandos das palavras seguintes.
+///
+/// # Erros
+///
+/// - [`DisasmError::Empty`] caso `words` esteja vazio.
+/// - [`DisasmError::InvalidInstruction`] caso `words[0]` não seja uma instrução válida.
+/// - [`DisasmError::MissingOperand`] caso faltem palavras de operando em `words`.
+///
+/// # Exemplo
+///
+/// ```
+/// use isa::disasm::*;
+///
+/// let decoded = decode(&[0b1110000010000000, 42]).unwrap();
+/// assert_eq!(isa::Instruction::LOADN, decoded.instruction);
+/// assert_eq!(Some(42), decoded.immediate);
+/// assert_eq!("LOADN R1, #42", decoded.to_string());
+/// ```
+pub fn decode(words: &[MemoryCell]) -> Result<DecodedInstruction, DisasmError> {
+    let word = *words.first().ok_or(DisasmError::Empty)?;
+
+    let instruction = Instruction::get_instruction(word)?;
+    let received = words.len();
+
+    let mut decoded = DecodedInstruction {
+        instruction,
+        registers: Vec::new(),
+        immediate: None,
+        address: None,
+        length: instruction.length(),
+    };
+
+    let mut words = words.iter().copied().skip(1);
+    let mut next_word = || {
+        words.next().ok_or(DisasmError::MissingOperand {
+            instruction,
+            expected: instruction.length(),
+            received,
+        })
+    };
+
+    for operand in instruction.operands() {
+        match operand {
+            OperandKind::Rx | OperandKind::Ry | OperandKind::Rz => {
+                decoded.registers.push(field(word, operand))
+            }
+            OperandKind::Shift => decoded.immediate = Some(bits(word, 0..=3)),
+            OperandKind::Immediate => decoded.immediate = Some(next_word()?),
+            OperandKind::Address => decoded.address = Some(next_word()?),
+            OperandKind::MovRegisters => {
+                let rx = field(word, &OperandKind::Rx);
+                decoded.registers.extend(match bits(word, 0..=1) {
+                    0 => [rx, field(word, &OperandKind::Ry)],
+                    1 => [rx, Register::SP],
+                    _ => [Register::SP, rx],
+                })
+            }
+            OperandKind::StackRegister => decoded.registers.push(match bits(word, 6..=6) {
+                0 => field(word, &OperandKind::Rx),
+                _ => Register::FR,
+            }),
+        }
+    }
+
+    Ok(decoded)
+}
+
+impl DecodedInstruction {
+    /// Retorna o texto dos operandos, sem o mnemônico da instrução.
+    pub fn operands(&self) -> String {
+        self.operands_with(|addr| addr.to_string())
+    }
+
+    /// Retorna o texto dos operandos, escrevendo o endereço (`END`) com `address`. Permite
+    /// mostrar o rótulo de um desvio no lugar do número.
+    ///
+    /// # Exemplo
+    ///
+    /// ```
+    /// use isa::disasm::decode;
+    ///
+    /// let decoded = decode(&[0b0000100000000000, 123]).unwrap();
+    /// assert_eq!("123", decoded.operands());
+    /// assert_eq!("loop", decoded.operands_with(|_| "loop".to_string()));
+    /// ```
+    pub fn operands_with(&self, address: impl Fn(MemoryCell) -> String) -> String {
+        let mut registers = self.registers.iter();
+        let mut next_register = || registers.next().map(|r| r.to_string()).unwrap_or_default();
+
+        self.instruction
+            .operands()
+            .iter()
+            .map(|operand| match operand {
+                OperandKind::Rx
+                | OperandKind::Ry
+                | OperandKind::Rz
+                | OperandKind::StackRegister => next_register(),
+                OperandKind::MovRegisters => format!("{}, {}", next_register(), next_register()),
+                OperandKind::Immediate => format!("#{}", self.immediate.unwrap_or(0)),
+                OperandKind::Shift => self.immediate.unwrap_or(0).to_string(),
+                OperandKind::Address => address(self.address.unwrap_or(0)),
+            })
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+impl Display for DecodedInstruction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.operands().as_str() {
+            "" => write!(f, "{}", self.instruction),
+            ops => write!(f, "{} {}", self.instruction, ops),
+        }
+    }
+}
+
+/// Desmonta as instruções de `memory`, a partir do endereço 0, até o fim da memória.
+///
+/// Cada item contém o endereço da instrução e o resultado da decodificação. Palavras inválidas
+/// ocupam uma única posição.
+pub fn disassemble(
+    memory: &[MemoryCell],
+) -> impl Iterator<Item = (MemoryCell, Result<DecodedInstruction, DisasmError>)> + '_ {
+    let mut addr = 0;
+    std::iter::from_fn(move || {
+        if addr >= memory.len() {
+            return None;
+        }
+
+        let decoded = decode(&memory[addr..]);
+        let item = (addr, decoded);
+        addr += item.1.as_ref().map(|d| d.length).unwrap_or(1);
+        Some(item)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_text() {
+        let cases: [(&[MemoryCell], &str); 9] = [
+            (&[0b1100000010000000, 16], "LOAD R1, 16"),
+            (&[0b1100010110000000, 20], "STORE 20, R3"),
+            (&[0b1110010000000000, 20, 65], "STOREN 20, #65"),
+            (&[0b1100111000000011], "MOV SP, R4"),
+            (&[0b1100111010000001], "MOV R5, SP"),
+            (&[0b1000000010100111], "ADDC R1, R2, R3"),
+            (&[0b0100001110011001], "SHIFTL1 R7, 9"),
+            (&[0b0001010001000000], "PUSH FR"),
+            (&[0b0001000000000001], "RTI"),
+        ];
+
+        for (words, text) in cases {
+            assert_eq!(text, decode(words).unwrap().to_string());
+        }
+    }
+
+    #[test]
+    fn test_decode_errors() {
+        assert!(matches!(
+            decode(&[0b1011110000000000]),
+            Err(DisasmError::InvalidInstruction(_))
+        ));
+        assert_eq!(
+            Err(DisasmError::MissingOperand {
+                instruction: Instruction::STOREN,
+                expected: 3,
+                received: 2
+            }),
+            decode(&[0b1110010000000000, 20])
+        );
+    }
+
+    #[test]
+    fn test_disassemble() {
+        let memory = [
+            0b0000100000000000,
+            3,
+            0b1011110000000000,
+            0b0011110000000000,
+        ];
+        let addrs: Vec<MemoryCell> = disassemble(&memory).map(|(a, _)| a).collect();
+        assert_eq!(vec![0, 2, 3], addrs);
+    }
+}
@@ -1,15 +1,21 @@
-///! *Instruction Set Architecture - ISA*
+//! *Instruction Set Architecture - ISA*
 use std::ops::RangeInclusive;
 use std::str::FromStr;
+use std::sync::OnceLock;
 
 use thiserror::Error;
 
+pub mod disasm;
 pub mod memory;
+mod word;
+
+pub use word::{ParseWordError, Word};
 
 pub const BITS_ADDRESS: usize = 16;
 pub const MAX_VALUE_MEMORY: usize = 2_usize.pow(BITS_ADDRESS as u32) - 1;
 
-/// Tipo de dado utilizado para representar a memória do processador.
+/// Tipo de dado utilizado para representar endereços e as palavras das imagens de memória
+/// geradas pelo montador. Os registradores e a memória do processador usam [`Word`].
 pub type MemoryCell = usize;
 
 type Opcode = MemoryCell;
@@ -35,11 +41,78 @@ pub struct InvalidInstruction {
     code: String,
 }
 
+/// Tipo de operando de uma [`Instruction`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum OperandKind {
+    /// Registrador `Rx`, nos bits 7..=9.
+    Rx,
+    /// Registrador `Ry`, nos bits 4..=6.
+    Ry,
+    /// Registrador `Rz`, nos bits 1..=3.
+    Rz,
+    /// Valor `#NR`, na próxima palavra da memória.
+    Immediate,
+    /// Endereço `END`, na próxima palavra da memória.
+    Address,
+    /// Quantidade de bits deslocados ou rotacionados, nos bits 0..=3.
+    Shift,
+    /// Os dois operandos de [`Instruction::MOV`]: `Rx, Ry`, `Rx, SP` ou `SP, Rx`, escolhidos
+    /// pelos bits 0..=1 (`00`, `01` e `11`).
+    MovRegisters,
+    /// Operando de [`Instruction::PUSH`] e [`Instruction::POP`]: `Rx` ou, se o bit 6 for `1`,
+    /// `FR`.
+    StackRegister,
+}
+
+impl OperandKind {
+    /// Retorna o campo da instrução que guarda o valor do operando. Operandos que ocupam uma
+    /// palavra própria ou que dependem de outros bits da instrução retornam `None`.
+    pub fn bits(&self) -> Option<RangeInclusive<usize>> {
+        match self {
+            OperandKind::Rx => Some(7..=9),
+            OperandKind::Ry => Some(4..=6),
+            OperandKind::Rz => Some(1..=3),
+            OperandKind::Shift => Some(0..=3),
+            OperandKind::Immediate
+            | OperandKind::Address
+            | OperandKind::MovRegisters
+            | OperandKind::StackRegister => None,
+        }
+    }
+
+    /// Retorna se o operando ocupa uma palavra própria, após a palavra da instrução.
+    pub fn is_word(&self) -> bool {
+        matches!(self, OperandKind::Immediate | OperandKind::Address)
+    }
+}
+
+/// Índice usado na tabela de decodificação para palavras que não são instruções.
+const INVALID_ENTRY: u8 = u8::MAX;
+
+/// Tabela com o índice, em [`Instruction::ALL`], da instrução presente em cada palavra de 16
+/// bits.
+fn decode_table() -> &'static [u8] {
+    static TABLE: OnceLock<Vec<u8>> = OnceLock::new();
+
+    TABLE.get_or_init(|| {
+        let mut table = vec![INVALID_ENTRY; MAX_VALUE_MEMORY + 1];
+        for (i, inst) in Instruction::ALL.iter().enumerate() {
+            let (mask, value) = inst.fixed_bits();
+            for (word, entry) in table.iter_mut().enumerate() {
+                if word & mask == value {
+                    *entry = i as u8;
+                }
+            }
+        }
+        table
+    })
+}
+
 macro_rules! instruction_set {
-    ($($(#[$doc:meta])* $name:ident $op:literal),+) => {
+    ($($(#[$doc:meta])* $name:ident $op:literal [$($operand:ident),*] $len:literal $cycles:literal),+) => {
 
         /// Conjunto de instruções presentes na Arquitetura do Processador ICMC.
-        #[derive(Debug, Copy, Clone, PartialEq)]
+        #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
         pub enum Instruction {
             $(
                 $(#[$doc])*
@@ -47,6 +120,10 @@ macro_rules! instruction_set {
                 #[doc = "```txt"]
                 #[doc = $op]
                 #[doc = "```"]
+                #[doc = "# Operandos\n"]
+                #[doc = concat!("`[", stringify!($($operand),*), "]`, ", $len, " palavra(s).\n")]
+                #[doc = "# Ciclos\n"]
+                #[doc = concat!($cycles, " ciclo(s) de *clock*.")]
                 $name
             ),+
         }
@@ -72,6 +149,9 @@ macro_rules! instruction_set {
 
         impl Instruction {
 
+            /// Todas as instruções, na ordem em que foram declaradas.
+            pub const ALL: &'static [Instruction] = &[$(Instruction::$name),+];
+
             /// Retorna o OPCODE da instrução.
             pub fn opcode(&self) -> Opcode {
                 let code = match self {
@@ -87,6 +167,41 @@ macro_rules! instruction_set {
                 }
             }
 
+            /// Retorna os operandos da instrução, na ordem em que aparecem no texto de montagem.
+            ///
+            /// ## Exemplo
+            ///
+            /// ```
+            /// use isa::*;
+            ///
+            /// assert_eq!(
+            ///     &[OperandKind::Address, OperandKind::Rx],
+            ///     Instruction::STORE.operands()
+            /// );
+            /// ```
+            pub fn operands(&self) -> &'static [OperandKind] {
+                match self {
+                    $(Instruction::$name => &[$(OperandKind::$operand),*]),+,
+                }
+            }
+
+            /// Retorna o número de palavras ocupadas pela instrução, incluindo as palavras de
+            /// operando ([`OperandKind::Immediate`] e [`OperandKind::Address`]).
+            pub fn length(&self) -> usize {
+                match self {
+                    $(Instruction::$name => $len),+,
+                }
+            }
+
+            /// Retorna o número de ciclos de *clock* gastos pela instrução, incluindo a busca e a
+            /// decodificação. Saltos e chamadas condicionais gastam o mesmo número de ciclos,
+            /// tomados ou não.
+            pub fn cycles(&self) -> u64 {
+                match self {
+                    $(Instruction::$name => $cycles),+,
+                }
+            }
+
             pub fn bits(&self, r: RangeInclusive<usize>) -> MemoryCell {
                 let code = match self {
                     $(Instruction::$name => $op),+,
@@ -98,8 +213,28 @@ macro_rules! instruction_set {
                 MemoryCell::from_str_radix(&code[cr], 2).unwrap()
             }
 
+            /// Retorna os bits fixos da máscara da instrução, como `(máscara, valor)`. Uma palavra
+            /// `w` contém a instrução se `w & máscara == valor`.
+            ///
+            /// ## Exemplo
+            ///
+            /// ```
+            /// use isa::*;
+            ///
+            /// assert_eq!((0b1111111111000000, 0b0000100000000000), Instruction::JMP.fixed_bits());
+            /// ```
+            pub fn fixed_bits(&self) -> (MemoryCell, MemoryCell) {
+                self.mask().chars().fold((0, 0), |(mask, value), c| match c {
+                    '-' => (mask << 1, value << 1),
+                    c => ((mask << 1) | 1, (value << 1) | (c == '1') as MemoryCell),
+                })
+            }
+
             /// Retorna qual [`Instruction`] está presente no argumento `value`.
-            /// Se a instrução for inválida, irá retornar [`Instruction::InvalidInstruction`].
+            /// Se a instrução for inválida, irá retornar [`InvalidInstruction`].
+            ///
+            /// A decodificação consulta uma tabela com todas as palavras de 16 bits, gerada a
+            /// partir das máscaras no primeiro uso.
             ///
             /// ## Exemplo
             ///
@@ -110,23 +245,13 @@ macro_rules! instruction_set {
             /// assert_eq!(Instruction::LOAD, Instruction::get_instruction(mem).unwrap());
             /// ```
             pub fn get_instruction(value: MemoryCell) -> Result<Instruction, InvalidInstruction> {
-                let value_string = format!("{:016b}", value);
-
-                let mut test_value: String;
-
-                $(test_value = value_string.chars().zip($op.chars()).map(|(v, x)| {
-                    if x == '-' {
-                        '-'
-                    } else {
-                        v
-                    }
-                }).collect();
-
-                if test_value == $op {
-                    return Ok(Instruction::$name);
-                })+
-
-                Err(InvalidInstruction { code: value.to_string() })
+                match decode_table()
+                    .get(value)
+                    .and_then(|&i| Instruction::ALL.get(i as usize))
+                {
+                    Some(&inst) => Ok(inst),
+                    None => Err(InvalidInstruction { code: value.to_string() }),
+                }
             }
 
         }
@@ -151,7 +276,7 @@ instruction_set!(
     /// ```asm
     /// LOAD R3, 0xff00
     /// ```
-    LOAD        "110000----------", // Data Manipulation Instruction
+    LOAD        "110000----------" [Rx, Address] 2 5, // Data Manipulation Instruction
 
     /// Carrega o valor `NR` no registrador `Rx`. 
     ///
@@ -167,7 +292,7 @@ instruction_set!(
     /// ```asm
     /// LOADN R3, #0xff00
     /// ```
-    LOADN       "111000----------",
+    LOADN       "111000----------" [Rx, Immediate] 2 4,
 
     /// Carrega o valor da memória presente no endereço armazenado em `Ry` para o registrador
     /// `Rx`. 
@@ -184,7 +309,7 @@ instruction_set!(
     /// ```asm
     /// LOADI R3, R0
     /// ```
-    LOADI       "111100----------",
+    LOADI       "111100----------" [Rx, Ry] 1 4,
 
     /// Salva no endereço `END` da memória o valor presente no registrador `Rx`.
     ///
@@ -200,7 +325,7 @@ instruction_set!(
     /// ```asm
     /// STORE 0x00ff, R3 
     /// ```
-    STORE       "110001----------",
+    STORE       "110001----------" [Address, Rx] 2 5,
 
     /// Salva no endereço `END` da memória o valor `NR`.
     ///
@@ -216,7 +341,7 @@ instruction_set!(
     /// ```asm
     /// STOREN 0x00ff, #0b10100 
     /// ```
-    STOREN      "111001----------",
+    STOREN      "111001----------" [Address, Immediate] 3 6,
 
     /// Salva, na memória, no endereço armazenado em `Rx`, o valor presente no registrador `Ry`.
     ///
@@ -232,7 +357,7 @@ instruction_set!(
     /// ```asm
     /// STOREI R3, R0 
     /// ```
-    STOREI      "111101----------",
+    STOREI      "111101----------" [Rx, Ry] 1 4,
 
     /// Move, para um registrador `Rx` ou para o `SP`, o valor presente em outro registrador.
     ///
@@ -254,10 +379,10 @@ instruction_set!(
     /// MOV R3, SP 
     /// MOV SP, R0 
     /// ```
-    MOV         "110011----------",
+    MOV         "110011----------" [MovRegisters] 1 3,
 
-    INPUT       "111110----------", // Peripheric Instructions
-    OUTPUT      "111111----------",
+    INPUT       "111110----------" [Rx, Ry] 1 3, // Peripheric Instructions
+    OUTPUT      "111111----------" [Rx, Ry] 1 3,
 
     /// Imprime na tela do processador um *char* mapeado de um arquivo *charmap*. O código do
     /// *pixelmap* que representa o desenho do *char* está codificado no *low-byte* do registrador
@@ -300,10 +425,10 @@ instruction_set!(
     /// ```asm
     /// OUTCHAR R1, R0
     /// ```
-    OUTCHAR     "110010----------", // IO Instructions
+    OUTCHAR     "110010----------" [Rx, Ry] 1 3, // IO Instructions
 
-    INCHAR      "110101----------",
-    SOUND       "110100----------",
+    INCHAR      "110101----------" [Rx] 1 3,
+    SOUND       "110100----------" [Rx] 1 3,
 
     /// Realiza a soma dos valores presentes nos registradores `Ry` e `Rz`, guardando o resultado
     /// no registrador `Rx`.
@@ -320,7 +445,7 @@ instruction_set!(
     /// ```asm
     /// ADD R3, R0, R7 
     /// ```
-    ADD         "100000---------0", // Aritmethic Instructions
+    ADD         "100000---------0" [Rx, Ry, Rz] 1 3, // Aritmethic Instructions
 
     /// Realiza a soma dos valores presentes nos registradores `Ry` e `Rz` mais o *carry* (`C`),
     /// guardando o resultado no registrador `Rx`. 
@@ -337,7 +462,7 @@ instruction_set!(
     /// ```asm
     /// ADDC R3, R0, R7 
     /// ```
-    ADDC        "100000---------1", 
+    ADDC        "100000---------1" [Rx, Ry, Rz] 1 3, 
 
     /// Realiza a subtração dos valores presentes nos registradores `Ry` e `Rz`, guardando o
     /// resultado no registrador `Rx`.
@@ -354,7 +479,7 @@ instruction_set!(
     /// ```asm
     /// SUB R3, R0, R7 
     /// ```
-    SUB         "100001---------0",
+    SUB         "100001---------0" [Rx, Ry, Rz] 1 3,
 
     /// Realiza a subtração dos valores presentes nos registradores `Ry` e `Rz`, guardando no
     /// registrador `Rx` o resultado somado com o *carry* (`C`).
@@ -371,7 +496,7 @@ instruction_set!(
     /// ```asm
     /// SUBC R3, R0, R7 
     /// ```
-    SUBC        "100001---------1",
+    SUBC        "100001---------1" [Rx, Ry, Rz] 1 3,
 
     /// Realiza a multiplicação dos valores presentes nos registradores `Ry` e `Rz`, guardando o
     /// resultado no registrador `Rx`. 
@@ -388,7 +513,7 @@ instruction_set!(
     /// ```asm
     /// MUL R3, R0, R7 
     /// ```
-    MUL         "100010---------0",
+    MUL         "100010---------0" [Rx, Ry, Rz] 1 3,
 
     /// Realiza a divisão de `Ry` por `Rz`, guardando o resultado no registrador `Rx`.
     ///
@@ -404,7 +529,7 @@ instruction_set!(
     /// ```asm
     /// DIV R3, R0, R7 
     /// ```
-    DIV         "100011---------0",
+    DIV         "100011---------0" [Rx, Ry, Rz] 1 3,
 
     /// Incrementa em uma unidade o registrador `Rx`.
     ///
@@ -420,7 +545,7 @@ instruction_set!(
     /// ```asm
     /// INC R3 
     /// ```
-    INC         "100100---0------",
+    INC         "100100---0------" [Rx] 1 3,
 
     /// Decrementa em uma unidade o registrador `Rx`.
     ///
@@ -436,7 +561,7 @@ instruction_set!(
     /// ```asm
     /// DEC R3 
     /// ```
-    DEC         "100100---1------",
+    DEC         "100100---1------" [Rx] 1 3,
 
     /// Realiza a operação de módulo entre os registradores `Ry` e `Rz` e salva o resultado no
     /// registrador `Rx`.
@@ -453,7 +578,7 @@ instruction_set!(
     /// ```asm
     /// MOD R3, R2, R5 
     /// ```
-    MOD         "100101----------",
+    MOD         "100101----------" [Rx, Ry, Rz] 1 3,
 
     /// Realiza a operação *AND* entre os registradores `Ry` e `Rz` e salva o resultado no
     /// registrador `Rx`.
@@ -470,7 +595,7 @@ instruction_set!(
     /// ```asm
     /// AND R3, R2, R5 
     /// ```
-    AND         "010010----------", // Logic Instructions
+    AND         "010010----------" [Rx, Ry, Rz] 1 3, // Logic Instructions
     
     /// Realiza a operação *OR* entre os registradores `Ry` e `Rz` e salva o resultado no
     /// registrador `Rx`.
@@ -487,7 +612,7 @@ instruction_set!(
     /// ```asm
     /// OR R3, R2, R5 
     /// ```
-    OR          "010011----------",
+    OR          "010011----------" [Rx, Ry, Rz] 1 3,
 
     /// Realiza a operação *XOR* entre os registradores `Ry` e `Rz` e salva o resultado no
     /// registrador `Rx`.
@@ -504,7 +629,7 @@ instruction_set!(
     /// ```asm
     /// XOR R3, R2, R5 
     /// ```
-    XOR         "010100----------",
+    XOR         "010100----------" [Rx, Ry, Rz] 1 3,
 
     /// Realiza a operação *NOT* no registrador `Ry` e salva o resultado no registrador `Rx`.
     ///
@@ -520,7 +645,7 @@ instruction_set!(
     /// ```asm
     /// NOT R3, R2 
     /// ```
-    NOT         "010101----------",
+    NOT         "010101----------" [Rx, Ry] 1 3,
 
     /// Esta operação desliza os bits para a esquerda `N` vezes e os bits que transbordam a
     /// extremidade esquerda desaparecem. Os espaços na direita são preenchidos com 0.
@@ -542,7 +667,7 @@ instruction_set!(
     /// ```asm
     /// SHIFTL0 R7, 9
     /// ```
-    SHIFTL0     "010000---000----",
+    SHIFTL0     "010000---000----" [Rx, Shift] 1 3,
 
     /// Esta operação desliza os bits para a esquerda `N` vezes e os bits que transbordam a
     /// extremidade esquerda desaparecem. Os espaços na direita são preenchidos com 1.
@@ -564,7 +689,7 @@ instruction_set!(
     /// ```asm
     /// SHIFTL1 R7, 9
     /// ```
-    SHIFTL1     "010000---001----",
+    SHIFTL1     "010000---001----" [Rx, Shift] 1 3,
 
     /// Esta operação desliza os bits para a direita `N` vezes e os bits que transbordam a
     /// extremidade direita desaparecem. Os espaços na esquerda são preenchidos com 0.
@@ -586,7 +711,7 @@ instruction_set!(
     /// ```asm
     /// SHIFTR0 R7, 9
     /// ```
-    SHIFTR0     "010000---010----",
+    SHIFTR0     "010000---010----" [Rx, Shift] 1 3,
 
     /// Esta operação desliza os bits para a direita `N` vezes e os bits que transbordam a
     /// extremidade direita desaparecem. Os espaços na esquerda são preenchidos com 1.
@@ -608,7 +733,7 @@ instruction_set!(
     /// ```asm
     /// SHIFTR1 R7, 9
     /// ```
-    SHIFTR1     "010000---011----",
+    SHIFTR1     "010000---011----" [Rx, Shift] 1 3,
 
     /// Esta operação gira os bits para a esquerda `N` vezes e os bits que transbordam para
     /// a extremidade esquerda são reintroduzidos no lado direito.
@@ -631,7 +756,7 @@ instruction_set!(
     /// ```asm
     /// ROTL R6, 2
     /// ```
-    ROTL        "010000---10-----",
+    ROTL        "010000---10-----" [Rx, Shift] 1 3,
 
     /// Esta operação gira os bits para a direita `N` vezes e os bits que transbordam para
     /// a extremidade direita são reintroduzidos no lado esquerdo.
@@ -654,7 +779,7 @@ instruction_set!(
     /// ```asm
     /// ROTL R6, 2
     /// ```
-    ROTR        "010000---11-----",
+    ROTR        "010000---11-----" [Rx, Shift] 1 3,
 
     /// Compara os valores dos registradores `Rx` e `Ry` e atualiza o *flag register* (`FR`) de
     /// acordo com o resultado.
@@ -671,7 +796,7 @@ instruction_set!(
     /// ```asm
     /// CMP R3, R2 
     /// ```
-    CMP         "010110----------",
+    CMP         "010110----------" [Rx, Ry] 1 3,
 
     /// Pula para o endereço `END` da memória.
     ///
@@ -687,7 +812,7 @@ instruction_set!(
     /// ```asm
     /// JMP 0x00ff 
     /// ```
-    JMP         "0000100000------",
+    JMP         "0000100000------" [Address] 2 4,
 
     /// Pula para o endereço `END` da memória **se** o *bit* [`FlagIndex::EQUAL`] do
     /// *flag register* estiver setado.
@@ -704,7 +829,7 @@ instruction_set!(
     /// ```asm
     /// JEQ 0x00ff 
     /// ```
-    JEQ         "0000100001------",
+    JEQ         "0000100001------" [Address] 2 4,
     
     /// Pula para o endereço `END` da memória **se** o *bit* [`FlagIndex::EQUAL`] do
     /// *flag register* não estiver setado.
@@ -721,7 +846,7 @@ instruction_set!(
     /// ```asm
     /// JNE 0x00ff 
     /// ```
-    JNE         "0000100010------",
+    JNE         "0000100010------" [Address] 2 4,
 
     /// Pula para o endereço `END` da memória **se** o *bit* [`FlagIndex::ZERO`] do
     /// *flag register* estiver setado.
@@ -738,7 +863,7 @@ instruction_set!(
     /// ```asm
     /// JZ 0x00ff 
     /// ```
-    JZ          "0000100011------",
+    JZ          "0000100011------" [Address] 2 4,
 
     /// Pula para o endereço `END` da memória **se** o *bit* [`FlagIndex::ZERO`] do
     /// *flag register* não estiver setado.
@@ -755,7 +880,7 @@ instruction_set!(
     /// ```asm
     /// JNZ 0x00ff 
     /// ```
-    JNZ         "0000100100------",
+    JNZ         "0000100100------" [Address] 2 4,
 
     /// Pula para o endereço `END` da memória **se** o *bit* [`FlagIndex::CARRY`] do
     /// *flag register* estiver setado.
@@ -772,7 +897,7 @@ instruction_set!(
     /// ```asm
     /// JC 0x00ff 
     /// ```
-    JC          "0000100101------",
+    JC          "0000100101------" [Address] 2 4,
 
     /// Pula para o endereço `END` da memória **se** o *bit* [`FlagIndex::CARRY`] do
     /// *flag register* não estiver setado.
@@ -789,7 +914,7 @@ instruction_set!(
     /// ```asm
     /// JNC 0x00ff 
     /// ```
-    JNC         "0000100110------",
+    JNC         "0000100110------" [Address] 2 4,
 
     /// Pula para o endereço `END` da memória **se** o *bit* [`FlagIndex::GREATER`] do
     /// *flag register* estiver setado.
@@ -806,7 +931,7 @@ instruction_set!(
     /// ```asm
     /// JGR 0x00ff 
     /// ```
-    JGR         "0000100111------",
+    JGR         "0000100111------" [Address] 2 4,
 
     /// Pula para o endereço `END` da memória **se** o *bit* [`FlagIndex::LESSER`] do
     /// *flag register* estiver setado.
@@ -823,7 +948,7 @@ instruction_set!(
     /// ```asm
     /// JLE 0x00ff 
     /// ```
-    JLE         "0000101000------",
+    JLE         "0000101000------" [Address] 2 4,
 
     /// Pula para o endereço `END` da memória **se** algum dos *bits* [`FlagIndex::GREATER`] ou
     /// [`FlagIndex::EQUAL`] do *flag register* estiver setado.
@@ -840,7 +965,7 @@ instruction_set!(
     /// ```asm
     /// JEG 0x00ff 
     /// ```
-    JEG         "0000101001------",
+    JEG         "0000101001------" [Address] 2 4,
 
     /// Pula para o endereço `END` da memória **se** algum dos *bits* [`FlagIndex::LESSER`] ou
     /// [`FlagIndex::EQUAL`] do *flag register* estiver setado.
@@ -857,7 +982,7 @@ instruction_set!(
     /// ```asm
     /// JEL 0x00ff 
     /// ```
-    JEL         "0000101010------",
+    JEL         "0000101010------" [Address] 2 4,
 
     /// Pula para o endereço `END` da memória **se** o *bit* [`FlagIndex::ARITHMETIC_OVERFLOW`] do
     /// *flag register* estiver setado.
@@ -874,7 +999,7 @@ instruction_set!(
     /// ```asm
     /// JOV 0x00ff 
     /// ```
-    JOV         "0000101011------",
+    JOV         "0000101011------" [Address] 2 4,
 
     /// Pula para o endereço `END` da memória **se** o *bit* [`FlagIndex::ARITHMETIC_OVERFLOW`] do
     /// *flag register* não estiver setado.
@@ -891,7 +1016,7 @@ instruction_set!(
     /// ```asm
     /// JNO 0x00ff 
     /// ```
-    JNO         "0000101100------",
+    JNO         "0000101100------" [Address] 2 4,
 
     /// Pula para o endereço `END` da memória **se** o *bit* [`FlagIndex::DIV_BY_ZERO`] do
     /// *flag register* estiver setado.
@@ -908,7 +1033,7 @@ instruction_set!(
     /// ```asm
     /// JDZ 0x00ff 
     /// ```
-    JDZ         "0000101101------",
+    JDZ         "0000101101------" [Address] 2 4,
 
     /// Pula para o endereço `END` da memória **se** o *bit* [`FlagIndex::NEGATIVE`] do
     /// *flag register* estiver setado.
@@ -925,7 +1050,7 @@ instruction_set!(
     /// ```asm
     /// JN 0x00ff 
     /// ```
-    JN          "0000101110------",
+    JN          "0000101110------" [Address] 2 4,
 
     /// Salva o valor atual do *PC* na *stack* e pula para o endereço do procedimento informado.
     ///
@@ -943,7 +1068,7 @@ instruction_set!(
     /// ```asm
     /// CALL 0x003C
     /// ```
-    CALL        "0000110000------",
+    CALL        "0000110000------" [Address] 2 5,
 
     /// Salva o valor atual do *PC* na *stack* e pula para o endereço do procedimento informado se
     /// o *bit* [`FlagIndex::EQUAL`] do *flag register* estiver setado.
@@ -962,7 +1087,7 @@ instruction_set!(
     /// ```asm
     /// CEQ 0x003C
     /// ```
-    CEQ         "0000110001------",
+    CEQ         "0000110001------" [Address] 2 5,
 
     /// Salva o valor atual do *PC* na *stack* e pula para o endereço do procedimento informado se
     /// o *bit* [`FlagIndex::EQUAL`] do *flag register* não estiver setado.
@@ -981,7 +1106,7 @@ instruction_set!(
     /// ```asm
     /// CNE 0x003C
     /// ```
-    CNE         "0000110010------",
+    CNE         "0000110010------" [Address] 2 5,
     
     /// Salva o valor atual do *PC* na *stack* e pula para o endereço do procedimento informado se
     /// o *bit* [`FlagIndex::ZERO`] do *flag register* estiver setado.
@@ -1000,7 +1125,7 @@ instruction_set!(
     /// ```asm
     /// CZ 0x003C
     /// ```
-    CZ          "0000110011------",
+    CZ          "0000110011------" [Address] 2 5,
 
     /// Salva o valor atual do *PC* na *stack* e pula para o endereço do procedimento informado se
     /// o *bit* [`FlagIndex::ZERO`] do *flag register* não estiver setado.
@@ -1019,7 +1144,7 @@ instruction_set!(
     /// ```asm
     /// CNZ 0x003C
     /// ```
-    CNZ         "0000110100------",
+    CNZ         "0000110100------" [Address] 2 5,
 
     /// Salva o valor atual do *PC* na *stack* e pula para o endereço do procedimento informado se
     /// o *bit* [`FlagIndex::CARRY`] do *flag register* estiver setado.
@@ -1038,7 +1163,7 @@ instruction_set!(
     /// ```asm
     /// CC 0x003C
     /// ```
-    CC          "0000110101------",
+    CC          "0000110101------" [Address] 2 5,
 
     /// Salva o valor atual do *PC* na *stack* e pula para o endereço do procedimento informado se
     /// o *bit* [`FlagIndex::CARRY`] do *flag register* não estiver setado.
@@ -1057,7 +1182,7 @@ instruction_set!(
     /// ```asm
     /// CNC 0x003C
     /// ```
-    CNC         "0000110110------",
+    CNC         "0000110110------" [Address] 2 5,
 
     /// Salva o valor atual do *PC* na *stack* e pula para o endereço do procedimento informado se
     /// o *bit* [`FlagIndex::GREATER`] do *flag register* estiver setado.
@@ -1076,7 +1201,7 @@ instruction_set!(
     /// ```asm
     /// CGR 0x003C
     /// ```
-    CGR         "0000110111------",
+    CGR         "0000110111------" [Address] 2 5,
 
     /// Salva o valor atual do *PC* na *stack* e pula para o endereço do procedimento informado se
     /// o *bit* [`FlagIndex::LESSER`] do *flag register* estiver setado.
@@ -1095,7 +1220,7 @@ instruction_set!(
     /// ```asm
     /// CLE 0x003C
     /// ```
-    CLE         "0000111000------",
+    CLE         "0000111000------" [Address] 2 5,
 
     /// Salva o valor atual do *PC* na *stack* e pula para o endereço do procedimento informado se
     /// algum dos *bits* [`FlagIndex::EQUAL`] ou [`FlagIndex::GREATER`] do *flag register* estiver
@@ -1115,7 +1240,7 @@ instruction_set!(
     /// ```asm
     /// CEG 0x003C
     /// ```
-    CEG         "0000111001------",
+    CEG         "0000111001------" [Address] 2 5,
 
     /// Salva o valor atual do *PC* na *stack* e pula para o endereço do procedimento informado se
     /// algum dos *bits* [`FlagIndex::EQUAL`] ou [`FlagIndex::LESSER`] do *flag register* estiver
@@ -1135,7 +1260,7 @@ instruction_set!(
     /// ```asm
     /// CEL 0x003C
     /// ```
-    CEL         "0000111010------",
+    CEL         "0000111010------" [Address] 2 5,
 
     /// Salva o valor atual do *PC* na *stack* e pula para o endereço do procedimento informado se
     /// o *bit* [`FlagIndex::ARITHMETIC_OVERFLOW`] do *flag register* estiver setado.
@@ -1154,7 +1279,7 @@ instruction_set!(
     /// ```asm
     /// COV 0x003C
     /// ```
-    COV         "0000111011------",
+    COV         "0000111011------" [Address] 2 5,
 
     /// Salva o valor atual do *PC* na *stack* e pula para o endereço do procedimento informado se
     /// o *bit* [`FlagIndex::ARITHMETIC_OVERFLOW`] do *flag register* não estiver setado.
@@ -1173,7 +1298,7 @@ instruction_set!(
     /// ```asm
     /// CNO 0x003C
     /// ```
-    CNO         "0000111100------",
+    CNO         "0000111100------" [Address] 2 5,
 
     /// Salva o valor atual do *PC* na *stack* e pula para o endereço do procedimento informado se
     /// o *bit* [`FlagIndex::DIV_BY_ZERO`] do *flag register* estiver setado.
@@ -1192,7 +1317,7 @@ instruction_set!(
     /// ```asm
     /// CDZ 0x003C
     /// ```
-    CDZ         "0000111101------",
+    CDZ         "0000111101------" [Address] 2 5,
 
     /// Salva o valor atual do *PC* na *stack* e pula para o endereço do procedimento informado se
     /// o *bit* [`FlagIndex::NEGATIVE`] do *flag register* estiver setado.
@@ -1211,7 +1336,7 @@ instruction_set!(
     /// ```asm
     /// CN 0x003C
     /// ```
-    CN          "0000111110------",
+    CN          "0000111110------" [Address] 2 5,
 
     /// Altera o valor do *PC* para o último valor salvo na *stack* somado de 1.
     /// 
@@ -1229,7 +1354,7 @@ instruction_set!(
     /// ```asm
     /// RTS
     /// ```
-    RTS         "000100---------0",
+    RTS         "000100---------0" [] 1 5,
 
     /// Altera o valor do *PC* para o último valor salvo na *stack*.
     /// 
@@ -1246,7 +1371,7 @@ instruction_set!(
     /// ```asm
     /// RTI
     /// ```
-    RTI         "000100---------1",
+    RTI         "000100---------1" [] 1 5,
 
     /// Salva na *stack* o conteúdo de um registrador ou do *flag register*.
     ///
@@ -1267,7 +1392,7 @@ instruction_set!(
     /// PUSH R5
     /// PUSH FR
     /// ```
-    PUSH        "000101----------",
+    PUSH        "000101----------" [StackRegister] 1 4,
 
     /// Recupera da *stack* o conteúdo de um registrador ou do *flag register*.
     ///
@@ -1288,7 +1413,7 @@ instruction_set!(
     /// POP R5
     /// POP FR
     /// ```
-    POP         "000110----------",
+    POP         "000110----------" [StackRegister] 1 4,
 
     /// Sem operação. Serve apenas para consumir tempo.
     ///
@@ -1304,7 +1429,7 @@ instruction_set!(
     /// ```asm
     /// NOP
     /// ```
-    NOP         "000000----------", // Control Instructions
+    NOP         "000000----------" [] 1 3, // Control Instructions
 
     /// Para a execução do programa.
     /// 
@@ -1320,7 +1445,7 @@ instruction_set!(
     /// ```asm
     /// HALT
     /// ```
-    HALT        "001111----------",
+    HALT        "001111----------" [] 1 3,
 
     /// Limpa o bit [`FlagIndex::CARRY`] do *flag register*.
     ///
@@ -1336,7 +1461,7 @@ instruction_set!(
     /// ```asm
     /// CLEARC
     /// ```
-    CLEARC      "0010000---------",
+    CLEARC      "0010000---------" [] 1 3,
 
     /// Seta o bit [`FlagIndex::CARRY`] do *flag register*.
     ///
@@ -1352,7 +1477,7 @@ instruction_set!(
     /// ```asm
     /// SETC
     /// ```
-    SETC        "0010001---------",
+    SETC        "0010001---------" [] 1 3,
 
     /// Gera um *breakpoint* no código, forçando o simulador a entrar no modo *debug*.
     ///
@@ -1368,7 +1493,7 @@ instruction_set!(
     /// ```asm
     /// BREAKP
     /// ```
-    BREAKP      "001110----------");
+    BREAKP      "001110----------" [] 1 3);
 
 /// Retorna os bits presentes no valor `mem` que estão no range `r`.
 /// A contagem começa do *low bit* para o *high bit*.
@@ -1434,4 +1559,44 @@ mod tests {
             Instruction::get_instruction(0b0001001111111111).unwrap()
         );
     }
+
+    #[test]
+    fn test_instruction_length() {
+        for inst in Instruction::ALL {
+            let words = inst.operands().iter().filter(|o| o.is_word()).count();
+            assert_eq!(1 + words, inst.length(), "{}", inst);
+            // Busca, decodificação e leitura de cada palavra da instrução.
+            assert!(inst.cycles() >= 2 + inst.length() as u64, "{}", inst);
+        }
+    }
+
+    /// Verifica se `word` se encaixa na máscara, ignorando os bits `-`.
+    fn matches_mask(mask: &str, word: MemoryCell) -> bool {
+        format!("{:016b}", word)
+            .chars()
+            .zip(mask.chars())
+            .all(|(w, m)| m == '-' || w == m)
+    }
+
+    #[test]
+    fn test_instruction_masks() {
+        for inst in Instruction::ALL {
+            assert_eq!(BITS_ADDRESS, inst.mask().len(), "{}", inst);
+        }
+
+        for word in 0..=MAX_VALUE_MEMORY {
+            let expected: Vec<&Instruction> = Instruction::ALL
+                .iter()
+                .filter(|i| matches_mask(i.mask(), word))
+                .collect();
+
+            assert!(expected.len() <= 1, "{:016b}: {:?}", word, expected);
+            assert_eq!(
+                expected.first().copied().copied(),
+                Instruction::get_instruction(word).ok(),
+                "{:016b}",
+                word
+            );
+        }
+    }
 }
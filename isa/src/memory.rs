@@ -34,24 +34,63 @@ pub mod layout {
     pub const ADDR_RETURN: Range = 32759..=32759;
     pub const ADDR_INTERRUPTIONS: Range = 32760..=32767;
 
+    /// Todas as regiões da memória, em ordem de endereço, com o nome retornado por
+    /// [`data_area`].
+    pub const REGIONS: [(&str, Range); 13] = [
+        (stringify!(ADDR_PROG_AND_VAR), ADDR_PROG_AND_VAR),
+        (stringify!(ADDR_STATIC_DATA), ADDR_STATIC_DATA),
+        (stringify!(ADDR_DYNAMIC_DATA), ADDR_DYNAMIC_DATA),
+        (stringify!(ADDR_SYSTEM_CALL), ADDR_SYSTEM_CALL),
+        (stringify!(ADDR_GAP_TOP_STACK), ADDR_GAP_TOP_STACK),
+        (stringify!(ADDR_STACK), ADDR_STACK),
+        (stringify!(ADDR_GAP_BOTTOM_STACK), ADDR_GAP_BOTTOM_STACK),
+        (stringify!(ADDR_RX), ADDR_RX),
+        (stringify!(ADDR_TX), ADDR_TX),
+        (stringify!(ADDR_TIMER), ADDR_TIMER),
+        (stringify!(ADDR_ARGS), ADDR_ARGS),
+        (stringify!(ADDR_RETURN), ADDR_RETURN),
+        (stringify!(ADDR_INTERRUPTIONS), ADDR_INTERRUPTIONS),
+    ];
+
+    /// Retorna o índice em [`REGIONS`] da região que contém `addr`.
+    ///
+    /// # Exemplo
+    ///
+    /// ```
+    /// use isa::memory::layout::{region, REGIONS};
+    ///
+    /// assert_eq!(Some("ADDR_STACK"), region(30690).map(|i| REGIONS[i].0));
+    /// assert_eq!(None, region(40000));
+    /// ```
+    pub fn region(addr: MemoryCell) -> Option<usize> {
+        REGIONS.iter().position(|(_, range)| range.contains(&addr))
+    }
+
+    /// Retorna o nome da região que contém `addr`, como em [`REGIONS`].
     pub fn data_area(addr: MemoryCell) -> String {
-        match addr {
-            a if ADDR_PROG_AND_VAR.contains(&a) => stringify!(ADDR_PROG_AND_VAR).to_string(),
-            a if ADDR_STATIC_DATA.contains(&a) => stringify!(ADDR_STATIC_DATA).to_string(),
-            a if ADDR_DYNAMIC_DATA.contains(&a) => stringify!(ADDR_DYNAMIC_DATA).to_string(),
-            a if ADDR_SYSTEM_CALL.contains(&a) => stringify!(ADDR_SYSTEM_CALL).to_string(),
-            a if ADDR_GAP_TOP_STACK.contains(&a) => stringify!(ADDR_GAP_TOP_STACK).to_string(),
-            a if ADDR_STACK.contains(&a) => stringify!(ADDR_STACK).to_string(),
-            a if ADDR_GAP_BOTTOM_STACK.contains(&a) => {
-                stringify!(ADDR_GAP_BOTTOM_STACK).to_string()
+        match region(addr) {
+            Some(i) => REGIONS[i].0.to_string(),
+            None => "Invalid Data Area".to_string(),
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn test_regions() {
+            // As regiões cobrem a memória sem lacunas.
+            let mut next = 0;
+            for (name, range) in REGIONS {
+                assert_eq!(next, *range.start());
+                assert_eq!(name, data_area(next));
+                assert_eq!(name, data_area(*range.end()));
+                next = range.end() + 1;
             }
-            a if ADDR_RX.contains(&a) => stringify!(ADDR_RX).to_string(),
-            a if ADDR_TX.contains(&a) => stringify!(ADDR_TX).to_string(),
-            a if ADDR_TIMER.contains(&a) => stringify!(ADDR_TIMER).to_string(),
-            a if ADDR_ARGS.contains(&a) => stringify!(ADDR_ARGS).to_string(),
-            a if ADDR_RETURN.contains(&a) => stringify!(ADDR_RETURN).to_string(),
-            a if ADDR_INTERRUPTIONS.contains(&a) => stringify!(ADDR_INTERRUPTIONS).to_string(),
-            _ => "Invalid Data Area".to_string(),
+            assert_eq!(32768, next);
+            assert_eq!("ADDR_STACK", data_area(30690));
+            assert_eq!("Invalid Data Area", data_area(next));
         }
     }
 }
@@ -0,0 +1,312 @@
+//! Palavra de 16 bits do processador.
+//!
+//! Todas as operações aritméticas de [`Word`] são modulares (*wrapping*), de modo que nenhum
+//! valor acima de [`u16::MAX`] chegue aos registradores ou à memória.
+
+use std::{
+    fmt::{Binary, Display, LowerHex, UpperHex},
+    ops::{Add, BitAnd, BitOr, BitXor, Not, RangeInclusive, Shl, Shr, Sub},
+    str::FromStr,
+};
+
+use thiserror::Error;
+
+use crate::{MemoryCell, BITS_ADDRESS};
+
+/// Palavra de [`BITS_ADDRESS`] bits, usada nos registradores e na memória do processador.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Word(pub u16);
+
+impl Word {
+    pub const ZERO: Word = Word(0);
+    pub const MAX: Word = Word(u16::MAX);
+
+    /// Retorna o valor da palavra.
+    pub const fn value(self) -> u16 {
+        self.0
+    }
+
+    /// Retorna o valor da palavra como índice, para uso como endereço ou registrador.
+    pub const fn as_usize(self) -> usize {
+        self.0 as usize
+    }
+
+    /// Cria uma palavra com os [`BITS_ADDRESS`] *bits* menos significativos de `v`.
+    ///
+    /// # Exemplo
+    ///
+    /// ```
+    /// use isa::Word;
+    ///
+    /// assert_eq!(Word(0x2345), Word::truncate(0x12345));
+    /// ```
+    pub const fn truncate(v: MemoryCell) -> Self {
+        Word(v as u16)
+    }
+
+    /// Retorna os *bits* do intervalo `r`, contados do *low bit* para o *high bit*.
+    ///
+    /// # Exemplo
+    ///
+    /// ```
+    /// use isa::Word;
+    ///
+    /// assert_eq!(0b101, Word(0b101000).bits(3..=5));
+    /// ```
+    pub fn bits(self, r: RangeInclusive<usize>) -> u16 {
+        crate::bits(self.as_usize(), r) as u16
+    }
+
+    /// Retorna o *bit* `i`.
+    pub fn bit(self, i: usize) -> bool {
+        self.bits(i..=i) != 0
+    }
+
+    /// Soma `rhs`, retornando o resultado modular e se houve *carry*.
+    pub const fn overflowing_add(self, rhs: Word) -> (Word, bool) {
+        let (r, c) = self.0.overflowing_add(rhs.0);
+        (Word(r), c)
+    }
+
+    /// Subtrai `rhs`, retornando o resultado modular e se houve *borrow*.
+    pub const fn overflowing_sub(self, rhs: Word) -> (Word, bool) {
+        let (r, b) = self.0.overflowing_sub(rhs.0);
+        (Word(r), b)
+    }
+
+    /// Subtrai `rhs`, retornando [`None`] caso o resultado seja negativo.
+    pub const fn checked_sub(self, rhs: Word) -> Option<Word> {
+        match self.0.checked_sub(rhs.0) {
+            Some(r) => Some(Word(r)),
+            None => None,
+        }
+    }
+
+    /// Retorna o resto da divisão por `rhs`, ou [`None`] caso `rhs` seja zero.
+    pub const fn checked_rem(self, rhs: Word) -> Option<Word> {
+        match self.0.checked_rem(rhs.0) {
+            Some(r) => Some(Word(r)),
+            None => None,
+        }
+    }
+
+    pub const fn rotate_left(self, n: u32) -> Word {
+        Word(self.0.rotate_left(n))
+    }
+
+    pub const fn rotate_right(self, n: u32) -> Word {
+        Word(self.0.rotate_right(n))
+    }
+}
+
+impl From<u16> for Word {
+    fn from(v: u16) -> Self {
+        Word(v)
+    }
+}
+
+impl From<bool> for Word {
+    fn from(v: bool) -> Self {
+        Word(v as u16)
+    }
+}
+
+impl From<Word> for u16 {
+    fn from(w: Word) -> Self {
+        w.0
+    }
+}
+
+impl From<Word> for MemoryCell {
+    fn from(w: Word) -> Self {
+        w.as_usize()
+    }
+}
+
+impl TryFrom<MemoryCell> for Word {
+    type Error = std::num::TryFromIntError;
+
+    fn try_from(v: MemoryCell) -> Result<Self, Self::Error> {
+        u16::try_from(v).map(Word)
+    }
+}
+
+impl Add for Word {
+    type Output = Word;
+
+    fn add(self, rhs: Word) -> Word {
+        Word(self.0.wrapping_add(rhs.0))
+    }
+}
+
+impl Sub for Word {
+    type Output = Word;
+
+    fn sub(self, rhs: Word) -> Word {
+        Word(self.0.wrapping_sub(rhs.0))
+    }
+}
+
+impl Not for Word {
+    type Output = Word;
+
+    fn not(self) -> Word {
+        Word(!self.0)
+    }
+}
+
+impl BitAnd for Word {
+    type Output = Word;
+
+    fn bitand(self, rhs: Word) -> Word {
+        Word(self.0 & rhs.0)
+    }
+}
+
+impl BitOr for Word {
+    type Output = Word;
+
+    fn bitor(self, rhs: Word) -> Word {
+        Word(self.0 | rhs.0)
+    }
+}
+
+impl BitXor for Word {
+    type Output = Word;
+
+    fn bitxor(self, rhs: Word) -> Word {
+        Word(self.0 ^ rhs.0)
+    }
+}
+
+/// Deslocamento lógico; os *bits* que saem da palavra são descartados.
+impl Shl<u32> for Word {
+    type Output = Word;
+
+    fn shl(self, n: u32) -> Word {
+        Word(self.0.checked_shl(n).unwrap_or(0))
+    }
+}
+
+/// Deslocamento lógico; os *bits* que saem da palavra são descartados.
+impl Shr<u32> for Word {
+    type Output = Word;
+
+    fn shr(self, n: u32) -> Word {
+        Word(self.0.checked_shr(n).unwrap_or(0))
+    }
+}
+
+impl Display for Word {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Display::fmt(&self.0, f)
+    }
+}
+
+impl LowerHex for Word {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        LowerHex::fmt(&self.0, f)
+    }
+}
+
+impl UpperHex for Word {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        UpperHex::fmt(&self.0, f)
+    }
+}
+
+impl Binary for Word {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Binary::fmt(&self.0, f)
+    }
+}
+
+/// Erro na leitura de uma [`Word`] a partir de texto.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+#[error("Valor \"{0}\" inválido: use decimal, hexadecimal (0x), binário (0b) ou caractere ('A')")]
+pub struct ParseWordError(pub String);
+
+impl FromStr for Word {
+    type Err = ParseWordError;
+
+    /// Lê uma palavra em decimal, hexadecimal (`0x`), binário (`0b`) ou como um caractere
+    /// ASCII entre aspas simples.
+    ///
+    /// # Exemplo
+    ///
+    /// ```
+    /// use isa::Word;
+    ///
+    /// assert_eq!(Ok(Word(42)), "42".parse());
+    /// assert_eq!(Ok(Word(0xFF)), "0xff".parse());
+    /// assert_eq!(Ok(Word(5)), "0b101".parse());
+    /// assert_eq!(Ok(Word(65)), "'A'".parse());
+    /// assert!("70000".parse::<Word>().is_err());
+    /// ```
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let t = s.trim();
+        let lower = t.to_ascii_lowercase();
+
+        let value = if let Some(hex) = lower.strip_prefix("0x") {
+            u16::from_str_radix(hex, 16).ok()
+        } else if let Some(bin) = lower.strip_prefix("0b") {
+            u16::from_str_radix(bin, 2).ok()
+        } else if let Some(c) = t.strip_prefix('\'').and_then(|c| c.strip_suffix('\'')) {
+            let mut chars = c.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) if c.is_ascii() => Some(c as u16),
+                _ => None,
+            }
+        } else {
+            t.parse().ok()
+        };
+
+        value.map(Word).ok_or_else(|| ParseWordError(t.to_string()))
+    }
+}
+
+const _: () = assert!(u16::BITS as usize == BITS_ADDRESS);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_word_wrapping() {
+        assert_eq!(Word::ZERO, Word::MAX + Word(1));
+        assert_eq!(Word::MAX, Word::ZERO - Word(1));
+        assert_eq!((Word(1), true), Word::MAX.overflowing_add(Word(2)));
+        assert_eq!(None, Word(1).checked_sub(Word(2)));
+        assert_eq!(Some(Word(2)), Word(7).checked_rem(Word(5)));
+        assert_eq!(None, Word(7).checked_rem(Word::ZERO));
+        assert_eq!(Word(0xFF00), !Word(0x00FF));
+        assert_eq!(Word(0x8000), Word(0xC000) << 1);
+        assert_eq!(Word::ZERO, Word(1) << 16);
+        assert_eq!(Word(0x8001), Word(0xC000).rotate_left(1));
+        assert_eq!(Word(0x6000), Word(0xC000).rotate_right(1));
+    }
+
+    #[test]
+    fn test_word_bits() {
+        let w = Word(0b1100_0000_1000_0001);
+        assert_eq!(0b11, w.bits(14..=15));
+        assert!(w.bit(0));
+        assert!(!w.bit(1));
+        assert_eq!("1100000010000001", format!("{:016b}", w));
+        assert_eq!("0xC081", format!("{:#06X}", w));
+    }
+
+    #[test]
+    fn test_word_parse() {
+        assert_eq!(Ok(Word(0xBEEF)), " 0XBEEF ".parse());
+        assert_eq!(Ok(Word::MAX), "65535".parse());
+        assert_eq!(Ok(Word(b' ' as u16)), "' '".parse());
+        for invalid in ["", "65536", "-1", "0x", "0b102", "'AB'", "'é'", "R0"] {
+            assert_eq!(
+                Err(ParseWordError(invalid.to_string())),
+                invalid.parse::<Word>(),
+                "{invalid}"
+            );
+        }
+    }
+}
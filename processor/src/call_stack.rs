@@ -0,0 +1,264 @@
+//! Pilha de chamadas paralela à pilha do processador.
+//!
+//! Cada `CALL` (ou chamada condicional) executado empilha um [`CallFrame`], e cada `RTS`
+//! desempilha o último. A pilha de chamadas não depende do conteúdo da memória, de modo que
+//! permanece correta mesmo quando o programa corrompe a pilha do processador, e permite
+//! detectar `RTS` sem `CALL` correspondente e desvios do *Stack Pointer* entre a entrada e a
+//! saída de uma sub-rotina. Essas situações são reportadas como [`Violation`].
+
+use std::{collections::HashMap, fmt::Display};
+
+use isa::{memory::layout, MemoryCell};
+
+use crate::{errors::ProcessorError, protection::Violation, Processor};
+
+type Result<T> = std::result::Result<T, ProcessorError>;
+
+/// Profundidade máxima da pilha de chamadas. Ao ultrapassá-la, a chamada mais antiga é
+/// descartada.
+pub const MAX_CALL_DEPTH: usize = *layout::ADDR_STACK.end() - *layout::ADDR_STACK.start() + 1;
+
+/// Chamada de sub-rotina em andamento.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CallFrame {
+    /// Endereço da instrução de chamada.
+    pub call_site: MemoryCell,
+    /// Endereço da sub-rotina chamada.
+    pub target: MemoryCell,
+    /// Valor do *Stack Pointer* na entrada da sub-rotina, após empilhar o endereço de retorno.
+    pub sp: MemoryCell,
+}
+
+/// Sequência de chamadas até uma instrução, da mais recente para a mais antiga.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Backtrace {
+    /// Endereço da instrução atual.
+    pub pc: MemoryCell,
+    /// Chamadas em andamento, da mais antiga para a mais recente.
+    pub frames: Vec<CallFrame>,
+}
+
+/// Formata `addr` com o símbolo mais próximo em ou antes dele, como `nome+deslocamento`.
+fn symbolize(addr: MemoryCell, symbols: Option<&HashMap<String, MemoryCell>>) -> String {
+    let symbol = symbols.and_then(|symbols| {
+        symbols
+            .iter()
+            .filter(|(_, &a)| a <= addr)
+            .max_by(|(n1, a1), (n2, a2)| a1.cmp(a2).then(n2.cmp(n1)))
+    });
+
+    match symbol {
+        Some((name, &a)) if a == addr => format!("{:#06X} <{}>", addr, name),
+        Some((name, &a)) => format!("{:#06X} <{}+{}>", addr, name, addr - a),
+        None => format!("{:#06X}", addr),
+    }
+}
+
+impl Backtrace {
+    /// Formata o *backtrace*, uma linha por chamada, identificando os endereços pelos rótulos
+    /// de `symbols`, como os gerados pelo ligador do montador.
+    ///
+    /// # Exemplo
+    ///
+    /// ```
+    /// use std::collections::HashMap;
+    /// use processor::call_stack::{Backtrace, CallFrame};
+    ///
+    /// let backtrace = Backtrace {
+    ///     pc: 0x12,
+    ///     frames: vec![CallFrame { call_site: 0x05, target: 0x10, sp: 32737 }],
+    /// };
+    /// let symbols = HashMap::from([("main".to_string(), 0x00), ("print".to_string(), 0x10)]);
+    ///
+    /// assert_eq!(
+    ///     "#0 0x0012 <print+2>\n#1 0x0005 <main+5>",
+    ///     backtrace.render(Some(&symbols))
+    /// );
+    /// ```
+    pub fn render(&self, symbols: Option<&HashMap<String, MemoryCell>>) -> String {
+        std::iter::once(self.pc)
+            .chain(self.frames.iter().rev().map(|f| f.call_site))
+            .enumerate()
+            .map(|(i, addr)| format!("#{} {}", i, symbolize(addr, symbols)))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+impl Display for Backtrace {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.render(None))
+    }
+}
+
+impl Processor {
+    /// Retorna as chamadas em andamento, da mais antiga para a mais recente.
+    pub fn call_stack(&self) -> Vec<CallFrame> {
+        self.call_stack.iter().copied().collect()
+    }
+
+    /// Retorna o número de chamadas em andamento.
+    pub fn call_depth(&self) -> usize {
+        self.call_stack.len()
+    }
+
+    /// Retorna o *backtrace* a partir do PC atual.
+    pub fn backtrace(&self) -> Backtrace {
+        self.backtrace_at(self.pc)
+    }
+
+    /// Retorna o *backtrace* a partir da instrução em `pc`.
+    pub(crate) fn backtrace_at(&self, pc: MemoryCell) -> Backtrace {
+        Backtrace {
+            pc,
+            frames: self.call_stack(),
+        }
+    }
+
+    /// Registra a entrada na sub-rotina `target`, chamada pela instrução em execução.
+    pub(crate) fn enter_call(&mut self, target: MemoryCell) {
+        if self.call_stack.len() == MAX_CALL_DEPTH {
+            self.call_stack.pop_front();
+        }
+
+        self.call_stack.push_back(CallFrame {
+            call_site: self.instruction_addr,
+            target,
+            sp: self.sp,
+        });
+        self.profile_call(target);
+    }
+
+    /// Registra a saída da sub-rotina atual, antes de desempilhar o endereço de retorno.
+    ///
+    /// # Erros
+    ///
+    /// Retorna [`ProcessorError::Protection`] em [`crate::protection::ProtectionMode::Strict`]
+    /// caso não haja chamada em andamento ou caso o SP seja diferente do SP na entrada da
+    /// sub-rotina.
+    pub(crate) fn leave_call(&mut self) -> Result<()> {
+        let violation = match self.call_stack.back() {
+            None => Some(Violation::UnmatchedReturn),
+            Some(frame) if frame.sp != self.sp => Some(Violation::StackDrift {
+                target: frame.target,
+                expected: frame.sp,
+                found: self.sp,
+            }),
+            Some(_) => None,
+        };
+
+        // A chamada permanece na pilha caso a violação interrompa a execução, para aparecer no
+        // backtrace do erro.
+        self.report(violation)?;
+        self.call_stack.pop_back();
+        self.profile_return();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use isa::Word;
+
+    use super::*;
+    use crate::{protection::ProtectionMode, ProcessorStatus};
+
+    const CALL: u16 = 0b0000110000000000;
+    const RTS: u16 = 0b0001000000000000;
+    const PUSH_R0: u16 = 0b0001010000000000;
+    const BREAKP: u16 = 0b0011100000000000;
+    const HALT: u16 = 0b0011110000000000;
+
+    fn processor(program: &[u16]) -> Processor {
+        let mut p = Processor::new();
+        let program: Vec<Word> = program.iter().map(|&w| Word(w)).collect();
+        p.load_memory(&program).unwrap();
+        p.set_reg(0, Word::ZERO).unwrap();
+        p
+    }
+
+    fn run(p: &mut Processor) -> Result<()> {
+        p.set_status(ProcessorStatus::Running);
+        while p.status() == ProcessorStatus::Running {
+            p.instruction_cicle()?;
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_backtrace() {
+        // 0: CALL 3; 2: HALT; 3: CALL 6; 5: RTS; 6: BREAKP; 7: RTS
+        let mut p = processor(&[CALL, 3, HALT, CALL, 6, RTS, BREAKP, RTS]);
+        let sp = p.sp();
+
+        run(&mut p).unwrap();
+        assert_eq!(
+            vec![
+                CallFrame {
+                    call_site: 0,
+                    target: 3,
+                    sp: sp - 1,
+                },
+                CallFrame {
+                    call_site: 3,
+                    target: 6,
+                    sp: sp - 2,
+                },
+            ],
+            p.call_stack()
+        );
+
+        let symbols = HashMap::from([("main".to_string(), 0), ("f".to_string(), 3)]);
+        assert_eq!(
+            "#0 0x0007 <f+4>\n#1 0x0003 <f>\n#2 0x0000 <main>",
+            p.backtrace().render(Some(&symbols))
+        );
+
+        run(&mut p).unwrap();
+        assert_eq!(ProcessorStatus::Halted, p.status());
+        assert!(p.call_stack().is_empty());
+    }
+
+    #[test]
+    fn test_unmatched_return() {
+        let mut p = processor(&[PUSH_R0, RTS]);
+        p.set_protection(ProtectionMode::Strict);
+
+        let e = run(&mut p).unwrap_err();
+        assert_eq!(
+            &ProcessorError::Protection(Violation::UnmatchedReturn),
+            e.root()
+        );
+        assert_eq!(Some(1), e.context().map(|c| c.backtrace.pc));
+    }
+
+    #[test]
+    fn test_stack_drift() {
+        // 0: CALL 3; 2: HALT; 3: PUSH R0; 4: RTS
+        let mut p = processor(&[CALL, 3, HALT, PUSH_R0, RTS]);
+        let sp = p.sp();
+        p.set_protection(ProtectionMode::Strict);
+
+        assert_eq!(
+            &ProcessorError::Protection(Violation::StackDrift {
+                target: 3,
+                expected: sp - 1,
+                found: sp - 2,
+            }),
+            run(&mut p).unwrap_err().root()
+        );
+    }
+
+    #[test]
+    fn test_max_call_depth() {
+        let mut p = processor(&[HALT]);
+        for target in 0..=MAX_CALL_DEPTH {
+            p.enter_call(target as MemoryCell);
+        }
+
+        let call_stack = p.call_stack();
+        assert_eq!(MAX_CALL_DEPTH, p.call_depth());
+        assert_eq!(1, call_stack[0].target);
+        assert_eq!(MAX_CALL_DEPTH, call_stack[MAX_CALL_DEPTH - 1].target);
+    }
+}
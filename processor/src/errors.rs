@@ -3,9 +3,36 @@ use std::{
     fmt::Display,
 };
 
-use isa::MemoryCell;
+use isa::{disasm::DecodedInstruction, Instruction, MemoryCell, Word};
 use thiserror::Error;
 
+use crate::call_stack::Backtrace;
+
+/// Estado do processador no momento em que uma instrução falhou.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ErrorContext {
+    /// Endereço da instrução que falhou.
+    pub pc: MemoryCell,
+    /// Valor do *Instruction Register* ao falhar.
+    pub ir: Word,
+    /// Instrução em `pc`, caso ela possa ser decodificada.
+    pub instruction: Option<DecodedInstruction>,
+    /// Ciclos de *clock* executados antes da instrução.
+    pub cycle: u64,
+    /// Chamadas de sub-rotina em andamento ao falhar.
+    pub backtrace: Backtrace,
+}
+
+impl Display for ErrorContext {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "em {:#06X}", self.pc)?;
+        if let Some(instruction) = &self.instruction {
+            write!(f, ": {}", instruction)?;
+        }
+        Ok(())
+    }
+}
+
 #[derive(Error, Debug, PartialEq)]
 pub enum ProcessorError {
     #[error("O registrador PC({pc}) tentou acessar uma área indevida: {data_area}")]
@@ -29,12 +56,61 @@ pub enum ProcessorError {
     #[error("Instrução inválida: {0}")]
     InvalidInstruction(MemoryCell),
 
+    #[error("A instrução {0} ainda não é suportada pelo simulador.")]
+    UnimplementedInstruction(Instruction),
+
     #[error("Registrador inválido: {0}")]
     InvalidRegister(MemoryCell),
 
     #[error("Flag inválida: {0}")]
     InvalidFlag(usize),
 
+    #[error("Posição {0} fora da tela.")]
+    InvalidScreenPosition(MemoryCell),
+
+    #[error("O código {0} não representa nenhuma cor mapeada.")]
+    InvalidColor(MemoryCell),
+
+    #[error("{0}")]
+    Protection(#[from] crate::protection::Violation),
+
     #[error("{title}: {description}")]
     Generic { title: String, description: String },
+
+    #[error("{context} — {error}")]
+    Context {
+        context: Box<ErrorContext>,
+        error: Box<ProcessorError>,
+    },
+}
+
+impl ProcessorError {
+    /// Retorna o erro original, sem o [`ErrorContext`] de execução.
+    ///
+    /// # Exemplo
+    ///
+    /// ```
+    /// use isa::Word;
+    /// use processor::{errors::ProcessorError, Processor};
+    ///
+    /// let mut p = Processor::with_capacity(10);
+    /// p.set_mem(0, Word(0b1011110000000000)).unwrap();
+    ///
+    /// let e = p.instruction_cicle().unwrap_err();
+    /// assert_eq!(&ProcessorError::InvalidInstruction(0b1011110000000000), e.root());
+    /// ```
+    pub fn root(&self) -> &ProcessorError {
+        match self {
+            ProcessorError::Context { error, .. } => error.root(),
+            e => e,
+        }
+    }
+
+    /// Retorna o contexto de execução do erro, caso exista.
+    pub fn context(&self) -> Option<&ErrorContext> {
+        match self {
+            ProcessorError::Context { context, .. } => Some(context),
+            _ => None,
+        }
+    }
 }
@@ -2,8 +2,9 @@ use std::borrow::Borrow;
 
 use crate::errors::ProcessorError;
 
-use super::Processor;
-use isa::{FlagIndex, Instruction, MAX_VALUE_MEMORY};
+use super::{Processor, SCREEN_CELLS};
+use crate::stack::SlotKind;
+use isa::{FlagIndex, Instruction, MemoryCell, Word};
 
 #[derive(Debug, Clone, Copy)]
 pub enum Color {
@@ -71,6 +72,8 @@ impl Color {
 }
 
 pub trait InstructionCicle {
+    /// Executa a instrução. Ao fim da execução, o PC deve apontar para a próxima instrução,
+    /// pulando as [`Instruction::length`] palavras ocupadas pela instrução atual.
     fn execution(&self, processor: &mut Processor) -> Result<(), ProcessorError>;
 }
 
@@ -78,94 +81,78 @@ impl InstructionCicle for Instruction {
     fn execution(&self, p: &mut Processor) -> Result<(), ProcessorError> {
         match self {
             Instruction::LOAD => {
-                p.set_reg(p.rx(), p.mem(p.mem(p.pc())?)?)?;
-                p.inc_pc(1)?;
+                let value = p.read_data(p.mem(p.pc())?.as_usize())?;
+                p.set_reg(p.rx(), value)?;
+                p.inc_pc(self.length() - 1)?;
             }
 
             Instruction::LOADN => {
                 p.set_reg(p.rx(), p.mem(p.pc())?)?;
-                p.inc_pc(1)?;
+                p.inc_pc(self.length() - 1)?;
             }
 
             Instruction::LOADI => {
-                p.set_reg(p.rx(), p.reg(p.ry())?)?;
+                let value = p.read_data(p.reg(p.ry())?.as_usize())?;
+                p.set_reg(p.rx(), value)?;
             }
 
             Instruction::STORE => {
-                p.set_mem(p.mem(p.pc())?, p.reg(p.rx())?)?;
-                p.inc_pc(1)?;
+                p.write_data(p.mem(p.pc())?.as_usize(), p.reg(p.rx())?)?;
+                p.inc_pc(self.length() - 1)?;
             }
 
             Instruction::STOREN => {
-                p.set_mem(p.mem(p.pc())?, p.mem(p.pc() + 1)?)?;
-                p.inc_pc(2)?;
+                p.write_data(p.mem(p.pc())?.as_usize(), p.mem(p.pc() + 1)?)?;
+                p.inc_pc(self.length() - 1)?;
             }
 
             Instruction::STOREI => {
-                p.set_mem(p.reg(p.rx())?, p.reg(p.ry())?)?;
+                p.write_data(p.reg(p.rx())?.as_usize(), p.reg(p.ry())?)?;
             }
 
-            Instruction::MOV => match isa::bits(p.ir(), 0..=1) {
+            Instruction::MOV => match p.ir().bits(0..=1) {
                 0 => p.set_reg(p.rx(), p.reg(p.ry())?)?,
-                1 => p.set_reg(p.rx(), p.sp())?,
-                _ => p.set_sp(p.reg(p.rx())?)?,
+                1 => p.set_reg(p.rx(), Word::truncate(p.sp()))?,
+                _ => p.set_sp(p.reg(p.rx())?.as_usize())?,
             },
 
-            Instruction::INPUT => todo!(),
-            Instruction::OUTPUT => todo!(),
+            Instruction::INPUT | Instruction::OUTPUT => {
+                return Err(ProcessorError::UnimplementedInstruction(*self));
+            }
 
             Instruction::OUTCHAR => {
-                let index = p.reg(p.ry())?;
-                if index > super::VRAM_SIZE {
-                    return Err(ProcessorError::Generic {
-                        title: "Índice inválido na Mémoria de Vídeo".to_string(),
-                        description: format!(
-                            "O índice {} não pertence ao intervalo de 0 a {}.",
-                            index,
-                            super::VRAM_SIZE
-                        ),
-                    });
-                }
-
-                let c = isa::bits(p.reg(p.rx())?, 0..=7) as u8;
-                let color_code = isa::bits(p.reg(p.rx())?, 8..=15);
-                let color = Color::from_outchar(color_code);
+                let index = p.reg(p.ry())?.as_usize();
+                let color_code = MemoryCell::from(p.reg(p.rx())?.bits(8..=15)) << 8;
+                let color = Color::from_outchar(color_code)
+                    .ok_or(ProcessorError::InvalidColor(color_code))?;
 
-                match color {
-                    Some(color) => p.set_pixel(index, color.rgba()).unwrap(),
-                    None => {
-                        return Err(ProcessorError::Generic {
-                            title: "Cor inválida".to_string(),
-                            description: format!(
-                                "O código {} não representa nenhuma cor mapeada.",
-                                color_code
-                            ),
-                        });
-                    }
+                if index >= SCREEN_CELLS {
+                    return Err(ProcessorError::InvalidScreenPosition(index));
                 }
+                // Cada posição da tela ocupa 4 valores (RGBA) da memória de vídeo.
+                p.set_pixel(index * 4, color.rgba())?;
             }
 
-            Instruction::INCHAR => todo!(),
-            Instruction::SOUND => todo!(),
+            Instruction::INCHAR | Instruction::SOUND => {
+                return Err(ProcessorError::UnimplementedInstruction(*self));
+            }
 
             Instruction::ADD | Instruction::ADDC => {
-                p.set_reg(p.rx(), p.reg(p.ry())? + p.reg(p.rz())?)?;
+                let (mut result, mut carry) = p.reg(p.ry())?.overflowing_add(p.reg(p.rz())?);
 
                 if *self == Instruction::ADDC {
-                    p.set_reg(p.rx(), p.reg(p.rx())? + p.fr(FlagIndex::CARRY)? as usize)?;
+                    let (r, c) = result.overflowing_add(p.fr(FlagIndex::CARRY)?.into());
+                    (result, carry) = (r, carry || c);
                 }
 
                 p.ula_operation()?; // Limpa as flags relacionadas as operações
                                     // lógicas-aritméticas
 
-                match p.reg(p.rx())? {
-                    r if r > MAX_VALUE_MEMORY => {
-                        p.set_fr(FlagIndex::CARRY, true)?;
-                        p.set_fr(FlagIndex::ARITHMETIC_OVERFLOW, true)?;
-                        p.set_reg(p.rx(), r - MAX_VALUE_MEMORY)?;
-                    }
-                    _ => (),
-                };
+                p.set_reg(p.rx(), result)?;
+                if carry {
+                    p.set_fr(FlagIndex::CARRY, true)?;
+                    p.set_fr(FlagIndex::ARITHMETIC_OVERFLOW, true)?;
+                }
             }
 
             Instruction::SUB | Instruction::SUBC => {
@@ -173,7 +160,8 @@ impl InstructionCicle for Instruction {
                     p.reg(p.ry())?.checked_sub(p.reg(p.rz())?)
                 } else {
                     p.reg(p.ry())?
-                        .checked_sub(p.reg(p.rz())? + p.fr(FlagIndex::CARRY)? as usize)
+                        .checked_sub(p.reg(p.rz())?)
+                        .and_then(|r| r.checked_sub(p.fr(FlagIndex::CARRY).ok()?.into()))
                 };
 
                 p.ula_operation()?;
@@ -185,7 +173,7 @@ impl InstructionCicle for Instruction {
                         p.set_fr(FlagIndex::ZERO, false)?;
                         p.set_fr(FlagIndex::NEGATIVE, false)?;
 
-                        if s == 0 {
+                        if s == Word::ZERO {
                             p.set_fr(FlagIndex::ZERO, true)?;
                         }
                     }
@@ -193,39 +181,39 @@ impl InstructionCicle for Instruction {
                         p.set_fr(FlagIndex::ZERO, false)?;
                         p.set_fr(FlagIndex::NEGATIVE, true)?;
 
-                        p.set_reg(p.rx(), 0x0000)?;
+                        p.set_reg(p.rx(), Word::ZERO)?;
                     }
                 }
             }
 
-            Instruction::MUL => todo!(),
-            Instruction::DIV => todo!(),
+            Instruction::MUL | Instruction::DIV => {
+                return Err(ProcessorError::UnimplementedInstruction(*self));
+            }
 
             Instruction::INC | Instruction::DEC => {
                 let result = if *self == Instruction::INC {
-                    p.reg(p.rx())?.checked_add(1)
+                    Some(p.reg(p.rx())?.overflowing_add(Word(1)))
                 } else {
-                    p.reg(p.rx())?.checked_sub(1)
+                    p.reg(p.rx())?.checked_sub(Word(1)).map(|r| (r, false))
                 };
 
                 p.ula_operation()?;
 
                 let r = match result {
-                    Some(r) => match r {
-                        r if r > MAX_VALUE_MEMORY => {
-                            p.set_fr(FlagIndex::CARRY, true)?;
-                            p.set_fr(FlagIndex::ARITHMETIC_OVERFLOW, true)?;
-                            r - MAX_VALUE_MEMORY
-                        }
-                        r if r == 0 => {
+                    Some((r, true)) => {
+                        p.set_fr(FlagIndex::CARRY, true)?;
+                        p.set_fr(FlagIndex::ARITHMETIC_OVERFLOW, true)?;
+                        r
+                    }
+                    Some((r, false)) => {
+                        if r == Word::ZERO {
                             p.set_fr(FlagIndex::ZERO, true)?;
-                            r
                         }
-                        _ => r,
-                    },
+                        r
+                    }
                     None => {
                         p.set_fr(FlagIndex::NEGATIVE, true)?;
-                        0
+                        Word::ZERO
                     }
                 };
 
@@ -233,12 +221,19 @@ impl InstructionCicle for Instruction {
             }
 
             Instruction::MOD => {
-                p.set_reg(p.rx(), p.reg(p.ry())? % p.reg(p.rz())?)?;
+                let result = p.reg(p.ry())?.checked_rem(p.reg(p.rz())?);
 
                 p.ula_operation()?;
 
-                if p.reg(p.rx())? == 0 {
-                    p.set_fr(FlagIndex::ZERO, true)?;
+                // Com divisor zero, Rx não é alterado e apenas a flag DIV_BY_ZERO é marcada.
+                match result {
+                    Some(r) => {
+                        p.set_reg(p.rx(), r)?;
+                        if r == Word::ZERO {
+                            p.set_fr(FlagIndex::ZERO, true)?;
+                        }
+                    }
+                    None => p.set_fr(FlagIndex::DIV_BY_ZERO, true)?,
                 }
             }
 
@@ -253,7 +248,7 @@ impl InstructionCicle for Instruction {
 
                 p.set_fr(FlagIndex::ZERO, false)?;
 
-                if p.reg(p.rx())? == 0 {
+                if p.reg(p.rx())? == Word::ZERO {
                     p.set_fr(FlagIndex::ZERO, true)?;
                 }
             }
@@ -264,41 +259,34 @@ impl InstructionCicle for Instruction {
             | Instruction::SHIFTR1 => {
                 p.ula_operation()?;
 
-                let mut result = match self {
-                    Instruction::SHIFTL0 => p.reg(p.rx())? << isa::bits(p.ir(), 0..=3),
-                    Instruction::SHIFTL1 => !(!(p.reg(p.rx())?) << isa::bits(p.ir(), 0..=3)),
-                    Instruction::SHIFTR0 => p.reg(p.rx())? >> isa::bits(p.ir(), 0..=3),
-                    Instruction::SHIFTR1 => !(!(p.reg(p.rx())?) >> isa::bits(p.ir(), 0..=3)),
+                let n = p.ir().bits(0..=3).into();
+                let result = match self {
+                    Instruction::SHIFTL0 => p.reg(p.rx())? << n,
+                    Instruction::SHIFTL1 => !(!p.reg(p.rx())? << n),
+                    Instruction::SHIFTR0 => p.reg(p.rx())? >> n,
+                    Instruction::SHIFTR1 => !(!p.reg(p.rx())? >> n),
                     _ => unreachable!(),
                 };
 
-                if result > MAX_VALUE_MEMORY {
-                    result = result - MAX_VALUE_MEMORY;
-                }
-
                 p.set_reg(p.rx(), result)?;
 
-                if p.reg(p.rx())? == 0 {
+                if p.reg(p.rx())? == Word::ZERO {
                     p.set_fr(FlagIndex::ZERO, true)?;
                 }
             }
 
             Instruction::ROTL | Instruction::ROTR => {
                 p.ula_operation()?;
+
+                let n = p.ir().bits(0..=3).into();
                 let result = match self {
-                    Instruction::ROTL => {
-                        (p.reg(p.rx())? << isa::bits(p.ir(), 0..=3))
-                            | ((p.reg(p.rx()))? >> (isa::BITS_ADDRESS - isa::bits(p.ir(), 0..=3)))
-                    }
-                    Instruction::ROTR => {
-                        (p.reg(p.rx())? >> isa::bits(p.ir(), 0..=3))
-                            | ((p.reg(p.rx()))? << (isa::BITS_ADDRESS - isa::bits(p.ir(), 0..=3)))
-                    }
+                    Instruction::ROTL => p.reg(p.rx())?.rotate_left(n),
+                    Instruction::ROTR => p.reg(p.rx())?.rotate_right(n),
                     _ => unreachable!(),
                 };
 
                 p.set_reg(p.rx(), result)?;
-                if p.reg(p.rx())? == 0 {
+                if p.reg(p.rx())? == Word::ZERO {
                     p.set_fr(FlagIndex::ZERO, true)?;
                 }
             }
@@ -349,8 +337,8 @@ impl InstructionCicle for Instruction {
                 };
 
                 match b {
-                    true => p.set_pc(p.mem(p.pc())?)?,
-                    false => p.inc_pc(1)?,
+                    true => p.set_pc(p.mem(p.pc())?.as_usize())?,
+                    false => p.inc_pc(self.length() - 1)?,
                 }
             }
 
@@ -392,51 +380,53 @@ impl InstructionCicle for Instruction {
 
                 match b {
                     true => {
-                        p.set_mem(p.sp(), p.pc())?;
+                        p.set_mem(p.sp(), Word::truncate(p.pc()))?;
                         p.dec_sp(1)?;
-                        p.set_pc(p.mem(p.pc())?)?;
+                        p.set_pc(p.mem(p.pc())?.as_usize())?;
+                        p.enter_call(p.pc());
                     }
-                    false => p.inc_pc(1)?,
+                    false => p.inc_pc(self.length() - 1)?,
                 }
             }
 
             Instruction::RTS => {
+                p.leave_call()?;
                 p.inc_sp(1)?;
-                p.set_pc(p.mem(p.sp())?)?;
-                p.inc_pc(1)?;
+                p.set_pc(p.mem(p.sp())?.as_usize())?;
+                // O endereço salvo aponta para o operando do CALL.
+                p.inc_pc(Instruction::CALL.length() - 1)?;
             }
 
             Instruction::RTI => {
                 p.inc_sp(1)?;
-                p.set_pc(p.mem(p.sp())?)?;
+                p.set_pc(p.mem(p.sp())?.as_usize())?;
             }
 
             Instruction::PUSH => {
-                match isa::bits(p.ir(), 6..=6) {
-                    0 => p.set_mem(p.sp(), p.reg(p.rx())?)?, // Registrador
+                let (kind, value) = match p.ir().bits(6..=6) {
+                    0 => (SlotKind::Register(p.rx()), p.reg(p.rx())?), // Registrador
                     1 => {
                         // FR
                         let mut temp = 0;
                         for i in 0..isa::BITS_ADDRESS {
-                            temp += (p.fr(i)? as usize) * 2usize.pow(i as u32)
+                            temp |= (p.fr(i)? as u16) << i;
                         }
-                        p.set_mem(p.sp(), temp)?;
+                        (SlotKind::Flags, Word(temp))
                     }
                     _ => unreachable!(),
                 };
+                p.set_mem(p.sp(), value)?;
+                p.record_push(kind, value);
                 p.dec_sp(1)?;
             }
 
             Instruction::POP => {
                 p.inc_sp(1)?;
-                match isa::bits(p.ir(), 6..=6) {
+                match p.ir().bits(6..=6) {
                     0 => p.set_reg(p.rx(), p.mem(p.sp())?)?,
                     1 => {
                         for i in 0..isa::BITS_ADDRESS {
-                            let b = match isa::bits(p.mem(p.sp())?, i..=i) {
-                                0 => false,
-                                _ => true,
-                            };
+                            let b = p.mem(p.sp())?.bit(i);
                             p.set_fr(i, b)?
                         }
                     }
@@ -452,7 +442,10 @@ impl InstructionCicle for Instruction {
             Instruction::SETC => {
                 p.set_fr(FlagIndex::CARRY, true)?;
             }
-            Instruction::BREAKP => p.set_status(super::ProcessorStatus::Debug),
+            Instruction::BREAKP => {
+                log::info!("Breakpoint:\n{}", p.backtrace());
+                p.set_status(super::ProcessorStatus::Debug)
+            }
         }
 
         Ok(())
@@ -467,10 +460,150 @@ mod tests {
     #[test]
     fn test_invalid_instruction() {
         let mut p = Processor::with_capacity(10);
-        let _ = p.set_mem(0, 0b1011110000000000);
+        let _ = p.set_mem(0, Word(0b1011110000000000));
         assert_eq!(
-            ProcessorError::InvalidInstruction(0b1011110000000000),
+            ProcessorError::Context {
+                context: Box::new(crate::errors::ErrorContext {
+                    pc: 0,
+                    ir: Word(0b1011110000000000),
+                    instruction: None,
+                    cycle: 0,
+                    backtrace: p.backtrace(),
+                }),
+                error: Box::new(ProcessorError::InvalidInstruction(0b1011110000000000)),
+            },
             p.instruction_cicle().err().unwrap()
         )
     }
+
+    #[test]
+    fn test_loadi() {
+        let mut p = Processor::with_capacity(10);
+        p.set_mem(0, Word(0b1111000010100000)).unwrap(); // LOADI R1, R2
+        p.set_mem(7, Word(42)).unwrap();
+        p.set_reg(2, Word(7)).unwrap();
+
+        p.instruction_cicle().unwrap();
+        assert_eq!(Word(42), p.reg(1).unwrap());
+        assert_eq!(Word(7), p.reg(2).unwrap());
+    }
+
+    #[test]
+    fn test_error_context() {
+        let mut p = Processor::with_capacity(10);
+        p.set_mem(0, Word(0)).unwrap(); // NOP
+        p.set_mem(1, Word(0b1100100010000000)).unwrap(); // OUTCHAR R1, R0
+        p.set_reg(1, Word(0x1000)).unwrap();
+
+        p.instruction_cicle().unwrap();
+        let e = p.instruction_cicle().unwrap_err();
+
+        assert_eq!(&ProcessorError::InvalidColor(0x1000), e.root());
+        assert_eq!(
+            Some(Instruction::NOP.cycles()),
+            e.context().map(|c| c.cycle)
+        );
+        assert_eq!(
+            "em 0x0001: OUTCHAR R1, R0 — O código 4096 não representa nenhuma cor mapeada.",
+            e.to_string()
+        );
+    }
+
+    #[test]
+    fn test_outchar() {
+        let mut p = Processor::with_capacity(10);
+        p.set_mem(0, Word(0b1100100010000000)).unwrap(); // OUTCHAR R1, R0
+        p.set_reg(1, Word(0x0200 | 'A' as u16)).unwrap();
+
+        p.set_reg(0, Word(1)).unwrap();
+        p.instruction_cicle().unwrap();
+        assert_eq!(Color::Green.rgba(), p.pixel(4).unwrap());
+        assert_eq!((0, 0, 0, 0), p.pixel(0).unwrap());
+
+        p.set_pc(0).unwrap();
+        p.set_reg(0, Word((SCREEN_CELLS - 1) as u16)).unwrap();
+        p.instruction_cicle().unwrap();
+        assert_eq!(
+            Color::Green.rgba(),
+            p.pixel((SCREEN_CELLS - 1) * 4).unwrap()
+        );
+
+        p.set_pc(0).unwrap();
+        p.set_reg(0, Word(SCREEN_CELLS as u16)).unwrap();
+        assert_eq!(
+            &ProcessorError::InvalidScreenPosition(SCREEN_CELLS),
+            p.instruction_cicle().unwrap_err().root()
+        );
+    }
+
+    #[test]
+    fn test_unimplemented_instruction() {
+        let program = [
+            (0b1000100000000000, Instruction::MUL),
+            (0b1000110000000000, Instruction::DIV),
+            (0b1111100000000000, Instruction::INPUT),
+            (0b1111110000000000, Instruction::OUTPUT),
+            (0b1101010000000000, Instruction::INCHAR),
+            (0b1101000000000000, Instruction::SOUND),
+        ];
+        for (word, instruction) in program {
+            let mut p = Processor::with_capacity(10);
+            p.set_mem(0, Word(word)).unwrap();
+            assert_eq!(
+                &ProcessorError::UnimplementedInstruction(instruction),
+                p.instruction_cicle().unwrap_err().root()
+            );
+        }
+    }
+
+    #[test]
+    fn test_word_overflow() {
+        let mut p = Processor::with_capacity(10);
+        let program = [
+            0b1000000010100110, // ADD R1, R2, R3
+            0b0101011000010000, // NOT R4, R1
+            0b1001001010000000, // INC R5
+            0b0100001101000100, // ROTL R6, 4
+        ];
+        for (addr, &word) in program.iter().enumerate() {
+            p.set_mem(addr, Word(word)).unwrap();
+        }
+        p.set_reg(2, Word::MAX).unwrap();
+        p.set_reg(3, Word(2)).unwrap();
+        p.set_reg(5, Word::MAX).unwrap();
+        p.set_reg(6, Word(0x8001)).unwrap();
+
+        p.instruction_cicle().unwrap();
+        assert_eq!(Word(1), p.reg(1).unwrap());
+        assert!(p.fr(FlagIndex::CARRY).unwrap());
+
+        p.instruction_cicle().unwrap();
+        assert_eq!(Word(0xFFFE), p.reg(4).unwrap());
+
+        p.instruction_cicle().unwrap();
+        assert_eq!(Word::ZERO, p.reg(5).unwrap());
+        assert!(p.fr(FlagIndex::CARRY).unwrap());
+
+        p.instruction_cicle().unwrap();
+        assert_eq!(Word(0x0018), p.reg(6).unwrap());
+    }
+
+    #[test]
+    fn test_mod_by_zero() {
+        let mut p = Processor::with_capacity(10);
+        // MOD R1, R2, R3 duas vezes
+        p.set_mem(0, Word(0b1001010010100110)).unwrap();
+        p.set_mem(1, Word(0b1001010010100110)).unwrap();
+        p.set_reg(1, Word(9)).unwrap();
+        p.set_reg(2, Word(7)).unwrap();
+
+        p.instruction_cicle().unwrap();
+        assert_eq!(Word(9), p.reg(1).unwrap());
+        assert!(p.fr(FlagIndex::DIV_BY_ZERO).unwrap());
+
+        p.set_reg(3, Word(5)).unwrap();
+        p.instruction_cicle().unwrap();
+        assert_eq!(Word(2), p.reg(1).unwrap());
+        assert!(!p.fr(FlagIndex::DIV_BY_ZERO).unwrap());
+    }
 }
@@ -1,23 +1,40 @@
 #![allow(dead_code, unused_imports, missing_docs)]
 
+pub mod call_stack;
 pub mod errors;
 pub mod instructions;
+pub mod memory_map;
+pub mod patch;
+pub mod profiler;
+pub mod protection;
+pub mod runner;
+pub mod snapshot;
+pub mod stack;
 
 use crate::instructions::InstructionCicle;
 
-use errors::ProcessorError;
-use isa::{Instruction, MemoryCell};
+use call_stack::CallFrame;
+
+use errors::{ErrorContext, ProcessorError};
+use isa::{Instruction, MemoryCell, Word};
 use log::{debug, info, warn};
+use profiler::Profile;
+use protection::{ProtectionMode, ProtectionWarning};
+use stack::SlotKind;
 
 use std::{
     borrow::Borrow,
+    collections::{HashMap, VecDeque},
     fmt::Display,
     ops::Deref,
-    sync::{Arc, Mutex},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
     thread,
 };
 
-#[derive(Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ProcessorStatus {
     Running,
     Debug,
@@ -31,6 +48,9 @@ pub const MEMORY_SIZE: usize = 32768;
 /// Tamanho da memória de vídeo do processador.
 pub const VRAM_SIZE: usize = 30 * 40 * 8 * 8 * 4;
 
+/// Número de posições da tela usadas pelo `OUTCHAR`: 30 linhas de 40 caracteres.
+pub const SCREEN_CELLS: usize = 30 * 40;
+
 /// Número de registradores disponíveis no processador.
 pub const NUM_REGISTERS: usize = 8;
 
@@ -39,9 +59,9 @@ pub const MAX_VALUE_MEMORY: usize = 2_usize.pow(isa::BITS_ADDRESS as u32) - 1;
 type Result<T> = std::result::Result<T, ProcessorError>;
 
 pub struct Processor {
-    ram: Vec<usize>,
+    ram: Vec<Word>,
     vram: Vec<usize>,
-    registers: [usize; NUM_REGISTERS],
+    registers: [Word; NUM_REGISTERS],
 
     rx: usize,
     ry: usize,
@@ -51,11 +71,54 @@ pub struct Processor {
     // Program Counter
     pc: usize,
     // Instruction Register
-    ir: usize,
+    ir: Word,
     // Stack Pointer
     sp: usize,
 
-    status: Arc<Mutex<ProcessorStatus>>,
+    status: ProcessorStatus,
+
+    // Ciclos de clock executados desde o último reset.
+    cycles: u64,
+
+    // Última imagem carregada, restaurada por `reload`.
+    image: Option<Image>,
+
+    // Proteção da memória: registradores e endereços já escritos e endereços já executados.
+    protection: ProtectionMode,
+    registers_initialized: [bool; NUM_REGISTERS],
+    initialized: Vec<bool>,
+    executed: Vec<bool>,
+    warnings: Vec<ProtectionWarning>,
+    // Endereço da instrução em execução.
+    instruction_addr: MemoryCell,
+
+    // Chamadas de sub-rotina em andamento, da mais antiga para a mais recente.
+    call_stack: VecDeque<CallFrame>,
+
+    // Origem e valor das palavras empilhadas por `PUSH`, indexadas pelo endereço.
+    pushes: HashMap<MemoryCell, (SlotKind, Word)>,
+
+    // Ciclo da última escrita de cada endereço, indexado pelo endereço; `None` para os
+    // endereços não escritos desde o último reset.
+    writes: Vec<Option<u64>>,
+
+    // Perfil de execução, presente enquanto o perfil estiver ativo.
+    profile: Option<Profile>,
+}
+
+/// Tratamento da memória fora da imagem carregada por [`Processor::load_memory_at`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Fill {
+    /// Preenche o restante da memória com zeros.
+    Zero,
+    /// Mantém o conteúdo atual do restante da memória.
+    Preserve,
+}
+
+struct Image {
+    base: MemoryCell,
+    words: Vec<Word>,
+    fill: Fill,
 }
 
 impl Default for Processor {
@@ -67,26 +130,33 @@ impl Default for Processor {
 
         let mut mem = Vec::with_capacity(MEMORY_SIZE);
         for _ in 0..MEMORY_SIZE {
-            mem.push(0);
-        }
-
-        let mut vram = Vec::with_capacity(VRAM_SIZE);
-        for _ in 0..vram.capacity() {
-            vram.push(0);
+            mem.push(Word::ZERO);
         }
 
         Self {
             ram: mem,
-            vram,
-            registers: [0; NUM_REGISTERS],
+            vram: vec![0; VRAM_SIZE],
+            registers: [Word::ZERO; NUM_REGISTERS],
             rx: 0,
             ry: 0,
             rz: 0,
             fr: [false; isa::BITS_ADDRESS],
             pc: *isa::memory::layout::ADDR_PROG_AND_VAR.start(),
-            ir: 0,
+            ir: Word::ZERO,
             sp: *isa::memory::layout::ADDR_STACK.end(),
-            status: Arc::new(Mutex::new(ProcessorStatus::Debug)),
+            status: ProcessorStatus::Debug,
+            cycles: 0,
+            image: None,
+            protection: ProtectionMode::default(),
+            registers_initialized: [false; NUM_REGISTERS],
+            initialized: vec![false; MEMORY_SIZE],
+            executed: vec![false; MEMORY_SIZE],
+            warnings: Vec::new(),
+            instruction_addr: 0,
+            call_stack: VecDeque::new(),
+            pushes: HashMap::new(),
+            writes: vec![None; MEMORY_SIZE],
+            profile: None,
         }
     }
 }
@@ -128,11 +198,14 @@ impl Processor {
 
         let mut mem = Vec::with_capacity(s);
         for _ in 0..s {
-            mem.push(0);
+            mem.push(Word::ZERO);
         }
 
         Self {
             ram: mem,
+            initialized: vec![false; s],
+            executed: vec![false; s],
+            writes: vec![None; s],
             ..Default::default()
         }
     }
@@ -149,9 +222,9 @@ impl Processor {
     /// use crate::processor::Processor;
     ///
     /// let mut p = Processor::with_capacity(10);
-    /// assert_eq!(0x0, p.mem(0).unwrap());
+    /// assert_eq!(0x0, p.mem(0).unwrap().value());
     /// ```
-    pub fn mem(&self, addr: MemoryCell) -> Result<MemoryCell> {
+    pub fn mem(&self, addr: MemoryCell) -> Result<Word> {
         match self.ram.get(addr) {
             Some(&v) => Ok(v),
             None => Err(ProcessorError::InvalidAddress(addr)),
@@ -170,14 +243,16 @@ impl Processor {
     /// use crate::processor::Processor;
     ///
     /// let mut p = Processor::with_capacity(10);
-    /// assert_eq!(0x0, p.mem(0).unwrap());
-    /// p.set_mem(0, 0x1);
-    /// assert_eq!(0x1, p.mem(0).unwrap());
+    /// assert_eq!(0x0, p.mem(0).unwrap().value());
+    /// p.set_mem(0, isa::Word(0x1));
+    /// assert_eq!(0x1, p.mem(0).unwrap().value());
     /// ```
-    pub fn set_mem(&mut self, adrr: MemoryCell, v: MemoryCell) -> Result<()> {
+    pub fn set_mem(&mut self, adrr: MemoryCell, v: Word) -> Result<()> {
         match self.ram.get_mut(adrr) {
             Some(m) => {
                 *m = v;
+                self.initialized[adrr] = true;
+                self.record_write(adrr);
                 Ok(())
             }
             None => Err(ProcessorError::InvalidAddress(adrr)),
@@ -186,7 +261,7 @@ impl Processor {
 
     pub fn pixel(&self, index: usize) -> Result<(usize, usize, usize, usize)> {
         // Pegar de 4 em 4 valores (RGBA)
-        if index % 4 == 0 && index < self.vram.len() {
+        if index.is_multiple_of(4) && index < self.vram.len() {
             Ok((
                 self.vram[index],
                 self.vram[index + 1],
@@ -200,7 +275,7 @@ impl Processor {
 
     pub fn set_pixel(&mut self, index: usize, rgba: (usize, usize, usize, usize)) -> Result<()> {
         // Pegar de 4 em 4 valores (RGBA)
-        if index % 4 == 0 && index < self.vram.len() {
+        if index.is_multiple_of(4) && index < self.vram.len() {
             self.vram[index] = rgba.0;
             self.vram[index + 1] = rgba.1;
             self.vram[index + 2] = rgba.2;
@@ -224,9 +299,9 @@ impl Processor {
     /// use crate::processor::Processor;
     ///
     /// let mut p = Processor::with_capacity(10);
-    /// assert_eq!(0x0, p.reg(0).unwrap());
+    /// assert_eq!(0x0, p.reg(0).unwrap().value());
     /// ```
-    pub fn reg(&self, n: MemoryCell) -> Result<MemoryCell> {
+    pub fn reg(&self, n: MemoryCell) -> Result<Word> {
         match self.registers.get(n) {
             Some(&r) => Ok(r),
             None => Err(ProcessorError::InvalidRegister(n)),
@@ -246,14 +321,15 @@ impl Processor {
     /// use crate::processor::Processor;
     ///
     /// let mut p = Processor::with_capacity(10);
-    /// assert_eq!(0x0, p.reg(0).unwrap());
-    /// p.set_reg(0, 0x1);
-    /// assert_eq!(0x1, p.reg(0).unwrap());
+    /// assert_eq!(0x0, p.reg(0).unwrap().value());
+    /// p.set_reg(0, isa::Word(0x1));
+    /// assert_eq!(0x1, p.reg(0).unwrap().value());
     /// ```
-    pub fn set_reg(&mut self, n: MemoryCell, v: MemoryCell) -> Result<()> {
+    pub fn set_reg(&mut self, n: MemoryCell, v: Word) -> Result<()> {
         match self.registers.get_mut(n) {
             Some(r) => {
                 *r = v;
+                self.registers_initialized[n] = true;
                 Ok(())
             }
             None => Err(ProcessorError::InvalidRegister(n)),
@@ -331,7 +407,7 @@ impl Processor {
     }
 
     /// Retorna o valor do registrador especial *Instruction Register*.
-    pub fn ir(&self) -> MemoryCell {
+    pub fn ir(&self) -> Word {
         self.ir
     }
 
@@ -377,11 +453,10 @@ impl Processor {
     ///
     /// - [`ProcessorError::StackOverflow`] caso o resultado seja menor que o topo da pilha.
     /// - [`ProcessorError::StackUnderflow`] caso o resultado seja maior que a base da pilha.
-    /// - [`ProcessorError::InvalidAddress`] caso o resultado seja negativo.
     pub fn dec_sp(&mut self, v: usize) -> Result<()> {
         match self.sp.checked_sub(v) {
             Some(r) => self.set_sp(r),
-            None => Err(ProcessorError::InvalidAddress(0)), // arrumar
+            None => Err(ProcessorError::StackOverflow(self.sp)),
         }
     }
 
@@ -418,14 +493,24 @@ impl Processor {
     }
 
     pub fn set_status(&mut self, status: ProcessorStatus) {
-        *self.status.lock().expect("Falha ao trocar o status atual") = status
+        self.status = status
     }
 
     pub fn status(&self) -> ProcessorStatus {
-        *self.status.lock().expect("Falha ao acessar o status atual")
+        self.status
     }
 
-    #[warn(missing_docs)]
+    /// Retorna o número de ciclos de *clock* executados desde o último *reset*, segundo o custo
+    /// de cada instrução em [`Instruction::cycles`].
+    pub fn cycles(&self) -> u64 {
+        self.cycles
+    }
+
+    /// Limpa as *flags* do *Flag Register* afetadas pelas operações da ULA.
+    ///
+    /// # Erros
+    ///
+    /// Retorna o erro [`ProcessorError::InvalidFlag`] caso algum índice seja inválido.
     pub fn ula_operation(&mut self) -> Result<()> {
         self.set_fr(isa::FlagIndex::GREATER, false)?;
         self.set_fr(isa::FlagIndex::LESSER, false)?;
@@ -444,9 +529,9 @@ impl Processor {
     ///
     /// Esta função pode retornar qualquer um dos erros abaixo:
     ///
-    /// - [`ProcError::InvalidMemoryIndex`] - Caso a etapa de busca ocorra sobre um índice
-    /// inválido.
-    /// - [`ProcError::MaximumMemoryReached`] - Caso o limite de memória seja atingido.
+    /// - [`ProcessorError::InvalidAddress`] - Caso a etapa de busca ocorra sobre um índice
+    ///   inválido.
+    /// - [`ProcessorError::SegmentationFault`] - Caso o limite de memória seja atingido.
     fn fetch_stage(&mut self) -> Result<()> {
         self.ir = self.mem(self.pc)?;
         debug!(
@@ -464,12 +549,12 @@ impl Processor {
     /// Esta função retorna o erro [`ProcessorError::InvalidInstruction`] caso a instrução seja
     /// inválida.
     fn decode_stage(&mut self) -> Result<Instruction> {
-        self.rx = isa::bits(self.ir, 7..=9);
-        self.ry = isa::bits(self.ir, 4..=6);
-        self.rz = isa::bits(self.ir, 1..=3);
+        self.rx = self.ir.bits(7..=9).into();
+        self.ry = self.ir.bits(4..=6).into();
+        self.rz = self.ir.bits(1..=3).into();
 
-        Instruction::get_instruction(self.ir)
-            .map_err(|_| ProcessorError::InvalidInstruction(self.ir))
+        Instruction::get_instruction(self.ir.as_usize())
+            .map_err(|_| ProcessorError::InvalidInstruction(self.ir.as_usize()))
     }
 
     /// Realiza a etapa de execução do processador.
@@ -486,60 +571,249 @@ impl Processor {
     ///
     /// # Erros
     ///
-    /// Esta função pode retornar qualquer erro presente em [`ProcessorError`].
+    /// Esta função pode retornar qualquer erro presente em [`ProcessorError`], envolvido em
+    /// [`ProcessorError::Context`] com o endereço da instrução, o IR, a instrução decodificada e
+    /// o número do ciclo em que a falha ocorreu.
     pub fn instruction_cicle(&mut self) -> Result<()> {
-        self.fetch_stage()?;
-        let inst = self.decode_stage()?;
-        self.execution_stage(inst)
+        let pc = self.pc;
+        let cycle = self.cycles;
+        self.instruction_addr = pc;
+
+        let result = self.fetch_stage().and_then(|_| {
+            let inst = self.decode_stage()?;
+            self.mark_executed(pc, inst.length());
+            self.check_registers(inst)?;
+            self.profile_instruction(pc, inst);
+            self.execution_stage(inst)?;
+            self.cycles += inst.cycles();
+            Ok(())
+        });
+
+        result.map_err(|e| ProcessorError::Context {
+            context: Box::new(self.error_context(pc, cycle)),
+            error: Box::new(e),
+        })
+    }
+
+    /// Monta o contexto de uma falha na instrução em `pc`.
+    fn error_context(&self, pc: MemoryCell, cycle: u64) -> ErrorContext {
+        let length = self
+            .mem(pc)
+            .ok()
+            .and_then(|w| Instruction::get_instruction(w.as_usize()).ok())
+            .map(|i| i.length())
+            .unwrap_or(1);
+        let words: Vec<MemoryCell> = (pc..pc + length)
+            .map_while(|a| self.mem(a).ok().map(MemoryCell::from))
+            .collect();
+
+        ErrorContext {
+            pc,
+            ir: self.ir,
+            instruction: isa::disasm::decode(&words).ok(),
+            cycle,
+            backtrace: self.backtrace_at(pc),
+        }
     }
 
-    #[warn(missing_docs)]
-    pub fn load_memory(&mut self, memory: &[MemoryCell]) -> Result<()> {
-        match memory.len() {
-            MEMORY_SIZE => {
-                self.ram.clear();
-                self.ram.copy_from_slice(memory);
-                Ok(())
+    /// Executa instruções até gastar pelo menos `cycles` ciclos de *clock*, parando antes caso
+    /// o processador deixe o status [`ProcessorStatus::Running`] (instruções `HALT` e `BREAKP`)
+    /// ou caso `stop` seja marcado. Retorna o número de instruções executadas com sucesso e o
+    /// resultado do lote.
+    ///
+    /// # Erros
+    ///
+    /// O resultado pode conter qualquer erro presente em [`ProcessorError`]. Nesse caso, a
+    /// contagem inclui as instruções executadas antes da que falhou.
+    pub fn run_batch(&mut self, cycles: u64, stop: &AtomicBool) -> (usize, Result<()>) {
+        self.run_batch_until(cycles, stop, |_| false)
+    }
+
+    /// Igual a [`Processor::run_batch`], mas também para assim que `done` for verdadeiro após
+    /// uma instrução.
+    ///
+    /// # Erros
+    ///
+    /// O resultado pode conter qualquer erro presente em [`ProcessorError`], como em
+    /// [`Processor::run_batch`].
+    pub fn run_batch_until(
+        &mut self,
+        cycles: u64,
+        stop: &AtomicBool,
+        done: impl Fn(&Self) -> bool,
+    ) -> (usize, Result<()>) {
+        let end = self.cycles + cycles;
+        let mut executed = 0;
+
+        while self.cycles < end {
+            if stop.load(Ordering::Relaxed) {
+                break;
             }
-            _ => Err(ProcessorError::Generic {
-                title: "Tamanho inválido de memória".to_string(),
-                description:
-                    "Uma memória de tamanho não permitido tentou ser carregada no processador."
-                        .to_string(),
-            }),
+
+            if let Err(e) = self.instruction_cicle() {
+                return (executed, Err(e));
+            }
+            executed += 1;
+
+            if self.status != ProcessorStatus::Running || done(self) {
+                break;
+            }
+        }
+
+        (executed, Ok(()))
+    }
+
+    /// Carrega a imagem `memory` a partir do endereço 0, preenchendo o restante da memória com
+    /// zeros.
+    ///
+    /// # Erros
+    ///
+    /// Retorna o erro [`ProcessorError::InvalidAddress`] com o primeiro endereço que não cabe
+    /// na memória caso a imagem seja maior que ela.
+    ///
+    /// # Exemplo
+    ///
+    /// ```
+    /// use crate::processor::Processor;
+    /// use isa::Word;
+    ///
+    /// let mut p = Processor::with_capacity(10);
+    /// p.load_memory(&[Word(1), Word(2), Word(3)]).unwrap();
+    /// assert_eq!(Word(3), p.mem(2).unwrap());
+    /// assert!(p.load_memory(&[Word::ZERO; 11]).is_err());
+    /// ```
+    pub fn load_memory(&mut self, memory: &[Word]) -> Result<()> {
+        self.load_memory_at(0, memory, Fill::Zero)
+    }
+
+    /// Carrega a imagem `memory` a partir do endereço `base`. O restante da memória é zerado ou
+    /// preservado de acordo com `fill`.
+    ///
+    /// A imagem fica guardada para que [`Processor::reload`] possa restaurá-la depois.
+    ///
+    /// # Erros
+    ///
+    /// Retorna o erro [`ProcessorError::InvalidAddress`] com o primeiro endereço que não cabe
+    /// na memória caso a imagem ultrapasse o seu fim. Nesse caso a memória não é alterada.
+    ///
+    /// # Exemplo
+    ///
+    /// ```
+    /// use crate::processor::{Fill, Processor};
+    /// use isa::Word;
+    ///
+    /// let mut p = Processor::with_capacity(10);
+    /// let mem = |p: &Processor, addrs: &[usize]| -> Vec<u16> {
+    ///     addrs.iter().map(|&a| p.mem(a).unwrap().value()).collect()
+    /// };
+    ///
+    /// p.set_mem(0, Word(7)).unwrap();
+    /// p.load_memory_at(4, &[Word(1), Word(2)], Fill::Preserve).unwrap();
+    /// assert_eq!(vec![7, 0, 0, 0, 1, 2], mem(&p, &[0, 1, 2, 3, 4, 5]));
+    ///
+    /// p.load_memory_at(8, &[Word(3)], Fill::Zero).unwrap();
+    /// assert_eq!(vec![0, 0, 3], mem(&p, &[0, 4, 8]));
+    ///
+    /// assert!(p.load_memory_at(8, &[Word(1); 3], Fill::Zero).is_err());
+    /// assert_eq!(vec![3], mem(&p, &[8]));
+    /// ```
+    pub fn load_memory_at(&mut self, base: MemoryCell, memory: &[Word], fill: Fill) -> Result<()> {
+        let end = base + memory.len();
+        if end > self.ram.len() {
+            return Err(ProcessorError::InvalidAddress(end.max(self.ram.len())));
+        }
+
+        let image = Image {
+            base,
+            words: memory.to_vec(),
+            fill,
+        };
+        self.apply_image(&image);
+        self.image = Some(image);
+        Ok(())
+    }
+
+    /// Escreve `image` na memória. As palavras da imagem passam a ser consideradas escritas, e
+    /// nenhum endereço é considerado executado.
+    fn apply_image(&mut self, image: &Image) {
+        let range = image.base..image.base + image.words.len();
+
+        if image.fill == Fill::Zero {
+            self.ram.fill(Word::ZERO);
+            self.initialized.fill(false);
         }
+        self.ram[range.clone()].copy_from_slice(&image.words);
+        self.initialized[range].fill(true);
+        self.executed.fill(false);
+        self.writes.fill(None);
     }
 
     #[warn(missing_docs)]
     fn reset_fields(&mut self) {
         self.pc = *isa::memory::layout::ADDR_PROG_AND_VAR.start();
-        self.ir = 0;
+        self.ir = Word::ZERO;
         self.sp = *isa::memory::layout::ADDR_STACK.end();
         self.fr = [false; isa::BITS_ADDRESS];
         self.rx = 0;
         self.ry = 0;
         self.rz = 0;
-        *self
-            .status
-            .lock()
-            .expect("Falha ao trocar o status para o seu valor padrão") = ProcessorStatus::Debug
+        self.status = ProcessorStatus::Debug;
+        self.cycles = 0;
+        self.warnings.clear();
+        self.call_stack.clear();
+        self.pushes.clear();
+        self.writes.fill(None);
+        self.reset_profile();
+    }
+
+    /// Reinicia os registradores de uso geral e os registradores especiais, sem alterar a
+    /// memória.
+    pub fn reset_registers(&mut self) {
+        self.registers = [Word::ZERO; NUM_REGISTERS];
+        self.registers_initialized = [false; NUM_REGISTERS];
+        self.reset_fields();
     }
 
-    #[warn(missing_docs)]
-    pub fn reset(&mut self, memory: &[MemoryCell]) -> Result<()> {
-        self.reset_fields();
-        match memory.len() {
-            MEMORY_SIZE => {
-                self.ram.clear();
-                self.ram.copy_from_slice(memory);
-                Ok(())
+    /// Reinicia os registradores e carrega a imagem `memory` a partir do endereço 0, preenchendo
+    /// o restante da memória com zeros.
+    ///
+    /// # Erros
+    ///
+    /// Retorna o erro [`ProcessorError::InvalidAddress`] caso a imagem seja maior que a memória.
+    pub fn reset(&mut self, memory: &[Word]) -> Result<()> {
+        self.load_memory(memory)?;
+        self.reset_registers();
+        Ok(())
+    }
+
+    /// Reinicia os registradores e restaura a última imagem carregada, desfazendo as alterações
+    /// feitas na memória pelo programa. Retorna `false` caso nenhuma imagem tenha sido
+    /// carregada; nesse caso apenas os registradores são reiniciados.
+    ///
+    /// # Exemplo
+    ///
+    /// ```
+    /// use crate::processor::Processor;
+    /// use isa::Word;
+    ///
+    /// let mut p = Processor::with_capacity(10);
+    /// p.load_memory(&[Word(1), Word(2), Word(3)]).unwrap();
+    /// p.set_mem(0, Word(42)).unwrap();
+    /// p.set_mem(5, Word(42)).unwrap();
+    ///
+    /// assert!(p.reload());
+    /// assert_eq!(Word(1), p.mem(0).unwrap());
+    /// assert_eq!(Word::ZERO, p.mem(5).unwrap());
+    /// ```
+    pub fn reload(&mut self) -> bool {
+        self.reset_registers();
+        match self.image.take() {
+            Some(image) => {
+                self.apply_image(&image);
+                self.image = Some(image);
+                true
             }
-            _ => Err(ProcessorError::Generic {
-                title: "Tamanho inválido de memória".to_string(),
-                description:
-                    "Uma memória de tamanho não permitido tentou ser carregada no processador."
-                        .to_string(),
-            }),
+            None => false,
         }
     }
 }
@@ -1,11 +1,4 @@
-use std::{
-    sync::{Arc, Mutex},
-    thread,
-    time::Duration,
-};
-
 use env_logger::{Builder, Target};
-use processor::Processor;
 
 fn main() {
     std::env::set_var("RUST_LOG", "debug");
@@ -0,0 +1,97 @@
+//! Estado de cada endereço da memória, para o mapa da memória do simulador.
+//!
+//! Toda escrita por [`Processor::set_mem`] guarda o ciclo de *clock* em que ocorreu. As palavras
+//! carregadas por uma imagem não contam como escritas.
+
+use isa::{MemoryCell, Word};
+
+use crate::{errors::ProcessorError, Processor};
+
+type Result<T> = std::result::Result<T, ProcessorError>;
+
+/// Valor e uso de um endereço da memória.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CellUsage {
+    pub value: Word,
+    /// Indica se o endereço já foi executado como instrução ou operando.
+    pub executed: bool,
+    /// Ciclo de *clock* da última escrita, caso o endereço tenha sido escrito desde o último
+    /// *reset*.
+    pub written_at: Option<u64>,
+}
+
+impl CellUsage {
+    /// Verifica se o endereço foi escrito a partir do ciclo `cycle`, inclusive.
+    pub fn written_since(&self, cycle: u64) -> bool {
+        self.written_at.is_some_and(|at| at >= cycle)
+    }
+}
+
+impl Processor {
+    /// Registra uma escrita no endereço `addr`, no ciclo atual.
+    pub(crate) fn record_write(&mut self, addr: MemoryCell) {
+        self.writes[addr] = Some(self.cycles);
+    }
+
+    /// Retorna o valor e o uso do endereço `addr`.
+    ///
+    /// # Erros
+    ///
+    /// - [`ProcessorError::InvalidAddress`] caso o endereço seja inválido.
+    ///
+    /// # Exemplo
+    ///
+    /// ```
+    /// use processor::Processor;
+    /// use isa::Word;
+    ///
+    /// // STOREI R0, R1
+    /// let mut p = Processor::new();
+    /// p.load_memory(&[Word(0b1111010000010000)]).unwrap();
+    /// p.set_reg(0, Word(100)).unwrap();
+    /// p.set_reg(1, Word(7)).unwrap();
+    /// p.instruction_cicle().unwrap();
+    ///
+    /// assert!(p.usage(0).unwrap().executed);
+    /// let usage = p.usage(100).unwrap();
+    /// assert_eq!(Word(7), usage.value);
+    /// assert!(usage.written_since(0));
+    /// ```
+    pub fn usage(&self, addr: MemoryCell) -> Result<CellUsage> {
+        Ok(CellUsage {
+            value: self.mem(addr)?,
+            executed: self.executed[addr],
+            written_at: self.writes[addr],
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_usage() {
+        let mut p = Processor::new();
+        p.load_memory(&[Word(1), Word(2)]).unwrap();
+        assert_eq!(
+            CellUsage {
+                value: Word(1),
+                executed: false,
+                written_at: None,
+            },
+            p.usage(0).unwrap()
+        );
+
+        p.set_mem(40, Word(3)).unwrap();
+        let usage = p.usage(40).unwrap();
+        assert_eq!(Some(0), usage.written_at);
+        assert!(usage.written_since(0));
+        assert!(!usage.written_since(1));
+
+        // Uma nova imagem apaga as escritas anteriores.
+        p.load_memory(&[Word(1)]).unwrap();
+        assert_eq!(None, p.usage(40).unwrap().written_at);
+        assert!(p.usage(crate::MEMORY_SIZE).is_err());
+    }
+}
@@ -0,0 +1,187 @@
+//! Alterações manuais da memória, feitas pela interface, que podem ser desfeitas.
+//!
+//! Um [`Patch`] guarda as palavras escritas a partir de um endereço e as que estavam lá antes
+//! dele. O [`PatchHistory`] mantém as pilhas de desfazer e refazer dessas alterações.
+
+use isa::{MemoryCell, Word};
+
+use crate::{errors::ProcessorError, Processor};
+
+type Result<T> = std::result::Result<T, ProcessorError>;
+
+/// Alteração de palavras consecutivas da memória.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Patch {
+    pub addr: MemoryCell,
+    pub old: Vec<Word>,
+    pub new: Vec<Word>,
+}
+
+impl Processor {
+    /// Escreve `words` a partir de `addr` e retorna o [`Patch`] que desfaz a escrita.
+    ///
+    /// # Erros
+    ///
+    /// Retorna [`ProcessorError::InvalidAddress`] com o primeiro endereço fora da memória. Nesse
+    /// caso, nenhuma palavra é escrita.
+    ///
+    /// # Exemplo
+    ///
+    /// ```
+    /// use processor::Processor;
+    /// use isa::Word;
+    ///
+    /// let mut p = Processor::with_capacity(4);
+    /// let patch = p.patch(1, &[Word(7), Word(8)]).unwrap();
+    /// assert_eq!(Word(8), p.mem(2).unwrap());
+    ///
+    /// p.unpatch(&patch).unwrap();
+    /// assert_eq!(Word::ZERO, p.mem(2).unwrap());
+    /// assert!(p.patch(3, &[Word(1), Word(2)]).is_err());
+    /// ```
+    pub fn patch(&mut self, addr: MemoryCell, words: &[Word]) -> Result<Patch> {
+        let old = (addr..addr + words.len())
+            .map(|a| self.mem(a))
+            .collect::<Result<Vec<Word>>>()?;
+
+        self.write_words(addr, words)?;
+        Ok(Patch {
+            addr,
+            old,
+            new: words.to_vec(),
+        })
+    }
+
+    /// Restaura as palavras sobrescritas por `patch`.
+    ///
+    /// # Erros
+    ///
+    /// Retorna [`ProcessorError::InvalidAddress`] caso o *patch* não caiba na memória.
+    pub fn unpatch(&mut self, patch: &Patch) -> Result<()> {
+        self.write_words(patch.addr, &patch.old)
+    }
+
+    fn write_words(&mut self, addr: MemoryCell, words: &[Word]) -> Result<()> {
+        for (i, &w) in words.iter().enumerate() {
+            self.set_mem(addr + i, w)?;
+        }
+        Ok(())
+    }
+}
+
+/// Pilhas de desfazer e refazer de [`Patch`].
+#[derive(Debug, Clone, Default)]
+pub struct PatchHistory {
+    undo: Vec<Patch>,
+    redo: Vec<Patch>,
+}
+
+impl PatchHistory {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Escreve `words` a partir de `addr` e guarda a alteração para ser desfeita. A pilha de
+    /// refazer é descartada.
+    ///
+    /// # Erros
+    ///
+    /// Retorna o erro de [`Processor::patch`].
+    pub fn apply(&mut self, p: &mut Processor, addr: MemoryCell, words: &[Word]) -> Result<()> {
+        let patch = p.patch(addr, words)?;
+        self.undo.push(patch);
+        self.redo.clear();
+        Ok(())
+    }
+
+    /// Desfaz a última alteração e retorna o endereço dela, ou [`None`] se não houver
+    /// alterações.
+    ///
+    /// # Erros
+    ///
+    /// Retorna o erro de [`Processor::unpatch`].
+    pub fn undo(&mut self, p: &mut Processor) -> Result<Option<MemoryCell>> {
+        let Some(patch) = self.undo.pop() else {
+            return Ok(None);
+        };
+
+        p.unpatch(&patch)?;
+        let addr = patch.addr;
+        self.redo.push(patch);
+        Ok(Some(addr))
+    }
+
+    /// Refaz a última alteração desfeita e retorna o endereço dela, ou [`None`] se não houver
+    /// alterações desfeitas.
+    ///
+    /// # Erros
+    ///
+    /// Retorna o erro de [`Processor::patch`].
+    pub fn redo(&mut self, p: &mut Processor) -> Result<Option<MemoryCell>> {
+        let Some(patch) = self.redo.pop() else {
+            return Ok(None);
+        };
+
+        let redone = p.patch(patch.addr, &patch.new)?;
+        let addr = redone.addr;
+        self.undo.push(redone);
+        Ok(Some(addr))
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    /// Descarta todas as alterações, por exemplo ao carregar outro programa.
+    pub fn clear(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_patch_history() {
+        let mut p = Processor::with_capacity(8);
+        let mut history = PatchHistory::new();
+
+        history.apply(&mut p, 2, &[Word(1), Word(2)]).unwrap();
+        history.apply(&mut p, 3, &[Word(3)]).unwrap();
+        assert_eq!(Word(3), p.mem(3).unwrap());
+
+        assert_eq!(Some(3), history.undo(&mut p).unwrap());
+        assert_eq!(Word(2), p.mem(3).unwrap());
+        assert_eq!(Some(2), history.undo(&mut p).unwrap());
+        assert_eq!(Word::ZERO, p.mem(2).unwrap());
+        assert_eq!(None, history.undo(&mut p).unwrap());
+
+        assert_eq!(Some(2), history.redo(&mut p).unwrap());
+        assert_eq!(Word(1), p.mem(2).unwrap());
+        assert!(history.can_redo());
+
+        // Uma nova alteração descarta as desfeitas.
+        history.apply(&mut p, 0, &[Word(9)]).unwrap();
+        assert!(!history.can_redo());
+        assert_eq!(None, history.redo(&mut p).unwrap());
+    }
+
+    #[test]
+    fn test_patch_out_of_memory() {
+        let mut p = Processor::with_capacity(4);
+        let mut history = PatchHistory::new();
+
+        assert_eq!(
+            Err(ProcessorError::InvalidAddress(4)),
+            history.apply(&mut p, 3, &[Word(1), Word(2)])
+        );
+        assert_eq!(Word::ZERO, p.mem(3).unwrap());
+        assert!(!history.can_undo());
+    }
+}
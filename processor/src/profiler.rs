@@ -0,0 +1,538 @@
+//! Perfil de execução dos programas.
+//!
+//! Com o perfil ativado por [`Processor::start_profiling`], cada instrução executada é contada
+//! por endereço e por [`Instruction`], e seus ciclos são atribuídos às sub-rotinas da pilha de
+//! chamadas ([`crate::call_stack`]). As sub-rotinas são identificadas pelo endereço chamado; as
+//! instruções executadas fora de qualquer chamada pertencem à rotina [`ROOT`].
+//!
+//! O perfil pode ser exportado como tabela ([`Profile::write_flat`]) ou no formato de pilhas
+//! colapsadas ([`Profile::write_collapsed`]), lido por ferramentas de *flamegraph*.
+
+use std::{
+    collections::{HashMap, VecDeque},
+    io::{self, Write},
+};
+
+use isa::{memory::layout, Instruction, MemoryCell};
+
+use crate::{call_stack::CallFrame, Processor};
+
+/// Rotina à qual pertencem as instruções executadas fora de qualquer chamada: o início do
+/// programa.
+pub const ROOT: MemoryCell = *layout::ADDR_PROG_AND_VAR.start();
+
+/// Contadores de uma [`Instruction`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct InstructionCount {
+    pub executions: u64,
+    pub cycles: u64,
+}
+
+/// Contadores de uma sub-rotina.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Routine {
+    /// Número de vezes que a sub-rotina foi chamada.
+    pub calls: u64,
+    /// Ciclos gastos pelas instruções da própria sub-rotina.
+    pub self_cycles: u64,
+    /// Ciclos gastos pela sub-rotina e pelas sub-rotinas chamadas por ela. Chamadas recursivas
+    /// são contadas uma única vez.
+    pub total_cycles: u64,
+}
+
+/// Pilha de chamadas distinta, identificada pela posição em [`Profile::stacks`].
+#[derive(Debug, Clone, Copy)]
+struct Stack {
+    /// Pilha sem a chamada mais recente; a pilha [`ROOT_STACK`] não tem pilha anterior.
+    parent: usize,
+    /// Sub-rotina da chamada mais recente.
+    target: MemoryCell,
+    /// Ciclos gastos com exatamente esta pilha em andamento.
+    cycles: u64,
+}
+
+/// Pilha que contém apenas a rotina [`ROOT`].
+const ROOT_STACK: usize = 0;
+
+/// Chamada em andamento, acompanhando a pilha de chamadas do processador.
+#[derive(Debug, Clone, Copy)]
+struct Frame {
+    /// Pilha de chamadas até esta chamada, inclusive.
+    stack: usize,
+    /// Indica se esta é a chamada mais antiga da sub-rotina na pilha, a única que conta os
+    /// ciclos totais da sub-rotina.
+    first: bool,
+}
+
+/// Perfil de execução acumulado desde [`Processor::start_profiling`] ou do último *reset*.
+#[derive(Debug, Clone, Default)]
+pub struct Profile {
+    executions: Vec<u64>,
+    instructions: HashMap<Instruction, InstructionCount>,
+    routines: HashMap<MemoryCell, Routine>,
+    stacks: Vec<Stack>,
+    // Posição em `stacks` de cada pilha, pela pilha anterior e pela sub-rotina chamada.
+    stack_ids: HashMap<(usize, MemoryCell), usize>,
+    // Chamadas em andamento e o número de chamadas em andamento de cada sub-rotina, mantidos
+    // por `enter` e `leave` para que cada instrução não percorra a pilha de chamadas.
+    frames: Vec<Frame>,
+    active: HashMap<MemoryCell, usize>,
+    total_instructions: u64,
+    total_cycles: u64,
+}
+
+/// Nome de uma rotina: o símbolo no seu endereço ou, na falta dele, o endereço.
+fn routine_name(addr: MemoryCell, symbols: Option<&HashMap<String, MemoryCell>>) -> String {
+    symbols
+        .and_then(|symbols| {
+            symbols
+                .iter()
+                .filter(|(_, &a)| a == addr)
+                .map(|(name, _)| name)
+                .min()
+        })
+        .cloned()
+        .unwrap_or_else(|| format!("{:#06x}", addr))
+}
+
+impl Profile {
+    /// Cria um perfil vazio, com as chamadas `call_stack` já em andamento.
+    fn new(memory_size: usize, call_stack: &VecDeque<CallFrame>) -> Self {
+        let mut profile = Self {
+            executions: vec![0; memory_size],
+            stacks: vec![Stack {
+                parent: ROOT_STACK,
+                target: ROOT,
+                cycles: 0,
+            }],
+            active: HashMap::from([(ROOT, 1)]),
+            ..Self::default()
+        };
+        for frame in call_stack {
+            profile.enter(frame.target);
+        }
+        profile
+    }
+
+    /// Pilha de chamadas atual.
+    fn current(&self) -> usize {
+        self.frames.last().map_or(ROOT_STACK, |f| f.stack)
+    }
+
+    /// Registra a entrada na sub-rotina `target`.
+    fn enter(&mut self, target: MemoryCell) {
+        let parent = self.current();
+        let stack = *self.stack_ids.entry((parent, target)).or_insert_with(|| {
+            self.stacks.push(Stack {
+                parent,
+                target,
+                cycles: 0,
+            });
+            self.stacks.len() - 1
+        });
+
+        let active = self.active.entry(target).or_default();
+        *active += 1;
+        self.frames.push(Frame {
+            stack,
+            first: *active == 1,
+        });
+    }
+
+    /// Registra a saída da sub-rotina mais recente.
+    fn leave(&mut self) {
+        let Some(frame) = self.frames.pop() else {
+            return;
+        };
+        let target = self.stacks[frame.stack].target;
+        if let Some(active) = self.active.get_mut(&target) {
+            *active -= 1;
+        }
+    }
+
+    /// Refaz as chamadas em andamento a partir de `call_stack`.
+    fn resync(&mut self, call_stack: &VecDeque<CallFrame>) {
+        while !self.frames.is_empty() {
+            self.leave();
+        }
+        for frame in call_stack {
+            self.enter(frame.target);
+        }
+    }
+
+    /// Conta a execução de `instruction`, no endereço `pc`, na pilha de chamadas atual.
+    fn record(&mut self, pc: MemoryCell, instruction: Instruction) {
+        let cycles = instruction.cycles();
+
+        if let Some(count) = self.executions.get_mut(pc) {
+            *count += 1;
+        }
+        let count = self.instructions.entry(instruction).or_default();
+        count.executions += 1;
+        count.cycles += cycles;
+        self.total_instructions += 1;
+        self.total_cycles += cycles;
+
+        let current = self.current();
+        self.stacks[current].cycles += cycles;
+        self.routines
+            .entry(self.stacks[current].target)
+            .or_default()
+            .self_cycles += cycles;
+
+        // A rotina `ROOT` está sempre na base da pilha, mesmo quando também é chamada.
+        self.routines.entry(ROOT).or_default().total_cycles += cycles;
+        for frame in self.frames.iter().filter(|f| f.first) {
+            let target = self.stacks[frame.stack].target;
+            self.routines.entry(target).or_default().total_cycles += cycles;
+        }
+    }
+
+    /// Sub-rotinas da pilha `stack`, da mais antiga para a mais recente.
+    fn path(&self, mut stack: usize) -> Vec<MemoryCell> {
+        let mut path = vec![self.stacks[stack].target];
+        while stack != ROOT_STACK {
+            stack = self.stacks[stack].parent;
+            path.push(self.stacks[stack].target);
+        }
+        path.reverse();
+        path
+    }
+
+    /// Número de vezes que a instrução no endereço `addr` foi executada.
+    pub fn executions(&self, addr: MemoryCell) -> u64 {
+        self.executions.get(addr).copied().unwrap_or(0)
+    }
+
+    /// Maior número de execuções de um mesmo endereço.
+    pub fn max_executions(&self) -> u64 {
+        self.executions.iter().copied().max().unwrap_or(0)
+    }
+
+    /// Contadores de `instruction`.
+    pub fn instruction(&self, instruction: Instruction) -> InstructionCount {
+        self.instructions
+            .get(&instruction)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Contadores das sub-rotinas, indexados pelo endereço chamado.
+    pub fn routines(&self) -> &HashMap<MemoryCell, Routine> {
+        &self.routines
+    }
+
+    pub fn total_instructions(&self) -> u64 {
+        self.total_instructions
+    }
+
+    pub fn total_cycles(&self) -> u64 {
+        self.total_cycles
+    }
+
+    /// Escreve o perfil como tabelas de sub-rotinas e de instruções, ordenadas pelos ciclos
+    /// gastos. As sub-rotinas são identificadas pelos rótulos de `symbols`, quando presentes.
+    pub fn write_flat<W: Write>(
+        &self,
+        w: &mut W,
+        symbols: Option<&HashMap<String, MemoryCell>>,
+    ) -> io::Result<()> {
+        let percent = |cycles: u64| match self.total_cycles {
+            0 => 0.0,
+            total => cycles as f64 * 100.0 / total as f64,
+        };
+
+        writeln!(
+            w,
+            "# {} instruções, {} ciclos",
+            self.total_instructions, self.total_cycles
+        )?;
+
+        writeln!(w)?;
+        writeln!(
+            w,
+            "{:>7} {:>12} {:>12} {:>10}  rotina",
+            "%total", "próprios", "totais", "chamadas"
+        )?;
+        let mut routines: Vec<_> = self.routines.iter().collect();
+        routines
+            .sort_by(|(a1, r1), (a2, r2)| r2.total_cycles.cmp(&r1.total_cycles).then(a1.cmp(a2)));
+        for (&addr, routine) in routines {
+            writeln!(
+                w,
+                "{:>6.2}% {:>12} {:>12} {:>10}  {}",
+                percent(routine.total_cycles),
+                routine.self_cycles,
+                routine.total_cycles,
+                routine.calls,
+                routine_name(addr, symbols)
+            )?;
+        }
+
+        writeln!(w)?;
+        writeln!(
+            w,
+            "{:>7} {:>12} {:>12}  instrução",
+            "%total", "ciclos", "execuções"
+        )?;
+        let mut instructions: Vec<_> = self.instructions.iter().collect();
+        instructions.sort_by(|(i1, c1), (i2, c2)| {
+            c2.cycles
+                .cmp(&c1.cycles)
+                .then_with(|| i1.to_string().cmp(&i2.to_string()))
+        });
+        for (instruction, count) in instructions {
+            writeln!(
+                w,
+                "{:>6.2}% {:>12} {:>12}  {}",
+                percent(count.cycles),
+                count.cycles,
+                count.executions,
+                instruction
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /// Escreve o perfil no formato de pilhas colapsadas, uma linha por pilha de chamadas com os
+    /// ciclos gastos nela, como em `main;print;putc 120`.
+    ///
+    /// # Exemplo
+    ///
+    /// ```
+    /// use std::collections::HashMap;
+    /// use isa::Word;
+    /// use processor::Processor;
+    ///
+    /// // CALL 3; HALT; RTS
+    /// let program = [0b0000110000000000, 3, 0b0011110000000000, 0b0001000000000000];
+    /// let mut p = Processor::new();
+    /// p.load_memory(&program.map(Word)).unwrap();
+    /// p.start_profiling();
+    /// for _ in 0..3 {
+    ///     p.instruction_cicle().unwrap();
+    /// }
+    ///
+    /// let symbols = HashMap::from([("main".to_string(), 0), ("f".to_string(), 3)]);
+    /// let mut buf = Vec::new();
+    /// p.profile().unwrap().write_collapsed(&mut buf, Some(&symbols)).unwrap();
+    ///
+    /// assert_eq!("main 8\nmain;f 5\n", String::from_utf8(buf).unwrap());
+    /// ```
+    pub fn write_collapsed<W: Write>(
+        &self,
+        w: &mut W,
+        symbols: Option<&HashMap<String, MemoryCell>>,
+    ) -> io::Result<()> {
+        let mut stacks: Vec<(String, u64)> = self
+            .stacks
+            .iter()
+            .enumerate()
+            .filter(|(_, stack)| stack.cycles > 0)
+            .map(|(i, stack)| {
+                let names: Vec<String> = self
+                    .path(i)
+                    .into_iter()
+                    .map(|a| routine_name(a, symbols))
+                    .collect();
+                (names.join(";"), stack.cycles)
+            })
+            .collect();
+        stacks.sort();
+
+        for (stack, cycles) in stacks {
+            writeln!(w, "{} {}", stack, cycles)?;
+        }
+        Ok(())
+    }
+}
+
+impl Processor {
+    /// Ativa o perfil de execução, descartando o perfil anterior.
+    pub fn start_profiling(&mut self) {
+        self.profile = Some(Profile::new(self.ram.len(), &self.call_stack));
+    }
+
+    /// Desativa o perfil de execução, retornando o perfil acumulado.
+    pub fn stop_profiling(&mut self) -> Option<Profile> {
+        self.profile.take()
+    }
+
+    /// Retorna o perfil acumulado, caso o perfil de execução esteja ativo.
+    pub fn profile(&self) -> Option<&Profile> {
+        self.profile.as_ref()
+    }
+
+    /// Conta a execução de `instruction`, no endereço `pc`, caso o perfil esteja ativo.
+    pub(crate) fn profile_instruction(&mut self, pc: MemoryCell, instruction: Instruction) {
+        if let Some(profile) = &mut self.profile {
+            profile.record(pc, instruction);
+        }
+    }
+
+    /// Conta uma chamada da sub-rotina `target`, caso o perfil esteja ativo. Deve ser chamada
+    /// depois de empilhar a chamada em [`Processor::call_stack`].
+    pub(crate) fn profile_call(&mut self, target: MemoryCell) {
+        if let Some(profile) = &mut self.profile {
+            profile.routines.entry(target).or_default().calls += 1;
+            match profile.frames.len() + 1 == self.call_stack.len() {
+                true => profile.enter(target),
+                // A chamada mais antiga foi descartada ao atingir `MAX_CALL_DEPTH`.
+                false => profile.resync(&self.call_stack),
+            }
+        }
+    }
+
+    /// Registra a saída da sub-rotina atual, caso o perfil esteja ativo. Deve ser chamada
+    /// depois de desempilhar a chamada de [`Processor::call_stack`].
+    pub(crate) fn profile_return(&mut self) {
+        if let Some(profile) = &mut self.profile {
+            if profile.frames.len() > self.call_stack.len() {
+                profile.leave();
+            }
+        }
+    }
+
+    /// Descarta o perfil acumulado, mantendo o perfil de execução ativo.
+    pub(crate) fn reset_profile(&mut self) {
+        if self.profile.is_some() {
+            self.start_profiling();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use isa::Word;
+
+    use super::*;
+    use crate::ProcessorStatus;
+
+    const CALL: u16 = 0b0000110000000000;
+    const RTS: u16 = 0b0001000000000000;
+    const NOP: u16 = 0b0000000000000000;
+    const HALT: u16 = 0b0011110000000000;
+
+    /// Executa até o `HALT`: `main` chama `f` duas vezes, e `f` chama `g`.
+    fn profile() -> Profile {
+        // 0: CALL 7; 2: CALL 7; 4: HALT; 5: NOP; 6: RTS; 7: NOP; 8: CALL 5; 10: RTS
+        let program = [CALL, 7, CALL, 7, HALT, NOP, RTS, NOP, CALL, 5, RTS];
+        let mut p = Processor::new();
+        p.load_memory(&program.map(Word)).unwrap();
+        p.start_profiling();
+
+        p.set_status(ProcessorStatus::Running);
+        while p.status() == ProcessorStatus::Running {
+            p.instruction_cicle().unwrap();
+        }
+        p.stop_profiling().unwrap()
+    }
+
+    #[test]
+    fn test_profile_counts() {
+        let profile = profile();
+
+        assert_eq!(13, profile.total_instructions());
+        assert_eq!(2, profile.executions(7));
+        assert_eq!(1, profile.executions(4));
+        assert_eq!(0, profile.executions(9));
+        assert_eq!(2, profile.max_executions());
+        assert_eq!(
+            InstructionCount {
+                executions: 4,
+                cycles: 4 * Instruction::CALL.cycles(),
+            },
+            profile.instruction(Instruction::CALL)
+        );
+
+        let f = profile.routines()[&7];
+        let g = profile.routines()[&5];
+        assert_eq!(2, f.calls);
+        assert_eq!(2, g.calls);
+        assert_eq!(
+            2 * (Instruction::NOP.cycles() + Instruction::RTS.cycles()),
+            g.total_cycles
+        );
+        assert_eq!(f.self_cycles + g.total_cycles, f.total_cycles);
+        assert_eq!(
+            profile.total_cycles(),
+            profile.routines()[&ROOT].total_cycles
+        );
+    }
+
+    #[test]
+    fn test_profile_collapsed() {
+        let profile = profile();
+        let symbols = HashMap::from([
+            ("main".to_string(), 0),
+            ("g".to_string(), 5),
+            ("f".to_string(), 7),
+        ]);
+
+        let mut buf = Vec::new();
+        profile.write_collapsed(&mut buf, Some(&symbols)).unwrap();
+        let lines: Vec<String> = String::from_utf8(buf)
+            .unwrap()
+            .lines()
+            .map(String::from)
+            .collect();
+
+        let cycles = |path: &[MemoryCell]| {
+            (0..profile.stacks.len())
+                .find(|&i| profile.path(i) == path)
+                .map(|i| profile.stacks[i].cycles)
+                .unwrap()
+        };
+        assert_eq!(
+            vec![
+                format!("main {}", cycles(&[0])),
+                format!("main;f {}", cycles(&[0, 7])),
+                format!("main;f;g {}", cycles(&[0, 7, 5])),
+            ],
+            lines
+        );
+
+        let mut buf = Vec::new();
+        profile.write_collapsed(&mut buf, None).unwrap();
+        assert!(String::from_utf8(buf)
+            .unwrap()
+            .contains("0x0000;0x0007;0x0005 "));
+    }
+
+    #[test]
+    fn test_profile_inside_call() {
+        // 0: CALL 3; 2: HALT; 3: CALL 6; 5: RTS; 6: RTS
+        let program = [CALL, 3, HALT, CALL, 6, RTS, RTS];
+        let mut p = Processor::new();
+        p.load_memory(&program.map(Word)).unwrap();
+        p.instruction_cicle().unwrap();
+
+        // O perfil começa com `f` já em andamento.
+        p.start_profiling();
+        p.set_status(ProcessorStatus::Running);
+        while p.status() == ProcessorStatus::Running {
+            p.instruction_cicle().unwrap();
+        }
+        let profile = p.stop_profiling().unwrap();
+
+        let (call, rts) = (Instruction::CALL.cycles(), Instruction::RTS.cycles());
+        let f = profile.routines()[&3];
+        assert_eq!(0, f.calls);
+        assert_eq!(call + rts, f.self_cycles);
+        assert_eq!(call + 2 * rts, f.total_cycles);
+        assert_eq!(1, profile.routines()[&6].calls);
+
+        let mut buf = Vec::new();
+        profile.write_collapsed(&mut buf, None).unwrap();
+        assert_eq!(
+            format!(
+                "0x0000 {}\n0x0000;0x0003 {}\n0x0000;0x0003;0x0006 {}\n",
+                Instruction::HALT.cycles(),
+                call + rts,
+                rts
+            ),
+            String::from_utf8(buf).unwrap()
+        );
+    }
+}
@@ -0,0 +1,381 @@
+//! Políticas de proteção da memória por região de [`isa::memory::layout`].
+//!
+//! As instruções de acesso a dados (`LOAD`, `LOADI`, `STORE`, `STOREN` e `STOREI`) passam por
+//! [`Processor::read_data`] e [`Processor::write_data`], que detectam:
+//!
+//! - escritas em endereços que já foram executados como instrução ou operando;
+//! - leituras de endereços que nunca foram escritos nem carregados por uma imagem;
+//! - leituras de registradores que nunca foram escritos, verificadas antes da execução de cada
+//!   instrução por [`Processor::check_registers`];
+//! - acessos à área livre da pilha, abaixo do [`Processor::sp`];
+//! - escritas nas folgas reservadas no topo e na base da pilha.
+//!
+//! A busca de instruções e as operações de pilha (`PUSH`, `POP`, `CALL`, `RTS` e `RTI`) não são
+//! verificadas como acessos de dados; o `RTS` é verificado pela pilha de chamadas em
+//! [`crate::call_stack`]. O tratamento de cada violação depende do [`ProtectionMode`].
+
+use std::str::FromStr;
+
+use isa::{memory::layout, Instruction, MemoryCell, Word};
+use log::warn;
+use thiserror::Error;
+
+use crate::{errors::ProcessorError, Processor};
+
+type Result<T> = std::result::Result<T, ProcessorError>;
+
+/// Número máximo de avisos guardados em [`ProtectionMode::Warn`]. Os avisos seguintes são
+/// apenas registrados no *log*.
+pub const MAX_WARNINGS: usize = 1024;
+
+/// Tratamento das violações de proteção da memória.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ProtectionMode {
+    /// Nenhuma verificação é feita.
+    #[default]
+    Permissive,
+    /// As violações são guardadas em [`Processor::warnings`] e a execução continua.
+    Warn,
+    /// As violações interrompem a execução com [`ProcessorError::Protection`].
+    Strict,
+}
+
+impl ProtectionMode {
+    /// Identificador do modo, aceito por [`str::parse`].
+    pub fn name(&self) -> &'static str {
+        match self {
+            ProtectionMode::Permissive => "permissive",
+            ProtectionMode::Warn => "warn",
+            ProtectionMode::Strict => "strict",
+        }
+    }
+}
+
+impl FromStr for ProtectionMode {
+    type Err = ();
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "permissive" => Ok(ProtectionMode::Permissive),
+            "warn" => Ok(ProtectionMode::Warn),
+            "strict" => Ok(ProtectionMode::Strict),
+            _ => Err(()),
+        }
+    }
+}
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum Violation {
+    #[error("Escrita no endereço {0:#06X}, que contém código já executado.")]
+    WriteToCode(MemoryCell),
+
+    #[error("Leitura do endereço {0:#06X}, que nunca foi escrito.")]
+    UninitializedRead(MemoryCell),
+
+    #[error("Leitura do registrador R{0}, que nunca foi escrito.")]
+    UninitializedRegister(usize),
+
+    #[error("Acesso ao endereço {addr:#06X}, na área livre da pilha (SP {sp:#06X}).")]
+    StackAccess { addr: MemoryCell, sp: MemoryCell },
+
+    #[error("Escrita no endereço {0:#06X}, reservado como folga da pilha.")]
+    WriteToReserved(MemoryCell),
+
+    #[error("RTS executado sem um CALL correspondente.")]
+    UnmatchedReturn,
+
+    #[error(
+        "Retorno da sub-rotina em {target:#06X} com SP {found:#06X}, diferente do SP {expected:#06X} na entrada."
+    )]
+    StackDrift {
+        target: MemoryCell,
+        expected: MemoryCell,
+        found: MemoryCell,
+    },
+}
+
+/// Violação detectada em [`ProtectionMode::Warn`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProtectionWarning {
+    /// Endereço da instrução que causou a violação.
+    pub pc: MemoryCell,
+    /// Ciclos de *clock* executados antes da instrução.
+    pub cycle: u64,
+    pub violation: Violation,
+}
+
+impl Processor {
+    pub fn protection(&self) -> ProtectionMode {
+        self.protection
+    }
+
+    /// Altera o tratamento das violações de proteção. Pode ser chamada durante a execução.
+    pub fn set_protection(&mut self, mode: ProtectionMode) {
+        self.protection = mode
+    }
+
+    /// Retorna as violações detectadas em [`ProtectionMode::Warn`], em ordem.
+    pub fn warnings(&self) -> &[ProtectionWarning] {
+        &self.warnings
+    }
+
+    pub fn clear_warnings(&mut self) {
+        self.warnings.clear()
+    }
+
+    /// Lê o endereço `addr` como acesso de dados de uma instrução.
+    ///
+    /// # Erros
+    ///
+    /// - [`ProcessorError::InvalidAddress`] caso o endereço seja inválido.
+    /// - [`ProcessorError::Protection`] caso o acesso viole a proteção em
+    ///   [`ProtectionMode::Strict`].
+    pub(crate) fn read_data(&mut self, addr: MemoryCell) -> Result<Word> {
+        let value = self.mem(addr)?;
+
+        let violation = if self.in_free_stack(addr) {
+            Some(Violation::StackAccess { addr, sp: self.sp })
+        } else if !self.initialized[addr] {
+            Some(Violation::UninitializedRead(addr))
+        } else {
+            None
+        };
+
+        self.report(violation)?;
+        Ok(value)
+    }
+
+    /// Escreve `v` no endereço `addr` como acesso de dados de uma instrução. Em
+    /// [`ProtectionMode::Strict`], a memória não é alterada caso o acesso seja uma violação.
+    ///
+    /// # Erros
+    ///
+    /// - [`ProcessorError::InvalidAddress`] caso o endereço seja inválido.
+    /// - [`ProcessorError::Protection`] caso o acesso viole a proteção em
+    ///   [`ProtectionMode::Strict`].
+    pub(crate) fn write_data(&mut self, addr: MemoryCell, v: Word) -> Result<()> {
+        self.mem(addr)?;
+
+        let violation = if layout::ADDR_GAP_TOP_STACK.contains(&addr)
+            || layout::ADDR_GAP_BOTTOM_STACK.contains(&addr)
+        {
+            Some(Violation::WriteToReserved(addr))
+        } else if self.in_free_stack(addr) {
+            Some(Violation::StackAccess { addr, sp: self.sp })
+        } else if self.executed[addr] {
+            Some(Violation::WriteToCode(addr))
+        } else {
+            None
+        };
+
+        self.report(violation)?;
+        self.set_mem(addr, v)
+    }
+
+    /// Verifica se os registradores lidos por `instruction`, já decodificada, foram escritos.
+    ///
+    /// # Erros
+    ///
+    /// Retorna [`ProcessorError::Protection`] caso algum registrador nunca tenha sido escrito
+    /// em [`ProtectionMode::Strict`].
+    pub(crate) fn check_registers(&mut self, instruction: Instruction) -> Result<()> {
+        if self.protection == ProtectionMode::Permissive {
+            return Ok(());
+        }
+
+        let (rx, ry, rz) = (self.rx, self.ry, self.rz);
+        let sources: &[usize] = match instruction {
+            Instruction::LOADI | Instruction::NOT => &[ry],
+            Instruction::STORE
+            | Instruction::INC
+            | Instruction::DEC
+            | Instruction::SHIFTL0
+            | Instruction::SHIFTL1
+            | Instruction::SHIFTR0
+            | Instruction::SHIFTR1
+            | Instruction::ROTL
+            | Instruction::ROTR => &[rx],
+            Instruction::STOREI | Instruction::OUTCHAR | Instruction::CMP => &[rx, ry],
+            Instruction::ADD
+            | Instruction::ADDC
+            | Instruction::SUB
+            | Instruction::SUBC
+            | Instruction::MUL
+            | Instruction::DIV
+            | Instruction::MOD
+            | Instruction::AND
+            | Instruction::OR
+            | Instruction::XOR => &[ry, rz],
+            Instruction::MOV => match self.ir.bits(0..=1) {
+                0 => &[ry],
+                1 => &[],
+                _ => &[rx],
+            },
+            Instruction::PUSH if !self.ir.bit(6) => &[rx],
+            _ => &[],
+        };
+
+        for &r in sources {
+            if !self.registers_initialized[r] {
+                self.report(Some(Violation::UninitializedRegister(r)))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Marca as `length` palavras a partir de `addr` como executadas.
+    pub(crate) fn mark_executed(&mut self, addr: MemoryCell, length: usize) {
+        let end = (addr + length).min(self.executed.len());
+        if let Some(executed) = self.executed.get_mut(addr..end) {
+            executed.fill(true);
+        }
+    }
+
+    /// Indica se `addr` pertence à pilha mas não à sua área ocupada, acima do SP.
+    fn in_free_stack(&self, addr: MemoryCell) -> bool {
+        layout::ADDR_STACK.contains(&addr) && addr <= self.sp
+    }
+
+    pub(crate) fn report(&mut self, violation: Option<Violation>) -> Result<()> {
+        let Some(violation) = violation else {
+            return Ok(());
+        };
+
+        match self.protection {
+            ProtectionMode::Permissive => Ok(()),
+            ProtectionMode::Warn => {
+                warn!("[PC {:#06x}] {}", self.instruction_addr, violation);
+                if self.warnings.len() < MAX_WARNINGS {
+                    self.warnings.push(ProtectionWarning {
+                        pc: self.instruction_addr,
+                        cycle: self.cycles,
+                        violation,
+                    });
+                }
+                Ok(())
+            }
+            ProtectionMode::Strict => Err(ProcessorError::Protection(violation)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const STOREI: u16 = 0b1111010010100000; // STOREI R1, R2
+    const LOADI: u16 = 0b1111000110010000; // LOADI R3, R1
+
+    /// Cria um processador que executa `instruction` com R1 = `addr` e R2 = 0.
+    fn processor(instruction: u16, addr: MemoryCell, mode: ProtectionMode) -> Processor {
+        let mut p = Processor::new();
+        p.load_memory(&[Word(instruction), Word(instruction)])
+            .unwrap();
+        p.set_reg(1, Word::truncate(addr)).unwrap();
+        p.set_reg(2, Word::ZERO).unwrap();
+        p.set_protection(mode);
+        p
+    }
+
+    fn violation(instruction: u16, addr: MemoryCell) -> Option<Violation> {
+        let mut p = processor(instruction, addr, ProtectionMode::Strict);
+        match p.instruction_cicle() {
+            Ok(()) => None,
+            Err(e) => match e.root() {
+                ProcessorError::Protection(v) => Some(v.clone()),
+                e => panic!("{}", e),
+            },
+        }
+    }
+
+    #[test]
+    fn test_strict_violations() {
+        let gap = *layout::ADDR_GAP_TOP_STACK.start();
+        let sp = *layout::ADDR_STACK.end();
+        let data = *layout::ADDR_STATIC_DATA.start();
+
+        assert_eq!(Some(Violation::WriteToCode(0)), violation(STOREI, 0));
+        assert_eq!(
+            Some(Violation::WriteToReserved(gap)),
+            violation(STOREI, gap)
+        );
+        assert_eq!(
+            Some(Violation::StackAccess { addr: sp, sp }),
+            violation(STOREI, sp)
+        );
+        assert_eq!(
+            Some(Violation::UninitializedRead(data)),
+            violation(LOADI, data)
+        );
+
+        // Palavras carregadas pela imagem e ainda não executadas são dados válidos.
+        assert_eq!(None, violation(STOREI, 1));
+        assert_eq!(None, violation(LOADI, 1));
+    }
+
+    #[test]
+    fn test_strict_preserves_memory() {
+        let mut p = processor(STOREI, 0, ProtectionMode::Strict);
+        p.set_reg(2, Word(42)).unwrap();
+
+        assert!(p.instruction_cicle().is_err());
+        assert_eq!(Word(STOREI), p.mem(0).unwrap());
+    }
+
+    #[test]
+    fn test_warn_and_permissive() {
+        let data = *layout::ADDR_STATIC_DATA.start();
+
+        let mut p = processor(LOADI, data, ProtectionMode::Warn);
+        p.instruction_cicle().unwrap();
+        p.instruction_cicle().unwrap();
+        assert_eq!(
+            vec![
+                ProtectionWarning {
+                    pc: 0,
+                    cycle: 0,
+                    violation: Violation::UninitializedRead(data),
+                },
+                ProtectionWarning {
+                    pc: 1,
+                    cycle: isa::Instruction::LOADI.cycles(),
+                    violation: Violation::UninitializedRead(data),
+                },
+            ],
+            p.warnings()
+        );
+
+        let mut p = processor(LOADI, data, ProtectionMode::Permissive);
+        p.instruction_cicle().unwrap();
+        assert!(p.warnings().is_empty());
+    }
+
+    #[test]
+    fn test_uninitialized_registers() {
+        let add = 0b1000000010100110; // ADD R1, R2, R3
+        let mut p = processor(add, 0, ProtectionMode::Warn);
+
+        p.instruction_cicle().unwrap();
+        assert_eq!(
+            vec![ProtectionWarning {
+                pc: 0,
+                cycle: 0,
+                violation: Violation::UninitializedRegister(3),
+            }],
+            p.warnings()
+        );
+
+        // R2 já foi escrito ao criar o processador.
+        p.set_reg(3, Word(1)).unwrap();
+        p.clear_warnings();
+        p.instruction_cicle().unwrap();
+        assert!(p.warnings().is_empty());
+
+        p.reset_registers();
+        p.set_protection(ProtectionMode::Strict);
+        assert_eq!(
+            &ProcessorError::Protection(Violation::UninitializedRegister(2)),
+            p.instruction_cicle().unwrap_err().root()
+        );
+    }
+}
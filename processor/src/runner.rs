@@ -0,0 +1,531 @@
+//! Execução contínua do processador em lotes de ciclos.
+//!
+//! O [`Runner`] adquire o *lock* do processador apenas uma vez por lote, verifica um *flag*
+//! atômico de parada entre as instruções e publica um [`Frame`] com o estado visível do
+//! processador a uma taxa fixa, independente da velocidade de execução. A velocidade pode ser
+//! limitada a uma frequência de *clock* com [`ClockSpeed`].
+
+use std::{
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Arc, Mutex, MutexGuard,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use isa::{MemoryCell, Word};
+
+use crate::{errors::ProcessorError, Processor, ProcessorStatus, NUM_REGISTERS};
+
+type Result<T> = std::result::Result<T, ProcessorError>;
+
+/// Velocidade do *clock* simulado.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClockSpeed {
+    /// Executa o mais rápido possível.
+    Unlimited,
+    /// Limita a execução à frequência indicada, em Hz.
+    Hz(u64),
+}
+
+impl ClockSpeed {
+    fn encode(self) -> u64 {
+        match self {
+            ClockSpeed::Unlimited => 0,
+            ClockSpeed::Hz(hz) => hz.max(1),
+        }
+    }
+
+    fn decode(v: u64) -> Self {
+        match v {
+            0 => ClockSpeed::Unlimited,
+            hz => ClockSpeed::Hz(hz),
+        }
+    }
+}
+
+/// Condição de parada de [`Runner::run_until`], além das já verificadas por [`Runner::run`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Until {
+    /// Nenhuma condição extra: executa até `HALT`, `BREAKP`, um erro ou um pedido de parada.
+    Halt,
+    /// Para assim que a pilha de chamadas tiver no máximo o número de quadros indicado, isto
+    /// é, quando as chamadas mais profundas retornarem.
+    Depth(usize),
+}
+
+impl Until {
+    fn reached(self, p: &Processor) -> bool {
+        match self {
+            Until::Halt => false,
+            Until::Depth(depth) => p.call_depth() <= depth,
+        }
+    }
+}
+
+/// Cópia do estado visível do processador, publicada para a interface durante a execução.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Frame {
+    pub registers: [Word; NUM_REGISTERS],
+    pub fr: [bool; isa::BITS_ADDRESS],
+    pub pc: MemoryCell,
+    pub sp: MemoryCell,
+    pub ir: Word,
+    pub status: ProcessorStatus,
+    /// Instruções executadas desde o início de [`Runner::run`].
+    pub instructions: u64,
+    /// Ciclos de *clock* executados desde o último *reset* do processador.
+    pub cycles: u64,
+    /// Velocidade efetiva, em Hz, desde o quadro anterior.
+    pub speed: f64,
+}
+
+impl Frame {
+    fn capture(p: &Processor, instructions: u64, speed: f64) -> Self {
+        Self {
+            registers: p.registers,
+            fr: p.fr,
+            pc: p.pc,
+            sp: p.sp,
+            ir: p.ir,
+            status: p.status,
+            instructions,
+            cycles: p.cycles,
+            speed,
+        }
+    }
+}
+
+/// Mede a velocidade efetiva entre dois quadros.
+struct SpeedMeter {
+    start: Instant,
+    cycles: u64,
+}
+
+impl SpeedMeter {
+    fn new(cycles: u64) -> Self {
+        Self {
+            start: Instant::now(),
+            cycles,
+        }
+    }
+
+    /// Retorna a velocidade desde a última medição e reinicia a contagem.
+    fn measure(&mut self, cycles: u64) -> f64 {
+        let elapsed = self.start.elapsed().as_secs_f64();
+        let speed = match elapsed > 0.0 {
+            true => cycles.saturating_sub(self.cycles) as f64 / elapsed,
+            false => 0.0,
+        };
+        *self = Self::new(cycles);
+        speed
+    }
+}
+
+pub struct Runner {
+    processor: Arc<Mutex<Processor>>,
+    stop: Arc<AtomicBool>,
+    clock: AtomicU64,
+    batch: u64,
+    frame_interval: Duration,
+}
+
+impl Runner {
+    /// Número padrão de ciclos de *clock* executados a cada aquisição do *lock*.
+    pub const DEFAULT_BATCH: u64 = 30_000;
+
+    /// Taxa padrão de publicação de [`Frame`], em quadros por segundo.
+    pub const DEFAULT_FRAME_RATE: u32 = 60;
+
+    pub fn new(processor: Arc<Mutex<Processor>>) -> Self {
+        Self {
+            processor,
+            stop: Arc::new(AtomicBool::new(false)),
+            clock: AtomicU64::new(ClockSpeed::Unlimited.encode()),
+            batch: Self::DEFAULT_BATCH,
+            frame_interval: Duration::from_secs(1) / Self::DEFAULT_FRAME_RATE,
+        }
+    }
+
+    /// Altera o número de ciclos de *clock* executados a cada aquisição do *lock*.
+    pub fn with_batch(mut self, batch: u64) -> Self {
+        self.batch = batch.max(1);
+        self
+    }
+
+    /// Altera a taxa de publicação de [`Frame`], em quadros por segundo.
+    pub fn with_frame_rate(mut self, fps: u32) -> Self {
+        self.frame_interval = Duration::from_secs(1) / fps.max(1);
+        self
+    }
+
+    /// Altera a velocidade inicial do *clock*.
+    pub fn with_clock(self, clock: ClockSpeed) -> Self {
+        self.set_clock(clock);
+        self
+    }
+
+    /// Altera a velocidade do *clock*. Pode ser chamada durante a execução.
+    pub fn set_clock(&self, clock: ClockSpeed) {
+        self.clock.store(clock.encode(), Ordering::Relaxed);
+    }
+
+    pub fn clock(&self) -> ClockSpeed {
+        ClockSpeed::decode(self.clock.load(Ordering::Relaxed))
+    }
+
+    /// Retorna o *flag* de parada, que pode ser marcado de outra *thread* para interromper
+    /// [`Runner::run`].
+    pub fn stop_flag(&self) -> Arc<AtomicBool> {
+        self.stop.clone()
+    }
+
+    /// Pede a interrupção de [`Runner::run`] ao fim da instrução atual.
+    pub fn stop(&self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+
+    /// Descarta os pedidos de parada anteriores. Deve ser chamada antes de iniciar a execução
+    /// em outra *thread*, para que uma parada pedida logo após o início não seja perdida.
+    pub fn clear_stop(&self) {
+        self.stop.store(false, Ordering::Relaxed);
+    }
+
+    fn lock(&self) -> Result<MutexGuard<'_, Processor>> {
+        self.processor.lock().map_err(|_| ProcessorError::Generic {
+            title: "Poison Error".to_string(),
+            description: "Falha ao acessar o processador!".to_string(),
+        })
+    }
+
+    /// Executa o processador até uma instrução `HALT` ou `BREAKP`, até um erro ou até que a
+    /// parada seja pedida. O *flag* de parada não é limpo: uma parada pedida antes do início
+    /// interrompe a execução antes da primeira instrução. Veja [`Runner::clear_stop`].
+    ///
+    /// Com [`ClockSpeed::Hz`], a execução é pausada sempre que estiver adiantada em relação ao
+    /// *clock*, e cada lote fica limitado aos ciclos de um quadro.
+    ///
+    /// `publish` recebe um [`Frame`] a cada intervalo da taxa de quadros e ao fim da execução.
+    /// Se a execução for interrompida pelo *flag* de parada, o processador volta ao status
+    /// [`ProcessorStatus::Debug`].
+    ///
+    /// # Erros
+    ///
+    /// Retorna o erro da instrução que falhou, após publicar o estado do processador naquele
+    /// momento.
+    pub fn run(&self, publish: impl FnMut(Frame)) -> Result<Frame> {
+        self.run_until(Until::Halt, publish)
+    }
+
+    /// Igual a [`Runner::run`], mas também para ao atingir `until`. Nesse caso, o processador
+    /// volta ao status [`ProcessorStatus::Debug`].
+    ///
+    /// # Erros
+    ///
+    /// Retorna o erro da instrução que falhou, como [`Runner::run`].
+    pub fn run_until(&self, until: Until, mut publish: impl FnMut(Frame)) -> Result<Frame> {
+        let mut instructions = 0;
+        let mut last_frame = Instant::now();
+        let (mut meter, mut clock, mut base) = {
+            let mut p = self.lock()?;
+            p.set_status(ProcessorStatus::Running);
+            (
+                SpeedMeter::new(p.cycles),
+                self.clock(),
+                (Instant::now(), p.cycles),
+            )
+        };
+
+        loop {
+            // Uma mudança de velocidade reinicia a referência do clock.
+            if self.clock() != clock {
+                clock = self.clock();
+                base = (Instant::now(), self.lock()?.cycles);
+            }
+
+            let budget = match clock {
+                ClockSpeed::Unlimited => self.batch,
+                ClockSpeed::Hz(hz) => {
+                    ((hz as f64 * self.frame_interval.as_secs_f64()) as u64).clamp(1, self.batch)
+                }
+            };
+
+            let mut p = self.lock()?;
+            let (executed, result) = p.run_batch_until(budget, &self.stop, |p| until.reached(p));
+            instructions += executed as u64;
+            if let Err(e) = result {
+                // A instrução que falhou também conta como executada.
+                let speed = meter.measure(p.cycles);
+                publish(Frame::capture(&p, instructions + 1, speed));
+                return Err(e);
+            }
+
+            let stopped = self.stop.load(Ordering::Relaxed) || until.reached(&p);
+            if stopped && p.status == ProcessorStatus::Running {
+                p.set_status(ProcessorStatus::Debug);
+            }
+
+            if stopped || p.status != ProcessorStatus::Running {
+                let frame = Frame::capture(&p, instructions, meter.measure(p.cycles));
+                publish(frame.clone());
+                return Ok(frame);
+            }
+
+            if last_frame.elapsed() >= self.frame_interval {
+                publish(Frame::capture(&p, instructions, meter.measure(p.cycles)));
+                last_frame = Instant::now();
+            }
+
+            let cycles = p.cycles;
+            drop(p);
+
+            if let ClockSpeed::Hz(hz) = clock {
+                let due = Duration::from_secs_f64((cycles - base.1) as f64 / hz as f64);
+                while !self.stop.load(Ordering::Relaxed) {
+                    match due.checked_sub(base.0.elapsed()) {
+                        Some(ahead) if !ahead.is_zero() => {
+                            thread::sleep(ahead.min(self.frame_interval))
+                        }
+                        _ => break,
+                    }
+                }
+            }
+        }
+    }
+
+    /// Executa uma única instrução.
+    ///
+    /// # Erros
+    ///
+    /// Retorna o erro da instrução, caso ela falhe.
+    pub fn step(&self) -> Result<Frame> {
+        let mut p = self.lock()?;
+        p.instruction_cicle()?;
+        Ok(Frame::capture(&p, 1, 0.0))
+    }
+
+    /// Executa uma instrução e, caso ela seja uma chamada de sub-rotina, continua a execução
+    /// até o retorno dela, como em [`Runner::run_until`].
+    ///
+    /// # Erros
+    ///
+    /// Retorna o erro da instrução que falhou.
+    pub fn step_over(&self, mut publish: impl FnMut(Frame)) -> Result<Frame> {
+        let depth = self.lock()?.call_depth();
+        let frame = self.step()?;
+
+        if self.lock()?.call_depth() <= depth {
+            publish(frame.clone());
+            return Ok(frame);
+        }
+
+        self.run_until(Until::Depth(depth), |mut f| {
+            f.instructions += 1;
+            publish(f)
+        })
+        .map(|mut f| {
+            f.instructions += 1;
+            f
+        })
+    }
+
+    /// Executa até o retorno da sub-rotina atual. Fora de sub-rotinas, equivale a
+    /// [`Runner::run`].
+    ///
+    /// # Erros
+    ///
+    /// Retorna o erro da instrução que falhou.
+    pub fn step_out(&self, publish: impl FnMut(Frame)) -> Result<Frame> {
+        let until = match self.lock()?.call_depth().checked_sub(1) {
+            Some(depth) => Until::Depth(depth),
+            None => Until::Halt,
+        };
+        self.run_until(until, publish)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use isa::Instruction;
+
+    use super::*;
+
+    const HALT: Word = Word(0b0011110000000000);
+    const BREAKP: Word = Word(0b0011100000000000);
+    const JMP: Word = Word(0b0000100000000000);
+    const CALL: Word = Word(0b0000110000000000);
+    const RTS: Word = Word(0b0001000000000000);
+    const INC_R0: Word = Word(0b1001000000000000);
+
+    /// Programa que chama, do endereço 0, uma sub-rotina em 4 que incrementa R0 duas vezes.
+    const SUBROUTINE: [Word; 7] = [CALL, Word(4), HALT, Word::ZERO, INC_R0, INC_R0, RTS];
+
+    fn processor(program: &[Word]) -> Arc<Mutex<Processor>> {
+        let mut p = Processor::new();
+        for (addr, &word) in program.iter().enumerate() {
+            p.set_mem(addr, word).unwrap();
+        }
+        Arc::new(Mutex::new(p))
+    }
+
+    #[test]
+    fn test_run_until_halt() {
+        let runner = Runner::new(processor(&[Word::ZERO, Word::ZERO, HALT])).with_batch(2);
+        let mut frames = Vec::new();
+
+        let frame = runner.run(|f| frames.push(f)).unwrap();
+
+        assert_eq!(3, frame.instructions);
+        assert_eq!(3 * Instruction::NOP.cycles(), frame.cycles);
+        assert_eq!(3, frame.pc);
+        assert_eq!(ProcessorStatus::Halted, frame.status);
+        assert_eq!(Some(&frame), frames.last());
+    }
+
+    #[test]
+    fn test_run_until_breakpoint() {
+        let runner = Runner::new(processor(&[Word::ZERO, BREAKP, HALT]));
+        let frame = runner.run(|_| ()).unwrap();
+
+        assert_eq!(2, frame.instructions);
+        assert_eq!(ProcessorStatus::Debug, frame.status);
+    }
+
+    #[test]
+    fn test_run_stop() {
+        // Com a taxa de quadros máxima, cada lote publica um quadro.
+        let p = processor(&[JMP, Word::ZERO]);
+        let runner = Runner::new(p.clone())
+            .with_batch(10)
+            .with_frame_rate(u32::MAX);
+        let mut frames = 0;
+
+        let frame = runner
+            .run(|_| {
+                frames += 1;
+                if frames == 3 {
+                    runner.stop();
+                }
+            })
+            .unwrap();
+
+        assert_eq!(4, frames);
+        assert!(frame.instructions > 0);
+        assert_eq!(ProcessorStatus::Debug, frame.status);
+        assert_eq!(ProcessorStatus::Debug, p.lock().unwrap().status());
+    }
+
+    #[test]
+    fn test_run_stop_before_start() {
+        let runner = Runner::new(processor(&[JMP, Word::ZERO]));
+        runner.stop();
+
+        let frame = runner.run(|_| ()).unwrap();
+        assert_eq!(0, frame.instructions);
+        assert_eq!(ProcessorStatus::Debug, frame.status);
+
+        runner.clear_stop();
+        let frame = runner.run(|_| runner.stop()).unwrap();
+        assert!(frame.instructions > 0);
+    }
+
+    #[test]
+    fn test_run_error_counts_batch() {
+        // Duas instruções válidas seguidas de uma inválida, no mesmo lote.
+        let runner = Runner::new(processor(&[
+            Word::ZERO,
+            Word::ZERO,
+            Word(0b1011110000000000),
+        ]));
+        let mut last = None;
+
+        assert!(runner.run(|f| last = Some(f)).is_err());
+        assert_eq!(Some(3), last.map(|f| f.instructions));
+    }
+
+    #[test]
+    fn test_run_error() {
+        let runner = Runner::new(processor(&[Word(0b1011110000000000)]));
+        let mut last = None;
+
+        assert_eq!(
+            Some(&ProcessorError::InvalidInstruction(0b1011110000000000)),
+            runner
+                .run(|f| last = Some(f))
+                .as_ref()
+                .err()
+                .map(|e| e.root())
+        );
+        assert_eq!(Some(1), last.map(|f| f.instructions));
+    }
+
+    #[test]
+    fn test_step_over() {
+        let p = processor(&SUBROUTINE);
+        let runner = Runner::new(p.clone());
+
+        let frame = runner.step_over(|_| ()).unwrap();
+        assert_eq!(2, frame.pc);
+        assert_eq!(4, frame.instructions);
+        assert_eq!(Word(2), frame.registers[0]);
+        assert_eq!(ProcessorStatus::Debug, frame.status);
+
+        // Fora de uma chamada, avança uma única instrução.
+        let frame = runner.step_over(|_| ()).unwrap();
+        assert_eq!(1, frame.instructions);
+        assert_eq!(ProcessorStatus::Halted, frame.status);
+    }
+
+    #[test]
+    fn test_step_out() {
+        let p = processor(&SUBROUTINE);
+        let runner = Runner::new(p.clone());
+
+        assert_eq!(4, runner.step().unwrap().pc);
+        assert_eq!(5, runner.step().unwrap().pc);
+
+        let frame = runner.step_out(|_| ()).unwrap();
+        assert_eq!(2, frame.pc);
+        assert_eq!(2, frame.instructions);
+        assert_eq!(Word(2), frame.registers[0]);
+        assert!(p.lock().unwrap().call_stack().is_empty());
+    }
+
+    #[test]
+    fn test_run_throttled() {
+        const HZ: u64 = 1_000;
+        const FRAMES: usize = 20;
+
+        // Com a taxa de quadros máxima, cada lote tem um único ciclo e publica um quadro.
+        let runner = Runner::new(processor(&[JMP, Word::ZERO]))
+            .with_clock(ClockSpeed::Hz(HZ))
+            .with_frame_rate(u32::MAX);
+        let mut cycles = Vec::new();
+
+        let start = Instant::now();
+        runner
+            .run(|f| {
+                cycles.push(f.cycles);
+                if cycles.len() == FRAMES {
+                    runner.stop();
+                }
+            })
+            .unwrap();
+        let elapsed = start.elapsed();
+
+        // Cada lote executa uma única instrução.
+        assert!(cycles
+            .windows(2)
+            .take(FRAMES - 1)
+            .all(|w| w[1] - w[0] == Instruction::JMP.cycles()));
+
+        // Antes de cada lote, a execução espera o tempo dos ciclos já executados.
+        let waited = cycles[FRAMES - 2];
+        assert!(
+            elapsed >= Duration::from_secs_f64(waited as f64 / HZ as f64),
+            "{:?}",
+            elapsed
+        );
+    }
+}
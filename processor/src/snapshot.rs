@@ -0,0 +1,677 @@
+//! Cópia completa do estado da máquina em um arquivo versionado.
+//!
+//! O *snapshot* guarda a RAM, a VRAM (o estado do vídeo, único dispositivo do processador), os
+//! registradores, o FR, o PC, o SP, o IR, os campos decodificados da instrução atual, o status,
+//! o contador de ciclos, a última imagem carregada, a pilha de chamadas, a origem das palavras
+//! empilhadas, o modo e o estado da proteção da memória e as escritas usadas pelo mapa da
+//! memória. Ele pode ser restaurado a qualquer momento com [`Processor::read_snapshot`]. O
+//! perfil de execução e os avisos de proteção já emitidos não são guardados.
+//!
+//! O formato é textual, uma linha por campo ou por registro (`call`, `push` e `write`). As
+//! memórias são escritas em hexadecimal, com sequências de valores repetidos compactadas como
+//! `valor*quantidade`.
+
+use std::{
+    collections::HashMap,
+    fmt::LowerHex,
+    io::{BufRead, Write},
+    str::FromStr,
+};
+
+use isa::{MemoryCell, Word};
+use thiserror::Error;
+
+use crate::{
+    call_stack::CallFrame, protection::ProtectionMode, stack::SlotKind, Fill, Image, Processor,
+    ProcessorStatus, MEMORY_SIZE, NUM_REGISTERS, VRAM_SIZE,
+};
+
+/// Cabeçalho presente na primeira linha de todo *snapshot*.
+pub const SNAPSHOT_MAGIC: &str = "ICMC-SNAPSHOT";
+
+/// Versão atual do formato de *snapshot*. Arquivos da versão 1, sem a pilha de chamadas, a
+/// imagem carregada e a proteção da memória, continuam sendo lidos.
+pub const SNAPSHOT_VERSION: usize = 2;
+
+/// Tamanho mínimo de uma sequência de valores repetidos para ser compactada.
+const MIN_RUN: usize = 4;
+
+#[derive(Error, Debug)]
+pub enum SnapshotError {
+    #[error("Falha ao acessar o snapshot: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("O arquivo não é um snapshot do Processador ICMC.")]
+    InvalidHeader,
+
+    #[error("Versão {0} do formato de snapshot não suportada.")]
+    UnsupportedVersion(usize),
+
+    #[error("Linha {line} do snapshot mal formatada: {content}")]
+    BadFormat { line: usize, content: String },
+
+    #[error("O campo \"{0}\" não está presente no snapshot.")]
+    MissingField(&'static str),
+
+    #[error("O campo \"{field}\" do snapshot tem {found} valores, em vez de {expected}.")]
+    WrongLength {
+        field: &'static str,
+        expected: usize,
+        found: usize,
+    },
+
+    #[error("O valor {value} do campo \"{field}\" do snapshot está fora do intervalo permitido.")]
+    OutOfRange { field: &'static str, value: usize },
+}
+
+impl ProcessorStatus {
+    fn name(&self) -> &'static str {
+        match self {
+            ProcessorStatus::Running => "running",
+            ProcessorStatus::Debug => "debug",
+            ProcessorStatus::Halted => "halted",
+        }
+    }
+}
+
+impl FromStr for ProcessorStatus {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "running" => Ok(ProcessorStatus::Running),
+            "debug" => Ok(ProcessorStatus::Debug),
+            "halted" => Ok(ProcessorStatus::Halted),
+            _ => Err(()),
+        }
+    }
+}
+
+impl Fill {
+    fn name(&self) -> &'static str {
+        match self {
+            Fill::Zero => "zero",
+            Fill::Preserve => "preserve",
+        }
+    }
+}
+
+impl FromStr for Fill {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "zero" => Ok(Fill::Zero),
+            "preserve" => Ok(Fill::Preserve),
+            _ => Err(()),
+        }
+    }
+}
+
+/// Nome da origem de uma palavra empilhada por `PUSH`, as únicas registradas no processador.
+fn slot_name(kind: SlotKind) -> Option<String> {
+    match kind {
+        SlotKind::Register(r) => Some(format!("r{}", r)),
+        SlotKind::Flags => Some("fr".to_string()),
+        SlotKind::ReturnAddress(_) | SlotKind::Unknown => None,
+    }
+}
+
+fn parse_slot(s: &str) -> Option<SlotKind> {
+    match s {
+        "fr" => Some(SlotKind::Flags),
+        _ => s
+            .strip_prefix('r')?
+            .parse()
+            .ok()
+            .filter(|&r| r < NUM_REGISTERS)
+            .map(SlotKind::Register),
+    }
+}
+
+fn write_words<W: Write, T: LowerHex + PartialEq>(
+    w: &mut W,
+    name: &str,
+    words: &[T],
+) -> std::io::Result<()> {
+    write!(w, "{}", name)?;
+    for run in words.chunk_by(|a, b| a == b) {
+        match run.len() {
+            n if n >= MIN_RUN => write!(w, " {:x}*{}", run[0], n)?,
+            _ => {
+                for word in run {
+                    write!(w, " {:x}", word)?;
+                }
+            }
+        }
+    }
+    writeln!(w)
+}
+
+fn parse_words<T: Copy>(tokens: &[&str], parse: fn(&str) -> Option<T>) -> Option<Vec<T>> {
+    let mut words = Vec::new();
+    for token in tokens {
+        match token.split_once('*') {
+            Some((value, count)) => {
+                words.extend(std::iter::repeat_n(parse(value)?, count.parse().ok()?));
+            }
+            None => words.push(parse(token)?),
+        }
+    }
+    Some(words)
+}
+
+fn parse_word(s: &str) -> Option<Word> {
+    u16::from_str_radix(s.trim_start_matches("0x"), 16)
+        .ok()
+        .map(Word)
+}
+
+fn parse_cell(s: &str) -> Option<MemoryCell> {
+    MemoryCell::from_str_radix(s.trim_start_matches("0x"), 16).ok()
+}
+
+fn bits(flags: &[bool]) -> Vec<u8> {
+    flags.iter().map(|&f| f as u8).collect()
+}
+
+fn parse_bit(s: &str) -> Option<bool> {
+    match s {
+        "0" => Some(false),
+        "1" => Some(true),
+        _ => None,
+    }
+}
+
+/// Verifica se `value` pertence ao intervalo `0..limit` do campo `field`.
+fn check_range(field: &'static str, value: usize, limit: usize) -> Result<usize, SnapshotError> {
+    match value < limit {
+        true => Ok(value),
+        false => Err(SnapshotError::OutOfRange { field, value }),
+    }
+}
+
+/// Verifica se `values` tem exatamente `expected` valores.
+fn check_length<T>(
+    field: &'static str,
+    values: Vec<T>,
+    expected: usize,
+) -> Result<Vec<T>, SnapshotError> {
+    match values.len() == expected {
+        true => Ok(values),
+        false => Err(SnapshotError::WrongLength {
+            field,
+            expected,
+            found: values.len(),
+        }),
+    }
+}
+
+impl Processor {
+    /// Escreve o estado completo do processador no formato de *snapshot*.
+    pub fn write_snapshot<W: Write>(&self, w: &mut W) -> Result<(), SnapshotError> {
+        let fr = self
+            .fr
+            .iter()
+            .enumerate()
+            .fold(0u16, |acc, (i, &f)| acc | ((f as u16) << i));
+
+        writeln!(w, "{} {}", SNAPSHOT_MAGIC, SNAPSHOT_VERSION)?;
+        write_words(w, "registers", &self.registers)?;
+        writeln!(w, "fr {:#06x}", fr)?;
+        writeln!(w, "pc {:#06x}", self.pc)?;
+        writeln!(w, "sp {:#06x}", self.sp)?;
+        writeln!(w, "ir {:#06x}", self.ir)?;
+        writeln!(w, "fields {} {} {}", self.rx, self.ry, self.rz)?;
+        writeln!(w, "status {}", self.status.name())?;
+        writeln!(w, "cycles {}", self.cycles)?;
+        write_words(w, "ram", &self.ram)?;
+        write_words(w, "vram", &self.vram)?;
+
+        if let Some(image) = &self.image {
+            let name = format!("image {:#06x} {}", image.base, image.fill.name());
+            write_words(w, &name, &image.words)?;
+        }
+        for frame in &self.call_stack {
+            writeln!(
+                w,
+                "call {:#06x} {:#06x} {:#06x}",
+                frame.call_site, frame.target, frame.sp
+            )?;
+        }
+
+        let mut pushes: Vec<_> = self.pushes.iter().collect();
+        pushes.sort_by_key(|(&addr, _)| addr);
+        for (addr, &(kind, value)) in pushes {
+            if let Some(kind) = slot_name(kind) {
+                writeln!(w, "push {:#06x} {} {:#06x}", addr, kind, value)?;
+            }
+        }
+
+        writeln!(w, "protection {}", self.protection.name())?;
+        write_words(
+            w,
+            "registers-initialized",
+            &bits(&self.registers_initialized),
+        )?;
+        write_words(w, "initialized", &bits(&self.initialized))?;
+        write_words(w, "executed", &bits(&self.executed))?;
+
+        for (addr, cycle) in self.writes.iter().enumerate() {
+            if let Some(cycle) = cycle {
+                writeln!(w, "write {:#06x} {}", addr, cycle)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Lê um processador escrito por [`Processor::write_snapshot`].
+    ///
+    /// # Erros
+    ///
+    /// - [`SnapshotError::InvalidHeader`] caso a primeira linha não seja o cabeçalho esperado.
+    /// - [`SnapshotError::UnsupportedVersion`] caso a versão seja maior que
+    ///   [`SNAPSHOT_VERSION`].
+    /// - [`SnapshotError::BadFormat`] caso alguma linha esteja mal formatada.
+    /// - [`SnapshotError::MissingField`] caso algum campo obrigatório esteja ausente.
+    /// - [`SnapshotError::WrongLength`] caso a RAM, a VRAM ou o estado da proteção não tenham
+    ///   o tamanho da memória do processador.
+    /// - [`SnapshotError::OutOfRange`] caso algum endereço ou registrador não exista.
+    ///
+    /// # Exemplo
+    ///
+    /// ```
+    /// use isa::Word;
+    /// use processor::Processor;
+    ///
+    /// let mut p = Processor::new();
+    /// p.set_reg(3, Word(42)).unwrap();
+    ///
+    /// let mut buf = Vec::new();
+    /// p.write_snapshot(&mut buf).unwrap();
+    ///
+    /// let restored = Processor::read_snapshot(buf.as_slice()).unwrap();
+    /// assert_eq!(Word(42), restored.reg(3).unwrap());
+    /// ```
+    pub fn read_snapshot<R: BufRead>(r: R) -> Result<Self, SnapshotError> {
+        let mut lines = r.lines().enumerate();
+
+        match lines.next() {
+            Some((_, header)) => {
+                let header = header?;
+                match header.split_once(' ') {
+                    Some((SNAPSHOT_MAGIC, v)) => match v.trim().parse::<usize>() {
+                        Ok(1..=SNAPSHOT_VERSION) => (),
+                        Ok(v) => return Err(SnapshotError::UnsupportedVersion(v)),
+                        Err(_) => return Err(SnapshotError::InvalidHeader),
+                    },
+                    _ => return Err(SnapshotError::InvalidHeader),
+                }
+            }
+            None => return Err(SnapshotError::InvalidHeader),
+        }
+
+        let mut registers = None;
+        let mut fr = None;
+        let mut pc = None;
+        let mut sp = None;
+        let mut ir = None;
+        let mut fields = None;
+        let mut status = None;
+        let mut cycles = None;
+        let mut ram = None;
+        let mut vram = None;
+        let mut image = None;
+        let mut call_stack = Vec::new();
+        let mut pushes = HashMap::new();
+        let mut protection = None;
+        let mut registers_initialized = None;
+        let mut initialized = None;
+        let mut executed = None;
+        let mut writes = Vec::new();
+
+        for (i, line) in lines {
+            let line = line?;
+            let bad_format = || SnapshotError::BadFormat {
+                line: i + 1,
+                content: line.chars().take(80).collect(),
+            };
+
+            let tokens: Vec<&str> = line.split_whitespace().collect();
+            match tokens.as_slice() {
+                [] => (),
+                ["registers", words @ ..] => {
+                    let words = parse_words(words, parse_word).ok_or_else(bad_format)?;
+                    registers =
+                        Some(<[Word; NUM_REGISTERS]>::try_from(words).map_err(|_| bad_format())?);
+                }
+                ["fr", v] => fr = Some(parse_word(v).ok_or_else(bad_format)?),
+                ["pc", v] => pc = Some(parse_cell(v).ok_or_else(bad_format)?),
+                ["sp", v] => sp = Some(parse_cell(v).ok_or_else(bad_format)?),
+                ["ir", v] => ir = Some(parse_word(v).ok_or_else(bad_format)?),
+                ["fields", x, y, z] => {
+                    fields = Some((
+                        x.parse().map_err(|_| bad_format())?,
+                        y.parse().map_err(|_| bad_format())?,
+                        z.parse().map_err(|_| bad_format())?,
+                    ))
+                }
+                ["status", s] => status = Some(s.parse().map_err(|_| bad_format())?),
+                ["cycles", c] => cycles = Some(c.parse().map_err(|_| bad_format())?),
+                ["ram", words @ ..] => {
+                    ram = Some(parse_words(words, parse_word).ok_or_else(bad_format)?)
+                }
+                ["vram", words @ ..] => {
+                    vram = Some(parse_words(words, parse_cell).ok_or_else(bad_format)?)
+                }
+                ["image", base, fill, words @ ..] => {
+                    image = Some(Image {
+                        base: parse_cell(base).ok_or_else(bad_format)?,
+                        fill: fill.parse().map_err(|_| bad_format())?,
+                        words: parse_words(words, parse_word).ok_or_else(bad_format)?,
+                    })
+                }
+                ["call", call_site, target, sp] => call_stack.push(CallFrame {
+                    call_site: parse_cell(call_site).ok_or_else(bad_format)?,
+                    target: parse_cell(target).ok_or_else(bad_format)?,
+                    sp: parse_cell(sp).ok_or_else(bad_format)?,
+                }),
+                ["push", addr, kind, value] => {
+                    pushes.insert(
+                        parse_cell(addr).ok_or_else(bad_format)?,
+                        (
+                            parse_slot(kind).ok_or_else(bad_format)?,
+                            parse_word(value).ok_or_else(bad_format)?,
+                        ),
+                    );
+                }
+                ["protection", mode] => protection = Some(mode.parse().map_err(|_| bad_format())?),
+                ["registers-initialized", flags @ ..] => {
+                    let flags = parse_words(flags, parse_bit).ok_or_else(bad_format)?;
+                    registers_initialized =
+                        Some(<[bool; NUM_REGISTERS]>::try_from(flags).map_err(|_| bad_format())?);
+                }
+                ["initialized", flags @ ..] => {
+                    initialized = Some(parse_words(flags, parse_bit).ok_or_else(bad_format)?)
+                }
+                ["executed", flags @ ..] => {
+                    executed = Some(parse_words(flags, parse_bit).ok_or_else(bad_format)?)
+                }
+                ["write", addr, cycle] => {
+                    writes.push((
+                        parse_cell(addr).ok_or_else(bad_format)?,
+                        cycle.parse().map_err(|_| bad_format())?,
+                    ));
+                }
+                _ => return Err(bad_format()),
+            }
+        }
+
+        let fr = fr.ok_or(SnapshotError::MissingField("fr"))?;
+        let (rx, ry, rz) = fields.ok_or(SnapshotError::MissingField("fields"))?;
+        for (field, r) in [("fields", rx), ("fields", ry), ("fields", rz)] {
+            check_range(field, r, NUM_REGISTERS)?;
+        }
+
+        let ram = check_length(
+            "ram",
+            ram.ok_or(SnapshotError::MissingField("ram"))?,
+            MEMORY_SIZE,
+        )?;
+        let vram = check_length(
+            "vram",
+            vram.ok_or(SnapshotError::MissingField("vram"))?,
+            VRAM_SIZE,
+        )?;
+        let pc = check_range(
+            "pc",
+            pc.ok_or(SnapshotError::MissingField("pc"))?,
+            MEMORY_SIZE,
+        )?;
+        let sp = check_range(
+            "sp",
+            sp.ok_or(SnapshotError::MissingField("sp"))?,
+            MEMORY_SIZE,
+        )?;
+
+        if let Some(image) = &image {
+            check_range("image", image.base + image.words.len(), MEMORY_SIZE + 1)?;
+        }
+        for frame in &call_stack {
+            for addr in [frame.call_site, frame.target, frame.sp] {
+                check_range("call", addr, MEMORY_SIZE)?;
+            }
+        }
+        for &addr in pushes.keys() {
+            check_range("push", addr, MEMORY_SIZE)?;
+        }
+        let mut written_at = vec![None; MEMORY_SIZE];
+        for (addr, cycle) in writes {
+            written_at[check_range("write", addr, MEMORY_SIZE)?] = Some(cycle);
+        }
+
+        // Snapshots da versão 1 não guardam o estado da proteção; todos os registradores e toda
+        // a memória restaurados são considerados escritos, e nenhum endereço executado.
+        let initialized = match initialized {
+            Some(flags) => check_length("initialized", flags, MEMORY_SIZE)?,
+            None => vec![true; MEMORY_SIZE],
+        };
+        let executed = match executed {
+            Some(flags) => check_length("executed", flags, MEMORY_SIZE)?,
+            None => vec![false; MEMORY_SIZE],
+        };
+
+        Ok(Processor {
+            registers_initialized: registers_initialized.unwrap_or([true; NUM_REGISTERS]),
+            initialized,
+            executed,
+            ram,
+            vram,
+            registers: registers.ok_or(SnapshotError::MissingField("registers"))?,
+            rx,
+            ry,
+            rz,
+            fr: std::array::from_fn(|i| fr.bit(i)),
+            pc,
+            ir: ir.ok_or(SnapshotError::MissingField("ir"))?,
+            sp,
+            status: status.ok_or(SnapshotError::MissingField("status"))?,
+            cycles: cycles.ok_or(SnapshotError::MissingField("cycles"))?,
+            image,
+            protection: protection.unwrap_or_default(),
+            call_stack: call_stack.into(),
+            pushes,
+            writes: written_at,
+            ..Processor::default()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot(p: &Processor) -> Vec<u8> {
+        let mut buf = Vec::new();
+        p.write_snapshot(&mut buf).unwrap();
+        buf
+    }
+
+    #[test]
+    fn test_snapshot_round_trip() {
+        let mut p = Processor::new();
+        // INC R1; INC R1; JMP 0
+        for (addr, word) in [
+            0b1001000010000000,
+            0b1001000010000000,
+            0b0000100000000000,
+            0,
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            p.set_mem(addr, Word(word)).unwrap();
+        }
+        p.set_pixel(8, (1, 2, 3, 4)).unwrap();
+        p.set_fr(isa::FlagIndex::CARRY, true).unwrap();
+        for _ in 0..4 {
+            p.instruction_cicle().unwrap();
+        }
+
+        let buf = snapshot(&p);
+        let mut restored = Processor::read_snapshot(buf.as_slice()).unwrap();
+        assert_eq!(buf, snapshot(&restored));
+
+        // A execução continua igual a partir do estado restaurado.
+        for _ in 0..5 {
+            p.instruction_cicle().unwrap();
+            restored.instruction_cicle().unwrap();
+        }
+        assert_eq!(snapshot(&p), snapshot(&restored));
+        assert_eq!(Word(6), restored.reg(1).unwrap());
+        assert_eq!((1, 2, 3, 4), restored.pixel(8).unwrap());
+    }
+
+    /// Substitui a linha do campo `field` de um *snapshot* por `line`.
+    fn replace_line(buf: &[u8], field: &str, line: &str) -> String {
+        String::from_utf8_lossy(buf)
+            .lines()
+            .map(|l| match l.split(' ').next() == Some(field) {
+                true => line,
+                false => l,
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    #[test]
+    fn test_snapshot_inside_subroutine() {
+        const CALL: u16 = 0b0000110000000000;
+        const RTS: u16 = 0b0001000000000000;
+        const PUSH_R1: u16 = 0b0001010010000000;
+        const POP_R1: u16 = 0b0001100010000000;
+        const HALT: u16 = 0b0011110000000000;
+
+        // 0: CALL 3; 2: HALT; 3: PUSH R1; 4: POP R1; 5: RTS
+        let program: Vec<Word> = [CALL, 3, HALT, PUSH_R1, POP_R1, RTS]
+            .into_iter()
+            .map(Word)
+            .collect();
+        let mut p = Processor::new();
+        p.load_memory(&program).unwrap();
+        p.set_protection(ProtectionMode::Strict);
+        p.set_reg(1, Word(9)).unwrap();
+        p.instruction_cicle().unwrap();
+        p.instruction_cicle().unwrap();
+
+        let buf = snapshot(&p);
+        let mut restored = Processor::read_snapshot(buf.as_slice()).unwrap();
+        assert_eq!(buf, snapshot(&restored));
+        assert_eq!(p.call_stack(), restored.call_stack());
+        assert_eq!(p.stack(), restored.stack());
+        assert_eq!(ProtectionMode::Strict, restored.protection());
+
+        // Endereço de retorno e R1 empilhados: as escritas são restauradas com o ciclo.
+        for addr in [p.sp() + 1, p.sp() + 2] {
+            let usage = restored.usage(addr).unwrap();
+            assert!(usage.written_at.is_some());
+            assert_eq!(p.usage(addr).unwrap(), usage);
+        }
+
+        // POP R1 e RTS: o retorno encontra a chamada e a imagem carregada pode ser restaurada.
+        for _ in 0..2 {
+            restored.instruction_cicle().unwrap();
+        }
+        assert_eq!(2, restored.pc());
+        assert!(restored.reload());
+        assert_eq!(Word(CALL), restored.mem(0).unwrap());
+    }
+
+    #[test]
+    fn test_snapshot_version_1() {
+        let mut p = Processor::new();
+        p.set_reg(2, Word(5)).unwrap();
+
+        let v1: String = String::from_utf8(snapshot(&p))
+            .unwrap()
+            .lines()
+            .take_while(|l| !l.starts_with("protection"))
+            .map(|l| l.replace("ICMC-SNAPSHOT 2", "ICMC-SNAPSHOT 1") + "\n")
+            .collect();
+        let restored = Processor::read_snapshot(v1.as_bytes()).unwrap();
+        assert_eq!(Word(5), restored.reg(2).unwrap());
+        assert_eq!(ProtectionMode::Permissive, restored.protection());
+    }
+
+    #[test]
+    fn test_snapshot_validation() {
+        let buf = snapshot(&Processor::new());
+        let read = |s: String| Processor::read_snapshot(s.as_bytes());
+
+        assert!(matches!(
+            read(replace_line(&buf, "ram", "ram 0*10")),
+            Err(SnapshotError::WrongLength {
+                field: "ram",
+                expected: MEMORY_SIZE,
+                found: 10,
+            })
+        ));
+        assert!(matches!(
+            read(replace_line(
+                &buf,
+                "vram",
+                &format!("vram 0*{}", VRAM_SIZE - 1)
+            )),
+            Err(SnapshotError::WrongLength { field: "vram", .. })
+        ));
+        assert!(matches!(
+            read(replace_line(&buf, "pc", "pc 0x8000")),
+            Err(SnapshotError::OutOfRange {
+                field: "pc",
+                value: 0x8000,
+            })
+        ));
+        assert!(matches!(
+            read(replace_line(&buf, "sp", "sp 0xffff")),
+            Err(SnapshotError::OutOfRange { field: "sp", .. })
+        ));
+        assert!(matches!(
+            read(replace_line(&buf, "fields", "fields 0 8 0")),
+            Err(SnapshotError::OutOfRange {
+                field: "fields",
+                ..
+            })
+        ));
+        assert!(matches!(
+            read(replace_line(&buf, "executed", "executed 0*3")),
+            Err(SnapshotError::WrongLength {
+                field: "executed",
+                ..
+            })
+        ));
+        assert!(matches!(
+            read(String::from_utf8_lossy(&buf).to_string() + "call 0x0 0x8000 0x0\n"),
+            Err(SnapshotError::OutOfRange { field: "call", .. })
+        ));
+    }
+
+    #[test]
+    fn test_snapshot_errors() {
+        assert!(matches!(
+            Processor::read_snapshot("ICMC-SNAPSHOT 99\n".as_bytes()),
+            Err(SnapshotError::UnsupportedVersion(99))
+        ));
+        assert!(matches!(
+            Processor::read_snapshot("ICMC-OBJ 1\n".as_bytes()),
+            Err(SnapshotError::InvalidHeader)
+        ));
+        assert!(matches!(
+            Processor::read_snapshot("ICMC-SNAPSHOT 1\npc zz\n".as_bytes()),
+            Err(SnapshotError::BadFormat { line: 2, .. })
+        ));
+        assert!(matches!(
+            Processor::read_snapshot("ICMC-SNAPSHOT 1\n".as_bytes()),
+            Err(SnapshotError::MissingField(_))
+        ));
+    }
+}
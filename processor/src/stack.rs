@@ -0,0 +1,161 @@
+//! Inspeção da pilha do processador.
+//!
+//! Cada `PUSH` registra a origem da palavra empilhada, e os endereços de retorno são
+//! identificados pela pilha de chamadas ([`crate::call_stack`]). Palavras da pilha alteradas
+//! por outras instruções perdem a origem e são mostradas como [`SlotKind::Unknown`].
+
+use isa::{memory::layout, MemoryCell, Word};
+
+use crate::{call_stack::CallFrame, Processor};
+
+/// Origem de uma palavra da pilha.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SlotKind {
+    /// Endereço de retorno empilhado pela chamada `frame`.
+    ReturnAddress(CallFrame),
+    /// Registrador de uso geral empilhado por `PUSH Rx`.
+    Register(usize),
+    /// *Flag Register* empilhado por `PUSH FR`.
+    Flags,
+    /// Palavra de origem desconhecida.
+    Unknown,
+}
+
+/// Palavra da pilha e a sua origem.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StackSlot {
+    pub addr: MemoryCell,
+    pub value: Word,
+    pub kind: SlotKind,
+}
+
+impl StackSlot {
+    /// Verifica se a palavra está em uma das áreas de proteção ao redor da pilha, alcançadas
+    /// apenas com *stack overflow* ou *stack underflow*.
+    pub fn in_gap(&self) -> bool {
+        layout::ADDR_GAP_TOP_STACK.contains(&self.addr)
+            || layout::ADDR_GAP_BOTTOM_STACK.contains(&self.addr)
+    }
+}
+
+impl Processor {
+    /// Registra a origem da palavra `value`, empilhada no endereço apontado pelo SP.
+    pub(crate) fn record_push(&mut self, kind: SlotKind, value: Word) {
+        self.pushes.insert(self.sp, (kind, value));
+    }
+
+    /// Retorna as palavras da pilha, do topo (SP + 1) à base. Com o SP além da base
+    /// (*stack underflow*), retorna as palavras entre a base e o SP, já desempilhadas.
+    ///
+    /// # Exemplo
+    ///
+    /// ```
+    /// use processor::{stack::SlotKind, Processor};
+    /// use isa::Word;
+    ///
+    /// // PUSH R3; PUSH FR
+    /// let mut p = Processor::new();
+    /// p.load_memory(&[Word(0b0001010110000000), Word(0b0001010001000000)]).unwrap();
+    /// p.set_reg(3, Word(42)).unwrap();
+    /// p.instruction_cicle().unwrap();
+    /// p.instruction_cicle().unwrap();
+    ///
+    /// let stack = p.stack();
+    /// assert_eq!(SlotKind::Flags, stack[0].kind);
+    /// assert_eq!((SlotKind::Register(3), Word(42)), (stack[1].kind, stack[1].value));
+    /// ```
+    pub fn stack(&self) -> Vec<StackSlot> {
+        let base = *layout::ADDR_STACK.end();
+        let range = match self.sp {
+            sp if sp < base => sp + 1..=base,
+            sp => base + 1..=sp,
+        };
+
+        range
+            .filter_map(|addr| {
+                let value = self.mem(addr).ok()?;
+                Some(StackSlot {
+                    addr,
+                    value,
+                    kind: self.slot_kind(addr, value),
+                })
+            })
+            .collect()
+    }
+
+    fn slot_kind(&self, addr: MemoryCell, value: Word) -> SlotKind {
+        if let Some(frame) = self.call_stack.iter().find(|f| f.sp + 1 == addr) {
+            return SlotKind::ReturnAddress(*frame);
+        }
+
+        match self.pushes.get(&addr) {
+            Some(&(kind, pushed)) if pushed == value => kind,
+            _ => SlotKind::Unknown,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ProcessorStatus;
+
+    const CALL: u16 = 0b0000110000000000;
+    const PUSH_R1: u16 = 0b0001010010000000;
+    const BREAKP: u16 = 0b0011100000000000;
+
+    fn run(p: &mut Processor) {
+        p.set_status(ProcessorStatus::Running);
+        while p.status() == ProcessorStatus::Running {
+            p.instruction_cicle().unwrap();
+        }
+    }
+
+    #[test]
+    fn test_stack_slots() {
+        // 0: CALL 3; 2: BREAKP; 3: PUSH R1; 4: BREAKP
+        let program: Vec<Word> = [CALL, 3, BREAKP, PUSH_R1, BREAKP]
+            .iter()
+            .map(|&w| Word(w))
+            .collect();
+        let mut p = Processor::new();
+        p.load_memory(&program).unwrap();
+        p.set_reg(1, Word(7)).unwrap();
+        let base = p.sp();
+
+        run(&mut p);
+        let stack = p.stack();
+        assert_eq!(2, stack.len());
+        assert_eq!(
+            StackSlot {
+                addr: base - 1,
+                value: Word(7),
+                kind: SlotKind::Register(1),
+            },
+            stack[0]
+        );
+        assert!(matches!(
+            stack[1].kind,
+            SlotKind::ReturnAddress(CallFrame { target: 3, .. })
+        ));
+        assert!(!stack[1].in_gap());
+
+        // Uma escrita fora do PUSH apaga a origem da palavra.
+        p.set_mem(base - 1, Word(8)).unwrap();
+        assert_eq!(SlotKind::Unknown, p.stack()[0].kind);
+    }
+
+    #[test]
+    fn test_stack_underflow() {
+        let mut p = Processor::new();
+        let base = p.sp();
+        let _ = p.set_sp(base + 2);
+
+        let stack = p.stack();
+        assert_eq!(
+            vec![base + 1, base + 2],
+            stack.iter().map(|s| s.addr).collect::<Vec<_>>()
+        );
+        assert!(stack.iter().all(StackSlot::in_gap));
+    }
+}
@@ -0,0 +1,16 @@
+; Escreve a letra 'A' em cada uma das 16 cores da tela.
+
+main:
+    LOADN R0, #'A'      ; caractere
+    LOADN R1, #0        ; posição na tela
+    LOADN R2, #16       ; número de cores
+    LOADN R4, #256      ; incremento da cor
+
+loop:
+    OUTCHAR R0, R1
+    ADD R0, R0, R4
+    INC R1
+    CMP R1, R2
+    JNE loop
+
+    HALT
@@ -0,0 +1,27 @@
+; Calcula os 10 primeiros números de Fibonacci e os guarda em `fib`.
+
+main:
+    LOADN R0, #fib      ; endereço do próximo número
+    LOADN R1, #0        ; F(n - 2)
+    LOADN R2, #1        ; F(n - 1)
+    LOADN R5, #10       ; quantidade de números
+    LOADN R6, #0        ; números calculados
+
+loop:
+    STOREI R0, R1
+    CALL next
+    INC R0
+    INC R6
+    CMP R6, R5
+    JNE loop
+
+    HALT
+
+; Avança a sequência: R1, R2 <- R2, R1 + R2
+next:
+    ADD R3, R1, R2
+    MOV R1, R2
+    MOV R2, R3
+    RTS
+
+fib: var #10
@@ -0,0 +1,20 @@
+; Escreve "Hello, world!" na primeira linha da tela.
+
+main:
+    LOADN R0, #msg      ; endereço do próximo caractere
+    LOADN R1, #0        ; posição na tela
+    LOADN R2, #0        ; fim da string
+
+loop:
+    LOADI R3, R0
+    CMP R3, R2
+    JEQ fim
+    OUTCHAR R3, R1
+    INC R0
+    INC R1
+    JMP loop
+
+fim:
+    HALT
+
+msg: string "Hello, world!"
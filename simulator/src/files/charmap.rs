@@ -1,3 +1,7 @@
+use std::io::{self, BufRead, Write};
+
+use super::mif;
+
 macro_rules! charmap {
     ($($v:literal)+) => {
         {
@@ -8,15 +12,29 @@ macro_rules! charmap {
     };
 }
 
+/// Número de caracteres de um charmap.
+pub const GLYPHS: usize = 128;
+
+/// Largura e altura, em pixels, de um caractere.
+pub const GLYPH_WIDTH: usize = 8;
+
+/// Número de pixels de um caractere.
+pub const GLYPH_SIZE: usize = GLYPH_WIDTH * GLYPH_WIDTH;
+
 pub struct Charmap {
     lenght: usize,
     pixelmap: Vec<u8>,
 }
 
 impl Charmap {
-    pub fn new(charmap: Vec<u8>) -> Self {
+    /// Cria um charmap a partir dos pixels (0 ou 1) de cada caractere, em sequência. Caso haja
+    /// menos de [`GLYPHS`] caracteres, os restantes ficam vazios.
+    pub fn new(mut charmap: Vec<u8>) -> Self {
+        let len = charmap.len().div_ceil(GLYPH_SIZE).max(GLYPHS);
+        charmap.resize(len * GLYPH_SIZE, 0);
+
         Self {
-            lenght: charmap.len() / 64,
+            lenght: len,
             pixelmap: charmap,
         }
     }
@@ -28,6 +46,63 @@ impl Charmap {
     pub fn lenght(&self) -> usize {
         self.lenght
     }
+
+    /// Retorna os pixels do caractere `index`, linha a linha.
+    pub fn glyph(&self, index: usize) -> &[u8] {
+        &self.pixelmap[index * GLYPH_SIZE..(index + 1) * GLYPH_SIZE]
+    }
+
+    /// Substitui os pixels do caractere `index`.
+    pub fn set_glyph(&mut self, index: usize, glyph: &[u8]) {
+        self.pixelmap[index * GLYPH_SIZE..(index + 1) * GLYPH_SIZE].copy_from_slice(glyph);
+    }
+
+    pub fn pixel(&self, index: usize, x: usize, y: usize) -> bool {
+        self.glyph(index)[y * GLYPH_WIDTH + x] == 1
+    }
+
+    pub fn set_pixel(&mut self, index: usize, x: usize, y: usize, on: bool) {
+        self.pixelmap[index * GLYPH_SIZE + y * GLYPH_WIDTH + x] = on as u8;
+    }
+
+    /// Lê um charmap no formato MIF (*Memory Initialization File*), em que cada endereço
+    /// guarda uma linha de 8 pixels de um caractere, com o pixel mais à esquerda no *bit* mais
+    /// significativo.
+    pub fn read_mif<R: BufRead>(r: R) -> io::Result<Self> {
+        let mif = mif::read(r, GLYPHS * GLYPH_WIDTH)?;
+        if mif.width != GLYPH_WIDTH {
+            return Err(mif::invalid_data(format!(
+                "Charmap com palavras de {} bits; esperado {}.",
+                mif.width, GLYPH_WIDTH
+            )));
+        }
+
+        Ok(Self::new(
+            mif.data
+                .iter()
+                .flat_map(|row| (0..GLYPH_WIDTH).rev().map(move |b| ((row >> b) & 1) as u8))
+                .collect(),
+        ))
+    }
+
+    /// Escreve o charmap no formato MIF lido por [`Charmap::read_mif`].
+    pub fn write_mif<W: Write>(&self, w: &mut W) -> io::Result<()> {
+        writeln!(w, "WIDTH={};", GLYPH_WIDTH)?;
+        writeln!(w, "DEPTH={};", self.lenght * GLYPH_WIDTH)?;
+        writeln!(w)?;
+        writeln!(w, "ADDRESS_RADIX=UNS;")?;
+        writeln!(w, "DATA_RADIX=BIN;")?;
+        writeln!(w)?;
+        writeln!(w, "CONTENT BEGIN")?;
+        for (addr, row) in self.pixelmap.chunks(GLYPH_WIDTH).enumerate() {
+            let bits: String = row
+                .iter()
+                .map(|&p| if p == 1 { '1' } else { '0' })
+                .collect();
+            writeln!(w, "\t{}  :   {};", addr, bits)?;
+        }
+        writeln!(w, "END;")
+    }
 }
 
 impl Default for Charmap {
@@ -933,9 +1008,6 @@ impl Default for Charmap {
             0 0 0 0 0 0 0 0
         );
 
-        Self {
-            lenght: vec.len() / 64,
-            pixelmap: vec,
-        }
+        Self::new(vec)
     }
 }
@@ -0,0 +1,59 @@
+use std::{fs, path::PathBuf};
+
+use adw::glib;
+use log::error;
+
+use crate::ui::number_format::{Formats, NumberFormat, View, GLOBAL_ID};
+
+/// Chave do formato global no arquivo de preferências.
+const GLOBAL_KEY: &str = "global";
+
+/// Arquivo em que os formatos de exibição são guardados, um `chave=formato` por linha.
+fn formats_file() -> PathBuf {
+    glib::user_config_dir()
+        .join("processador-icmc")
+        .join("formats.txt")
+}
+
+/// Retorna os formatos salvos. Chaves ausentes ou inválidas mantêm o valor padrão.
+pub fn load() -> Formats {
+    let mut formats = Formats::default();
+    let content = fs::read_to_string(formats_file()).unwrap_or_default();
+
+    for (key, value) in content.lines().filter_map(|l| l.split_once('=')) {
+        let (key, value) = (key.trim(), value.trim());
+        if key == GLOBAL_KEY {
+            if let Some(format) = NumberFormat::from_id(value) {
+                formats.global = format;
+            }
+        } else if let Some(view) = View::ALL.into_iter().find(|v| v.id() == key) {
+            match value {
+                GLOBAL_ID => formats.set_view(view, None),
+                value => {
+                    if let Some(format) = NumberFormat::from_id(value) {
+                        formats.set_view(view, Some(format));
+                    }
+                }
+            }
+        }
+    }
+    formats
+}
+
+/// Salva os formatos para as próximas execuções do simulador.
+pub fn save(formats: &Formats) {
+    let mut content = format!("{}={}\n", GLOBAL_KEY, formats.global.id());
+    for view in View::ALL {
+        let value = formats.view(view).map_or(GLOBAL_ID, NumberFormat::id);
+        content.push_str(&format!("{}={}\n", view.id(), value));
+    }
+
+    let file = formats_file();
+    if let Err(e) = file
+        .parent()
+        .map_or(Ok(()), fs::create_dir_all)
+        .and_then(|_| fs::write(&file, content))
+    {
+        error!("Falha ao salvar os formatos de exibição: {e}");
+    }
+}
@@ -0,0 +1,177 @@
+use std::io::{self, BufRead};
+
+/// Conteúdo de um arquivo MIF (*Memory Initialization File*).
+pub struct Mif {
+    /// Largura, em *bits*, de cada palavra.
+    pub width: usize,
+    /// Palavra de cada endereço, de `0` a `DEPTH - 1`.
+    pub data: Vec<usize>,
+}
+
+pub(crate) fn invalid_data(msg: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
+
+/// Lê um número na base `radix` de um arquivo MIF.
+fn parse_radix(s: &str, radix: u32) -> io::Result<usize> {
+    usize::from_str_radix(s.trim(), radix)
+        .map_err(|_| invalid_data(format!("Número \"{}\" inválido no arquivo MIF.", s.trim())))
+}
+
+/// Converte o nome da base de um arquivo MIF (`ADDRESS_RADIX` e `DATA_RADIX`).
+fn radix(name: &str) -> io::Result<u32> {
+    match name.trim().to_ascii_uppercase().as_str() {
+        "BIN" => Ok(2),
+        "OCT" => Ok(8),
+        "DEC" | "UNS" => Ok(10),
+        "HEX" => Ok(16),
+        r => Err(invalid_data(format!(
+            "Base \"{}\" não suportada no arquivo MIF.",
+            r
+        ))),
+    }
+}
+
+/// Lê um arquivo MIF com no máximo `max_depth` endereços. São aceitos comentários `--`,
+/// intervalos de endereços `[a..b]` e as bases `BIN`, `OCT`, `DEC`, `UNS` e `HEX`. Endereços
+/// sem valor ficam zerados.
+pub fn read<R: BufRead>(r: R, max_depth: usize) -> io::Result<Mif> {
+    let mut width = 16;
+    let mut address_radix = 10;
+    let mut data_radix = 2;
+    let mut depth = 0;
+    let mut data: Vec<usize> = Vec::new();
+    let mut content = false;
+
+    for line in r.lines() {
+        let line = line?;
+        let line = match line.split_once("--") {
+            Some((code, _)) => code,
+            None => &line,
+        };
+
+        for statement in line.split(';').map(str::trim).filter(|s| !s.is_empty()) {
+            let upper = statement.to_ascii_uppercase();
+
+            if !content {
+                match upper.split_once('=') {
+                    Some((key, value)) => match key.trim() {
+                        "WIDTH" => width = parse_radix(value, 10)?,
+                        "DEPTH" => {
+                            depth = parse_radix(value, 10)?;
+                            if depth > max_depth {
+                                return Err(invalid_data(format!(
+                                    "Arquivo MIF com {} endereços; o máximo é {}.",
+                                    depth, max_depth
+                                )));
+                            }
+                        }
+                        "ADDRESS_RADIX" => address_radix = radix(value)?,
+                        "DATA_RADIX" => data_radix = radix(value)?,
+                        _ => (),
+                    },
+                    None if upper == "CONTENT BEGIN" || upper == "CONTENT" => {
+                        content = true;
+                        data.resize(depth, 0);
+                    }
+                    None => {
+                        return Err(invalid_data(format!(
+                            "Linha \"{}\" inválida no arquivo MIF.",
+                            statement
+                        )))
+                    }
+                }
+                continue;
+            }
+
+            if upper == "BEGIN" {
+                continue;
+            }
+
+            if upper == "END" {
+                return Ok(Mif { width, data });
+            }
+
+            let (addresses, value) = statement.split_once(':').ok_or_else(|| {
+                invalid_data(format!("Linha \"{}\" inválida no arquivo MIF.", statement))
+            })?;
+            let value = parse_radix(value, data_radix)?;
+
+            let addresses = addresses.trim();
+            let range = match addresses
+                .strip_prefix('[')
+                .and_then(|a| a.strip_suffix(']'))
+                .and_then(|a| a.split_once(".."))
+            {
+                Some((start, end)) => {
+                    parse_radix(start, address_radix)?..=parse_radix(end, address_radix)?
+                }
+                None => {
+                    let addr = parse_radix(addresses, address_radix)?;
+                    addr..=addr
+                }
+            };
+
+            for addr in range {
+                *data.get_mut(addr).ok_or_else(|| {
+                    invalid_data(format!("Endereço {} fora do arquivo MIF.", addr))
+                })? = value;
+            }
+        }
+    }
+
+    Err(invalid_data("Arquivo MIF sem \"END;\".".to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(mif: &str) -> io::Result<Mif> {
+        read(mif.as_bytes(), 16)
+    }
+
+    #[test]
+    fn test_radices() {
+        let mif = parse(
+            "WIDTH=8;\nDEPTH=4;\nADDRESS_RADIX=HEX;\nDATA_RADIX=OCT;\n\
+             CONTENT BEGIN\n0 : 17;\n3 : 7;\nEND;",
+        )
+        .unwrap();
+        assert_eq!(8, mif.width);
+        assert_eq!(vec![0o17, 0, 0, 0o7], mif.data);
+
+        let mif =
+            parse("DEPTH=2;\nADDRESS_RADIX=BIN;\nDATA_RADIX=HEX;\nCONTENT BEGIN\n1 : fF;\nEND;")
+                .unwrap();
+        assert_eq!(16, mif.width);
+        assert_eq!(vec![0, 0xFF], mif.data);
+
+        assert!(parse("DEPTH=2;\nDATA_RADIX=B64;\nCONTENT BEGIN\nEND;").is_err());
+        assert!(parse("DEPTH=2;\nCONTENT BEGIN\n0 : 2;\nEND;").is_err());
+    }
+
+    #[test]
+    fn test_ranges_and_comments() {
+        let mif = parse(
+            "-- Cabeçalho\nDEPTH=6; DATA_RADIX=DEC; -- bases\n\
+             CONTENT\nBEGIN\n[1..3] : 9; -- intervalo\n5 : 4;\nEND;",
+        )
+        .unwrap();
+        assert_eq!(vec![0, 9, 9, 9, 0, 4], mif.data);
+
+        assert!(parse("DEPTH=2;\nDATA_RADIX=DEC;\nCONTENT BEGIN\n[1..2] : 1;\nEND;").is_err());
+    }
+
+    #[test]
+    fn test_invalid_files() {
+        // Sem `END;`.
+        assert!(parse("DEPTH=2;\nCONTENT BEGIN\n0 : 1;\n").is_err());
+        // `DEPTH` acima do máximo.
+        assert!(parse("DEPTH=17;\nCONTENT BEGIN\nEND;").is_err());
+        assert!(parse("DEPTH=16;\nCONTENT BEGIN\nEND;").is_ok());
+        assert!(parse("DEPTH=2;\nlixo\nCONTENT BEGIN\nEND;").is_err());
+        // Endereço além de `DEPTH`.
+        assert!(parse("DEPTH=4;\nADDRESS_RADIX=HEX;\nCONTENT BEGIN\nA : 1;\nEND;").is_err());
+    }
+}
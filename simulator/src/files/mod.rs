@@ -1,17 +1,36 @@
 pub mod charmap;
+pub mod formats;
+pub mod mif;
+pub mod recent;
 
-use std::{
-    fs::File,
-    io::{BufRead, BufReader},
-};
+use std::{fs::File, io::BufReader};
 
+/// Largura, em *bits*, das palavras de um programa.
+const PROGRAM_WIDTH: usize = 16;
+
+/// Lê os pixels do charmap salvo no arquivo MIF `path`.
 pub fn charmap(path: &str) -> std::io::Result<Vec<u8>> {
     let file = File::open(path)?;
-    let mut reader = BufReader::new(file);
+    charmap::Charmap::read_mif(BufReader::new(file)).map(|c| c.get().clone())
+}
 
-    let mut line = String::new();
-    while let Ok(_) = reader.read_line(&mut line) {
-        line.clear();
+/// Lê a memória de um programa montado salvo no arquivo MIF `path`.
+pub fn program(path: &str) -> std::io::Result<Vec<isa::Word>> {
+    let file = File::open(path)?;
+    let mif = mif::read(BufReader::new(file), processor::MEMORY_SIZE)?;
+    if mif.width != PROGRAM_WIDTH {
+        return Err(mif::invalid_data(format!(
+            "Programa com palavras de {} bits; esperado {}.",
+            mif.width, PROGRAM_WIDTH
+        )));
     }
-    todo!()
+
+    Ok(mif.data.into_iter().map(isa::Word::truncate).collect())
+}
+
+/// Verifica se o arquivo MIF `path` guarda um charmap, e não um programa.
+pub fn is_charmap(path: &str) -> bool {
+    File::open(path)
+        .and_then(|f| mif::read(BufReader::new(f), processor::MEMORY_SIZE))
+        .is_ok_and(|mif| mif.width == charmap::GLYPH_WIDTH)
 }
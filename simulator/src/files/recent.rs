@@ -0,0 +1,51 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use adw::glib;
+use log::error;
+
+/// Número máximo de projetos recentes guardados.
+pub const MAX_RECENT: usize = 10;
+
+/// Arquivo em que os projetos recentes são guardados, um caminho por linha.
+fn recent_file() -> PathBuf {
+    glib::user_data_dir()
+        .join("processador-icmc")
+        .join("recent.txt")
+}
+
+/// Retorna os projetos abertos recentemente que ainda existem, do mais recente ao mais
+/// antigo.
+pub fn load() -> Vec<PathBuf> {
+    fs::read_to_string(recent_file())
+        .unwrap_or_default()
+        .lines()
+        .map(PathBuf::from)
+        .filter(|p| p.exists())
+        .take(MAX_RECENT)
+        .collect()
+}
+
+/// Coloca `path` no início da lista de projetos recentes.
+pub fn add(path: &Path) {
+    let mut recent = load();
+    recent.retain(|p| p != path);
+    recent.insert(0, path.to_path_buf());
+    recent.truncate(MAX_RECENT);
+
+    let file = recent_file();
+    let content: String = recent
+        .iter()
+        .map(|p| format!("{}\n", p.display()))
+        .collect();
+
+    if let Err(e) = file
+        .parent()
+        .map_or(Ok(()), fs::create_dir_all)
+        .and_then(|_| fs::write(&file, content))
+    {
+        error!("Falha ao salvar os projetos recentes: {e}");
+    }
+}
@@ -15,7 +15,6 @@ use ui::{simulator_window, window};
 
 mod files;
 // mod mem_obj;
-mod handler;
 mod mem_row;
 mod processor;
 mod ui;
@@ -76,6 +75,8 @@ fn load_css() {
 
 fn setup_actions(app: &Application) {
     app.set_accels_for_action("processor.debug", &["<Ctrl>T"]);
+    app.set_accels_for_action("processor.undo-patch", &["<Ctrl>Z"]);
+    app.set_accels_for_action("processor.redo-patch", &["<Ctrl><Shift>Z"]);
 }
 
 fn build_ui(app: &Application) {
@@ -1,13 +1,20 @@
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
 
 use adw::glib;
+use adw::prelude::*;
 use adw::subclass::prelude::*;
 use glib::Binding;
 use gtk::{CompositeTemplate, Label};
 
+use crate::processor::instructions::parse_addr_link;
+use crate::ui::number_format::NumberFormat;
+
 #[derive(Default, CompositeTemplate)]
 #[template(resource = "/br/com/processador/mem_row.ui")]
 pub struct MemoryCellRow {
+    #[template_child]
+    pub label_mem_heat: TemplateChild<Label>,
     #[template_child]
     pub label_mem_addr: TemplateChild<Label>,
     #[template_child]
@@ -20,6 +27,20 @@ pub struct MemoryCellRow {
     pub label_mem_raw: TemplateChild<Label>,
     #[template_child]
     pub label_mem_float_raw: TemplateChild<Label>,
+    #[template_child]
+    pub separator_mem_source: TemplateChild<gtk::Separator>,
+    #[template_child]
+    pub label_mem_source: TemplateChild<Label>,
+
+    // Edição do conteúdo da célula
+    pub popover_edit: gtk::Popover,
+    pub entry_edit: gtk::Entry,
+    pub on_edited: RefCell<Option<Rc<super::EditHandler>>>,
+    pub on_target: RefCell<Option<Rc<dyn Fn(usize)>>>,
+    pub addr: Cell<usize>,
+    pub value: Cell<isa::Word>,
+    pub format: Cell<NumberFormat>,
+    pub instruction: Cell<bool>,
 }
 
 // The central trait for subclassing a GObject
@@ -40,7 +61,46 @@ impl ObjectSubclass for MemoryCellRow {
 }
 
 // Trait shared by all GObjects
-impl ObjectImpl for MemoryCellRow {}
+impl ObjectImpl for MemoryCellRow {
+    fn constructed(&self) {
+        self.parent_constructed();
+        let row = self.obj();
+
+        self.entry_edit.set_width_chars(24);
+        self.entry_edit
+            .set_placeholder_text(Some("Value or instruction"));
+        self.popover_edit.set_child(Some(&self.entry_edit));
+        self.popover_edit.set_parent(&*row);
+
+        let click = gtk::GestureClick::new();
+        click.connect_pressed(glib::clone!(@weak row => move |_, n_press, _, _| {
+            if n_press == 2 {
+                row.start_editing();
+            }
+        }));
+        row.add_controller(click);
+
+        self.entry_edit
+            .connect_activate(glib::clone!(@weak row => move |_| row.finish_editing()));
+
+        // Endereços de desvios, gerados por `InstructionDisplay::display_row`
+        self.label_mem_inst.connect_activate_link(
+            glib::clone!(@weak row => @default-return glib::Propagation::Proceed, move |_, uri| {
+                match parse_addr_link(uri) {
+                    Some(addr) => {
+                        row.activate_target(addr);
+                        glib::Propagation::Stop
+                    }
+                    None => glib::Propagation::Proceed,
+                }
+            }),
+        );
+    }
+
+    fn dispose(&self) {
+        self.popover_edit.unparent();
+    }
+}
 
 // Trait shared by all widgets
 impl WidgetImpl for MemoryCellRow {}
@@ -1,9 +1,20 @@
 mod imp;
+use std::rc::Rc;
+
 use adw::glib;
 use adw::prelude::*;
 use glib::Object;
 use gtk::glib::subclass::types::ObjectSubclassIsExt;
 
+use crate::ui::number_format::{self, NumberFormat};
+
+/// Função chamada com o endereço e o texto digitado ao editar uma célula. Retorna a descrição
+/// do erro caso o texto não possa ser escrito na memória.
+pub type EditHandler = dyn Fn(usize, &str) -> Result<(), String>;
+
+/// Classes do *heatmap*, da menos para a mais executada.
+const HEAT_CLASSES: [&str; 5] = ["heat-0", "heat-1", "heat-2", "heat-3", "heat-4"];
+
 glib::wrapper! {
     pub struct MemoryCellRow(ObjectSubclass<imp::MemoryCellRow>)
     @extends gtk::Box, gtk::Widget,
@@ -19,7 +30,7 @@ impl MemoryCellRow {
         f_addr: &str,
         inst: &str,
         f_inst: &str,
-        raw: usize,
+        raw: isa::Word,
         float: Option<&str>,
     ) -> Self {
         let mem = MemoryCellRow::build();
@@ -44,14 +55,22 @@ impl MemoryCellRow {
         instruction: Option<isa::Instruction>,
         addr: usize,
         inst: &str,
-        raw: usize,
+        raw: isa::Word,
+        format: NumberFormat,
     ) {
+        self.imp().addr.set(addr);
+        self.imp().value.set(raw);
+        self.imp().format.set(format);
+        self.imp().instruction.set(instruction.is_some());
         self.imp()
             .label_mem_addr
             .set_markup(&format!("{:#06X}", addr));
         self.imp().label_mem_inst.set_markup(inst);
 
-        match instruction {
+        self.imp()
+            .label_mem_raw
+            .set_tooltip_text(Some(&number_format::tooltip(raw)));
+        match instruction.filter(|_| format == NumberFormat::Binary) {
             Some(i) => {
                 let mask = i.mask();
                 let raw_str = format!("{:016b}", raw);
@@ -71,7 +90,7 @@ impl MemoryCellRow {
             None => self
                 .imp()
                 .label_mem_raw
-                .set_markup(&format!("{:016b}", raw)),
+                .set_markup(&glib::markup_escape_text(&format.format(raw))),
         }
 
         // match float {
@@ -86,6 +105,93 @@ impl MemoryCellRow {
         // }
     }
 
+    /// Mostra o número de execuções do endereço, com a cor proporcional a `max`, o maior número
+    /// de execuções da memória. Com [`None`], a coluna é escondida.
+    pub fn set_heat(&self, heat: Option<(u64, u64)>) {
+        let label = &self.imp().label_mem_heat;
+        for class in HEAT_CLASSES {
+            label.remove_css_class(class);
+        }
+
+        match heat {
+            Some((executions, max)) => {
+                let level = match max {
+                    0 => 0,
+                    max => (executions * (HEAT_CLASSES.len() as u64 - 1)).div_ceil(max) as usize,
+                };
+                label.add_css_class(HEAT_CLASSES[level]);
+                label.set_text(&executions.to_string());
+                label.set_tooltip_text(Some(&format!("{} executions", executions)));
+                label.set_visible(true);
+            }
+            None => label.set_visible(false),
+        }
+    }
+
+    /// Define a função chamada ao editar a célula com um duplo clique.
+    pub fn connect_edited(&self, f: Rc<EditHandler>) {
+        *self.imp().on_edited.borrow_mut() = Some(f);
+    }
+
+    /// Abre o campo de edição, preenchido com a instrução ou o valor da célula no formato de
+    /// exibição. Células com instruções inválidas são preenchidas com o valor.
+    pub(super) fn start_editing(&self) {
+        let imp = self.imp();
+        if imp.on_edited.borrow().is_none() {
+            return;
+        }
+
+        let text = match imp.instruction.get() {
+            true => imp.label_mem_inst.text().to_string(),
+            false => imp.format.get().literal(imp.value.get()),
+        };
+        imp.entry_edit.set_text(&text);
+        imp.entry_edit.remove_css_class("error");
+        imp.entry_edit.set_tooltip_text(None);
+        imp.popover_edit.popup();
+        imp.entry_edit.grab_focus();
+    }
+
+    /// Escreve o texto digitado na memória. Em caso de erro, o campo continua aberto com o erro
+    /// na dica.
+    pub(super) fn finish_editing(&self) {
+        let imp = self.imp();
+        let Some(f) = imp.on_edited.borrow().clone() else {
+            return;
+        };
+
+        match f(imp.addr.get(), imp.entry_edit.text().trim()) {
+            Ok(()) => imp.popover_edit.popdown(),
+            Err(e) => {
+                imp.entry_edit.add_css_class("error");
+                imp.entry_edit.set_tooltip_text(Some(&e));
+            }
+        }
+    }
+
+    /// Define a função chamada com o endereço de um desvio clicado na instrução.
+    pub fn connect_target_activated(&self, f: Rc<dyn Fn(usize)>) {
+        *self.imp().on_target.borrow_mut() = Some(f);
+    }
+
+    pub(super) fn activate_target(&self, addr: usize) {
+        let f = self.imp().on_target.borrow().clone();
+        if let Some(f) = f {
+            f(addr);
+        }
+    }
+
+    /// Mostra a linha do código-fonte que gerou a instrução. Com [`None`], a coluna é
+    /// escondida.
+    pub fn set_source_line(&self, line: Option<&str>) {
+        let imp = self.imp();
+        imp.label_mem_source.set_text(line.unwrap_or_default());
+        imp.label_mem_source
+            .set_tooltip_text(line.filter(|l| !l.is_empty()));
+        imp.label_mem_source.set_visible(line.is_some());
+        imp.separator_mem_source.set_visible(line.is_some());
+    }
+
     pub fn set_float_address(&self, s: &str) {
         self.imp()
             .label_mem_float_addr
@@ -5,8 +5,13 @@ use async_channel::{Receiver, Sender};
 use cairo::glib::clone;
 use log::{debug, error};
 use once_cell::sync::Lazy;
-use processor::{errors::ProcessorError, Processor};
+use processor::{
+    errors::ProcessorError,
+    runner::{Frame, Runner},
+    Processor,
+};
 use std::{
+    panic::{self, AssertUnwindSafe},
     sync::{Arc, Mutex},
     thread,
     time::Duration,
@@ -34,6 +39,7 @@ impl Default for RunMode {
 pub struct ProcessorManager {
     pub mode: Arc<Mutex<RunMode>>,
     pub processor: Arc<Mutex<Processor>>,
+    pub runner: Arc<Runner>,
     pub error: Arc<Mutex<Option<ProcessorError>>>,
     pub tx: Option<Sender<Option<ProcessorError>>>,
     pub rx: Option<Receiver<bool>>,
@@ -48,9 +54,11 @@ impl Default for ProcessorManager {
     fn default() -> Self {
         let (status_notification_send, status_notification_recv) = async_channel::bounded(1);
         let (mode_notification_send, mode_notification_recv) = async_channel::bounded(1);
+        let processor: Arc<Mutex<Processor>> = Default::default();
         Self {
             mode: Default::default(),
-            processor: Default::default(),
+            runner: Arc::new(Runner::new(processor.clone())),
+            processor,
             error: Arc::new(Mutex::new(None)),
             tx: None,
             rx: None,
@@ -83,6 +91,94 @@ impl ProcessorManager {
         }
     }
 
+    /// Executa `f` com o [`Runner`] em outra *thread*, publicando em `frames` os estados do
+    /// processador. Quadros intermediários são descartados se a interface ainda não consumiu o
+    /// anterior; o último é sempre entregue. Ao fim da execução, o resultado é enviado em
+    /// `status_notification_send`, mesmo que `f` entre em pânico, para que a interface nunca
+    /// fique aguardando uma execução que já terminou. Os pedidos de parada anteriores são
+    /// descartados antes de criar a *thread*, de forma que um [`ProcessorManager::stop`] feito
+    /// logo depois sempre interrompe a execução.
+    fn spawn<F>(&self, frames: Sender<Frame>, f: F)
+    where
+        F: FnOnce(&Runner, &mut dyn FnMut(Frame)) -> Result<Frame, ProcessorError> + Send + 'static,
+    {
+        self.runner.clear_stop();
+        let runner = self.runner.clone();
+        let processor = self.processor.clone();
+        let status = self.status_notification_send.clone();
+
+        thread::spawn(move || {
+            let result = panic::catch_unwind(AssertUnwindSafe(|| {
+                f(&runner, &mut |f| {
+                    let _ = frames.try_send(f);
+                })
+            }))
+            .unwrap_or_else(|_| {
+                // O pânico envenena o processador se ocorrer com ele bloqueado; o estado fica
+                // acessível para que a interface possa exibi-lo e reiniciar o processador.
+                processor.clear_poison();
+                Err(ProcessorError::Generic {
+                    title: "Falha interna".to_string(),
+                    description: "A execução do processador foi interrompida inesperadamente."
+                        .to_string(),
+                })
+            });
+
+            let error = match result {
+                Ok(f) => {
+                    let _ = frames.send_blocking(f);
+                    None
+                }
+                Err(e) => {
+                    error!("{}", e);
+                    Some(e)
+                }
+            };
+
+            if status.send_blocking(error).is_err() {
+                error!("Falha ao notificar o fim da execução");
+            }
+        });
+    }
+
+    /// Executa o processador continuamente em outra *thread*, em lotes de ciclos, publicando
+    /// o seu estado em `frames` à taxa de quadros do [`Runner`].
+    pub fn run(&self, frames: Sender<Frame>) {
+        self.spawn(frames, |runner, publish| runner.run(publish));
+    }
+
+    /// Executa uma instrução, pulando as sub-rotinas chamadas por ela. Veja
+    /// [`Runner::step_over`].
+    pub fn step_over(&self, frames: Sender<Frame>) {
+        self.spawn(frames, |runner, publish| runner.step_over(publish));
+    }
+
+    /// Executa até o retorno da sub-rotina atual. Veja [`Runner::step_out`].
+    pub fn step_out(&self, frames: Sender<Frame>) {
+        self.spawn(frames, |runner, publish| runner.step_out(publish));
+    }
+
+    /// Executa uma única instrução na *thread* atual.
+    pub fn step(&self) -> Result<Frame, ProcessorError> {
+        self.runner.step()
+    }
+
+    /// Interrompe a execução e restaura o processador para a última imagem carregada. Retorna
+    /// `false` caso nenhuma imagem tenha sido carregada.
+    pub fn restart(&self) -> Result<bool, ProcessorError> {
+        self.stop();
+        let mut p = self.processor.lock().map_err(|_| ProcessorError::Generic {
+            title: "Poison Error".to_string(),
+            description: "Falha ao acessar o processador!".to_string(),
+        })?;
+        Ok(p.reload())
+    }
+
+    /// Interrompe a execução iniciada por [`ProcessorManager::run`].
+    pub fn stop(&self) {
+        self.runner.stop();
+    }
+
     // pub fn run(&self, tx: Sender<InfoType<ProcessorError>>) {
     //     let m = self.mode.clone();
     //     let p = self.processor.clone();
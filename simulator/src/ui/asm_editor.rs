@@ -0,0 +1,343 @@
+mod imp {
+    use std::cell::RefCell;
+
+    use adw::glib;
+    use adw::prelude::*;
+    use adw::subclass::prelude::*;
+    use gtk::CompositeTemplate;
+
+    #[derive(Default, CompositeTemplate)]
+    #[template(resource = "/br/com/processador/asm-editor.ui")]
+    pub struct AsmEditor {
+        #[template_child]
+        pub text_view: TemplateChild<gtk::TextView>,
+        #[template_child]
+        pub label_status: TemplateChild<gtk::Label>,
+
+        // Realce agendado pela última alteração do código, ainda não aplicado.
+        pub pending_highlight: RefCell<Option<glib::SourceId>>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for AsmEditor {
+        // `NAME` needs to match `class` attribute of template
+        const NAME: &'static str = "AsmEditor";
+        type Type = super::AsmEditor;
+        type ParentType = gtk::Box;
+
+        fn class_init(klass: &mut Self::Class) {
+            klass.bind_template();
+            klass.bind_template_callbacks();
+        }
+
+        fn instance_init(obj: &glib::subclass::InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    #[gtk::template_callbacks]
+    impl AsmEditor {
+        #[template_callback]
+        fn button_assemble_clicked(&self, _button: gtk::Button) {
+            self.obj().assemble_and_load();
+        }
+    }
+
+    // Trait shared by all GObjects
+    impl ObjectImpl for AsmEditor {
+        fn constructed(&self) {
+            self.parent_constructed();
+
+            let buffer = self.text_view.buffer();
+            super::create_tags(&buffer);
+
+            let editor = self.obj();
+            buffer.connect_changed(glib::clone!(@weak editor => move |_| {
+                editor.schedule_highlight();
+                editor.set_status(None);
+            }));
+        }
+    }
+
+    // Trait shared by all widgets
+    impl WidgetImpl for AsmEditor {}
+
+    impl BoxImpl for AsmEditor {}
+}
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use adw::glib;
+use adw::prelude::*;
+use adw::subclass::prelude::*;
+
+use assembler::{linker::Linker, AssemblerError, SpannedToken, TokenKind};
+
+use super::simulator_window::SimulatorWindow;
+
+/// Nome do módulo gerado a partir do código do editor.
+const MODULE_NAME: &str = "main";
+
+/// Intervalo sem alterações no código após o qual o realce é refeito.
+const HIGHLIGHT_DELAY: Duration = Duration::from_millis(150);
+
+/// *Tag* aplicada aos *tokens* inválidos e à posição do último erro de montagem.
+const ERROR_TAG: &str = "error";
+const COMMENT_TAG: &str = "comment";
+
+/// Nome da *tag* de realce de cada [`TokenKind`].
+fn tag_name(kind: TokenKind) -> &'static str {
+    match kind {
+        TokenKind::Instruction => "instruction",
+        TokenKind::Identifier => "identifier",
+        TokenKind::Literal => "literal",
+        TokenKind::Directive => "directive",
+        TokenKind::Register => "register",
+        TokenKind::Punctuation => "punctuation",
+    }
+}
+
+/// Cria as *tags* de realce no `buffer`.
+fn create_tags(buffer: &gtk::TextBuffer) {
+    let table = buffer.tag_table();
+    let tags = [
+        gtk::TextTag::builder()
+            .name(tag_name(TokenKind::Instruction))
+            .foreground("#1c71d8")
+            .weight(700)
+            .build(),
+        gtk::TextTag::builder()
+            .name(tag_name(TokenKind::Identifier))
+            .foreground("#9141ac")
+            .build(),
+        gtk::TextTag::builder()
+            .name(tag_name(TokenKind::Literal))
+            .foreground("#c64600")
+            .build(),
+        gtk::TextTag::builder()
+            .name(tag_name(TokenKind::Directive))
+            .foreground("#26a269")
+            .weight(700)
+            .build(),
+        gtk::TextTag::builder()
+            .name(tag_name(TokenKind::Register))
+            .foreground("#2190a4")
+            .build(),
+        gtk::TextTag::builder()
+            .name(tag_name(TokenKind::Punctuation))
+            .build(),
+        gtk::TextTag::builder()
+            .name(COMMENT_TAG)
+            .foreground("#77767b")
+            .style(gtk::pango::Style::Italic)
+            .build(),
+        gtk::TextTag::builder()
+            .name(ERROR_TAG)
+            .underline(gtk::pango::Underline::Error)
+            .build(),
+    ];
+
+    for tag in tags {
+        table.add(&tag);
+    }
+}
+
+/// Retorna a coluna, contada a partir de 1, em que começa o comentário de `line`, ignorando os
+/// `;` dentro dos *tokens* da linha.
+fn comment_column(line: &str, tokens: &[&SpannedToken]) -> Option<usize> {
+    line.chars()
+        .enumerate()
+        .map(|(i, c)| (i + 1, c))
+        .filter(|&(_, c)| c == ';')
+        .map(|(column, _)| column)
+        .find(|&column| {
+            !tokens
+                .iter()
+                .any(|t| (t.column..t.column + t.length).contains(&column))
+        })
+}
+
+glib::wrapper! {
+    pub struct AsmEditor(ObjectSubclass<imp::AsmEditor>)
+    @extends gtk::Box, gtk::Widget,
+    @implements gtk::Accessible, gtk::Buildable, gtk::ConstraintTarget, gtk::Orientable;
+}
+
+impl AsmEditor {
+    pub fn build() -> Self {
+        glib::Object::builder().build()
+    }
+
+    /// Retorna o código presente no editor.
+    pub fn source(&self) -> String {
+        let buffer = self.imp().text_view.buffer();
+        let (start, end) = buffer.bounds();
+        buffer.text(&start, &end, false).to_string()
+    }
+
+    /// Substitui o código presente no editor.
+    pub fn set_source(&self, source: &str) {
+        self.imp().text_view.buffer().set_text(source);
+    }
+
+    /// Aplica `tag` aos `length` caracteres a partir da `line` e `column`, contadas a partir
+    /// de 1. Com `length` nulo, a *tag* é aplicada até o fim da linha.
+    fn apply_tag(&self, tag: &str, line: usize, column: usize, length: usize) {
+        let buffer = self.imp().text_view.buffer();
+        let Some(start) = buffer.iter_at_line_offset(
+            line.saturating_sub(1) as i32,
+            column.saturating_sub(1) as i32,
+        ) else {
+            return;
+        };
+
+        let mut end = start;
+        match length {
+            0 => {
+                end.forward_to_line_end();
+            }
+            n => {
+                end.forward_chars(n as i32);
+            }
+        }
+        buffer.apply_tag_by_name(tag, &start, &end);
+    }
+
+    /// Agenda o realce de sintaxe para quando o código ficar [`HIGHLIGHT_DELAY`] sem alterações,
+    /// para que o código não seja analisado a cada tecla.
+    fn schedule_highlight(&self) {
+        let imp = self.imp();
+        if let Some(source) = imp.pending_highlight.take() {
+            source.remove();
+        }
+
+        let source = glib::timeout_add_local_once(
+            HIGHLIGHT_DELAY,
+            glib::clone!(@weak self as editor => move || {
+                editor.imp().pending_highlight.take();
+                editor.highlight();
+            }),
+        );
+        *imp.pending_highlight.borrow_mut() = Some(source);
+    }
+
+    /// Aplica imediatamente o realce agendado por [`AsmEditor::schedule_highlight`], caso haja.
+    fn flush_highlight(&self) {
+        if let Some(source) = self.imp().pending_highlight.take() {
+            source.remove();
+            self.highlight();
+        }
+    }
+
+    /// Refaz o realce de sintaxe de todo o código, a partir dos *tokens* do montador.
+    fn highlight(&self) {
+        let buffer = self.imp().text_view.buffer();
+        let (start, end) = buffer.bounds();
+        buffer.remove_all_tags(&start, &end);
+
+        let source = self.source();
+        let tokens = assembler::tokenize(&source);
+
+        // Tokens de cada linha, indexados a partir de 0.
+        let mut lines: Vec<Vec<&SpannedToken>> = vec![Vec::new(); source.lines().count()];
+        for t in tokens.iter() {
+            let tag = match &t.token {
+                Ok(token) => tag_name(token.kind()),
+                Err(_) => ERROR_TAG,
+            };
+            self.apply_tag(tag, t.line, t.column, t.length);
+
+            if let Some(line) = lines.get_mut(t.line.saturating_sub(1)) {
+                line.push(t);
+            }
+        }
+
+        for (i, (line, line_tokens)) in source.lines().zip(&lines).enumerate() {
+            if let Some(column) = comment_column(line, line_tokens) {
+                self.apply_tag(COMMENT_TAG, i + 1, column, 0);
+            }
+        }
+    }
+
+    /// Mostra `status` na barra do editor. Com [`None`], a barra é limpa.
+    fn set_status(&self, status: Option<&str>) {
+        let label = &self.imp().label_status;
+        match status {
+            Some(s) => {
+                label.set_markup(s);
+                label.set_tooltip_markup(Some(s));
+            }
+            None => {
+                label.set_text("");
+                label.set_tooltip_text(None);
+            }
+        }
+    }
+
+    /// Marca a posição de `error` no código e posiciona o cursor nela.
+    fn mark_error(&self, error: &AssemblerError) {
+        let (line, column) = error.position();
+        let length = assembler::tokenize(&self.source())
+            .into_iter()
+            .find(|t| t.line == line && t.column == column)
+            .map(|t| t.length)
+            .unwrap_or(0);
+        self.apply_tag(ERROR_TAG, line, column, length);
+
+        let buffer = self.imp().text_view.buffer();
+        if let Some(mut iter) = buffer.iter_at_line_offset(
+            line.saturating_sub(1) as i32,
+            column.saturating_sub(1) as i32,
+        ) {
+            buffer.place_cursor(&iter);
+            self.imp()
+                .text_view
+                .scroll_to_iter(&mut iter, 0.1, false, 0.0, 0.0);
+        }
+
+        self.set_status(Some(&format!(
+            "<b>Erro</b> {}",
+            glib::markup_escape_text(&error.to_string())
+        )));
+    }
+
+    /// Monta e liga o código do editor e, caso não haja erros, carrega a imagem no processador.
+    /// Retorna se o programa foi carregado.
+    pub fn assemble_and_load(&self) -> bool {
+        // O realce agendado apagaria a marcação do erro.
+        self.flush_highlight();
+
+        let source = self.source();
+        let module = match assembler::assemble(MODULE_NAME, &source) {
+            Ok(module) => module,
+            Err(e) => {
+                self.mark_error(&e);
+                return false;
+            }
+        };
+
+        let image = match Linker::new().add(module).link() {
+            Ok(image) => image,
+            Err(e) => {
+                self.set_status(Some(&format!(
+                    "<b>Erro</b> {}",
+                    glib::markup_escape_text(&e.to_string())
+                )));
+                return false;
+            }
+        };
+
+        match self.root().and_downcast::<SimulatorWindow>() {
+            Some(window) => {
+                window.load_program(&image, &HashMap::from([(MODULE_NAME.to_string(), source)]));
+                self.set_status(Some(&format!(
+                    "Programa carregado: {} símbolos.",
+                    image.symbols.len()
+                )));
+                true
+            }
+            None => false,
+        }
+    }
+}
@@ -0,0 +1,439 @@
+mod imp {
+
+    use std::cell::{Cell, RefCell};
+    use std::rc::Rc;
+
+    use adw::glib;
+    use adw::prelude::*;
+    use adw::subclass::prelude::*;
+    use gtk::CompositeTemplate;
+
+    use crate::files::charmap::Charmap;
+    use crate::ui::processor_window::processor_screen::ProcessorScreen;
+
+    #[derive(Default, CompositeTemplate)]
+    #[template(resource = "/br/com/processador/charmap-editor.ui")]
+    pub struct CharmapEditor {
+        #[template_child]
+        pub glyph_area: TemplateChild<gtk::DrawingArea>,
+        #[template_child]
+        pub pixel_area: TemplateChild<gtk::DrawingArea>,
+        #[template_child]
+        pub preview_area: TemplateChild<gtk::DrawingArea>,
+        #[template_child]
+        pub label_glyph: TemplateChild<gtk::Label>,
+
+        // Charmap editado, compartilhado com a tela do processador.
+        pub charmap: RefCell<Rc<RefCell<Charmap>>>,
+        pub screen: glib::WeakRef<ProcessorScreen>,
+
+        // Caractere selecionado e caractere copiado.
+        pub selected: Cell<usize>,
+        pub clipboard: RefCell<Option<Vec<u8>>>,
+
+        // Valor pintado nos pixels durante o arrasto do mouse.
+        pub painting: Cell<bool>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for CharmapEditor {
+        // `NAME` needs to match `class` attribute of template
+        const NAME: &'static str = "CharmapEditor";
+        type Type = super::CharmapEditor;
+        type ParentType = gtk::Box;
+
+        fn class_init(klass: &mut Self::Class) {
+            klass.bind_template();
+
+            klass.install_action("charmap.copy", None, |editor, _, _| editor.copy_glyph());
+            klass.install_action("charmap.paste", None, |editor, _, _| editor.paste_glyph());
+            klass.install_action("charmap.clear", None, |editor, _, _| {
+                editor.set_glyph(&[0; super::GLYPH_SIZE])
+            });
+            klass.install_action("charmap.import", None, |editor, _, _| editor.import_mif());
+            klass.install_action("charmap.export", None, |editor, _, _| editor.export_mif());
+        }
+
+        fn instance_init(obj: &glib::subclass::InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    // Trait shared by all GObjects
+    impl ObjectImpl for CharmapEditor {
+        fn constructed(&self) {
+            self.parent_constructed();
+            let obj = self.obj();
+
+            self.glyph_area
+                .set_content_width(super::GLYPHS_PER_ROW as i32 * super::GLYPH_CELL as i32);
+            self.glyph_area.set_content_height(
+                (super::GLYPHS / super::GLYPHS_PER_ROW) as i32 * super::GLYPH_CELL as i32,
+            );
+            self.glyph_area
+                .set_draw_func(glib::clone!(@weak obj as editor => move |_, cr, _, _| {
+                    editor.draw_glyphs(cr);
+                }));
+
+            self.pixel_area
+                .set_content_width((super::GLYPH_WIDTH as f64 * super::PIXEL_CELL) as i32);
+            self.pixel_area
+                .set_content_height((super::GLYPH_WIDTH as f64 * super::PIXEL_CELL) as i32);
+            self.pixel_area
+                .set_draw_func(glib::clone!(@weak obj as editor => move |_, cr, _, _| {
+                    editor.draw_pixels(cr);
+                }));
+
+            self.preview_area
+                .set_content_width((super::PREVIEW_COLUMNS as f64 * super::PREVIEW_CELL) as i32);
+            self.preview_area.set_content_height(
+                (super::COLORS / super::PREVIEW_COLUMNS) as i32 * super::PREVIEW_CELL as i32,
+            );
+            self.preview_area.set_draw_func(
+                glib::clone!(@weak obj as editor => move |_, cr, _, _| {
+                    editor.draw_preview(cr);
+                }),
+            );
+
+            // Seleção de caractere
+            let click = gtk::GestureClick::new();
+            click.connect_pressed(glib::clone!(@weak obj as editor => move |_, _, x, y| {
+                editor.select_at(x, y);
+            }));
+            self.glyph_area.add_controller(click);
+
+            // Pintura dos pixels: o primeiro pixel arrastado define se os demais são acesos ou
+            // apagados.
+            let drag = gtk::GestureDrag::new();
+            drag.connect_drag_begin(glib::clone!(@weak obj as editor => move |_, x, y| {
+                editor.begin_paint(x, y);
+            }));
+            drag.connect_drag_update(glib::clone!(@weak obj as editor => move |gesture, dx, dy| {
+                if let Some((x, y)) = gesture.start_point() {
+                    editor.paint(x + dx, y + dy);
+                }
+            }));
+            self.pixel_area.add_controller(drag);
+
+            obj.update_label();
+        }
+    }
+
+    // Trait shared by all widgets
+    impl WidgetImpl for CharmapEditor {}
+
+    impl BoxImpl for CharmapEditor {}
+}
+
+use adw::prelude::*;
+use adw::subclass::prelude::*;
+use adw::{gio, glib};
+use log::error;
+use processor::instructions::Color;
+
+use crate::files::charmap::{Charmap, GLYPHS, GLYPH_SIZE, GLYPH_WIDTH};
+
+use super::processor_window::processor_screen::ProcessorScreen;
+use super::simulator_window::SimulatorWindow;
+
+/// Número de cores da tela do processador.
+const COLORS: usize = 16;
+
+/// Caracteres por linha na lista de caracteres.
+const GLYPHS_PER_ROW: usize = 16;
+
+/// Tamanho, em pixels da tela, de cada caractere da lista, incluindo o espaçamento.
+const GLYPH_CELL: f64 = 28.0;
+
+/// Tamanho, em pixels da tela, de cada pixel do caractere em edição.
+const PIXEL_CELL: f64 = 32.0;
+
+/// Colunas e tamanho de cada caractere na prévia das cores.
+const PREVIEW_COLUMNS: usize = 4;
+const PREVIEW_CELL: f64 = 48.0;
+
+glib::wrapper! {
+    pub struct CharmapEditor(ObjectSubclass<imp::CharmapEditor>)
+    @extends gtk::Box, gtk::Widget,
+    @implements gtk::Accessible, gtk::Buildable, gtk::ConstraintTarget, gtk::Orientable;
+}
+
+/// Retorna a cor `i` da tela, na ordem dos códigos do `OUTCHAR`.
+fn color(i: usize) -> (f64, f64, f64) {
+    let (r, g, b, _) = Color::from_outchar(i << 8)
+        .expect("Esperado um código de cor válido")
+        .rgba();
+    (r as f64 / 255.0, g as f64 / 255.0, b as f64 / 255.0)
+}
+
+/// Desenha `glyph` em (`x`, `y`), com cada pixel de tamanho `size`.
+fn draw_glyph(cr: &cairo::Context, glyph: &[u8], x: f64, y: f64, size: f64) {
+    for (i, _) in glyph.iter().enumerate().filter(|(_, &p)| p == 1) {
+        cr.rectangle(
+            x + (i % GLYPH_WIDTH) as f64 * size,
+            y + (i / GLYPH_WIDTH) as f64 * size,
+            size,
+            size,
+        );
+    }
+    let _ = cr.fill().map_err(|e| error!("{e}"));
+}
+
+impl CharmapEditor {
+    pub fn build() -> Self {
+        glib::Object::builder().build()
+    }
+
+    /// Passa a editar o charmap da tela `screen`, que é redesenhada a cada alteração.
+    pub fn attach(&self, screen: &ProcessorScreen) {
+        self.imp().charmap.replace(screen.charmap());
+        self.imp().screen.set(Some(screen));
+        self.changed();
+    }
+
+    /// Substitui todos os caracteres do charmap editado.
+    pub fn set_charmap(&self, charmap: Charmap) {
+        *self.imp().charmap.borrow().borrow_mut() = charmap;
+        self.changed();
+    }
+
+    /// Redesenha o editor e a tela do processador após uma alteração do charmap.
+    fn changed(&self) {
+        let imp = self.imp();
+        imp.glyph_area.queue_draw();
+        imp.pixel_area.queue_draw();
+        imp.preview_area.queue_draw();
+        if let Some(screen) = imp.screen.upgrade() {
+            screen.queue_draw();
+        }
+    }
+
+    fn update_label(&self) {
+        let selected = self.imp().selected.get();
+        let ch = char::from_u32(selected as u32)
+            .filter(|c| c.is_ascii_graphic())
+            .map(|c| format!(" ({})", glib::markup_escape_text(&c.to_string())))
+            .unwrap_or_default();
+        self.imp()
+            .label_glyph
+            .set_markup(&format!("<b>Glyph {}{}</b>", selected, ch));
+    }
+
+    fn select_at(&self, x: f64, y: f64) {
+        let index = (y / GLYPH_CELL) as usize * GLYPHS_PER_ROW + (x / GLYPH_CELL) as usize;
+        if index < GLYPHS {
+            self.imp().selected.set(index);
+            self.update_label();
+            self.changed();
+        }
+    }
+
+    /// Retorna o pixel do caractere em edição na posição (`x`, `y`) da área de edição.
+    fn pixel_at(x: f64, y: f64) -> Option<(usize, usize)> {
+        let (px, py) = ((x / PIXEL_CELL).floor(), (y / PIXEL_CELL).floor());
+        let range = 0.0..GLYPH_WIDTH as f64;
+        (range.contains(&px) && range.contains(&py)).then_some((px as usize, py as usize))
+    }
+
+    fn begin_paint(&self, x: f64, y: f64) {
+        if let Some((px, py)) = Self::pixel_at(x, y) {
+            let on = !self
+                .imp()
+                .charmap
+                .borrow()
+                .borrow()
+                .pixel(self.imp().selected.get(), px, py);
+            self.imp().painting.set(on);
+            self.paint(x, y);
+        }
+    }
+
+    fn paint(&self, x: f64, y: f64) {
+        if let Some((px, py)) = Self::pixel_at(x, y) {
+            self.imp().charmap.borrow().borrow_mut().set_pixel(
+                self.imp().selected.get(),
+                px,
+                py,
+                self.imp().painting.get(),
+            );
+            self.changed();
+        }
+    }
+
+    fn set_glyph(&self, glyph: &[u8]) {
+        self.imp()
+            .charmap
+            .borrow()
+            .borrow_mut()
+            .set_glyph(self.imp().selected.get(), glyph);
+        self.changed();
+    }
+
+    fn copy_glyph(&self) {
+        let glyph = self
+            .imp()
+            .charmap
+            .borrow()
+            .borrow()
+            .glyph(self.imp().selected.get())
+            .to_vec();
+        self.imp().clipboard.replace(Some(glyph));
+    }
+
+    fn paste_glyph(&self) {
+        let glyph = self.imp().clipboard.borrow().clone();
+        if let Some(glyph) = glyph {
+            self.set_glyph(&glyph);
+        }
+    }
+
+    fn draw_glyphs(&self, cr: &cairo::Context) {
+        let charmap = self.imp().charmap.borrow();
+        let charmap = charmap.borrow();
+        let selected = self.imp().selected.get();
+        let size = (GLYPH_CELL - 4.0) / GLYPH_WIDTH as f64;
+
+        for i in 0..GLYPHS {
+            let x = (i % GLYPHS_PER_ROW) as f64 * GLYPH_CELL;
+            let y = (i / GLYPHS_PER_ROW) as f64 * GLYPH_CELL;
+
+            match i == selected {
+                true => cr.set_source_rgb(0.21, 0.52, 0.89),
+                false => cr.set_source_rgb(0.0, 0.0, 0.0),
+            }
+            cr.rectangle(x + 1.0, y + 1.0, GLYPH_CELL - 2.0, GLYPH_CELL - 2.0);
+            let _ = cr.fill().map_err(|e| error!("{e}"));
+
+            cr.set_source_rgb(1.0, 1.0, 1.0);
+            draw_glyph(cr, charmap.glyph(i), x + 2.0, y + 2.0, size);
+        }
+    }
+
+    fn draw_pixels(&self, cr: &cairo::Context) {
+        let charmap = self.imp().charmap.borrow();
+        let charmap = charmap.borrow();
+        let side = GLYPH_WIDTH as f64 * PIXEL_CELL;
+
+        cr.set_source_rgb(0.0, 0.0, 0.0);
+        cr.rectangle(0.0, 0.0, side, side);
+        let _ = cr.fill().map_err(|e| error!("{e}"));
+
+        cr.set_source_rgb(1.0, 1.0, 1.0);
+        draw_glyph(
+            cr,
+            charmap.glyph(self.imp().selected.get()),
+            0.0,
+            0.0,
+            PIXEL_CELL,
+        );
+
+        // Grade
+        cr.set_source_rgba(0.5, 0.5, 0.5, 0.6);
+        cr.set_line_width(1.0);
+        for i in 0..=GLYPH_WIDTH {
+            let pos = i as f64 * PIXEL_CELL;
+            cr.move_to(pos, 0.0);
+            cr.line_to(pos, side);
+            cr.move_to(0.0, pos);
+            cr.line_to(side, pos);
+        }
+        let _ = cr.stroke().map_err(|e| error!("{e}"));
+    }
+
+    fn draw_preview(&self, cr: &cairo::Context) {
+        let charmap = self.imp().charmap.borrow();
+        let charmap = charmap.borrow();
+        let glyph = charmap.glyph(self.imp().selected.get());
+        let size = (PREVIEW_CELL - 8.0) / GLYPH_WIDTH as f64;
+
+        // Fundo cinza escuro, para que a cor preta também fique visível.
+        cr.set_source_rgb(0.2, 0.2, 0.2);
+        cr.rectangle(
+            0.0,
+            0.0,
+            PREVIEW_COLUMNS as f64 * PREVIEW_CELL,
+            (COLORS / PREVIEW_COLUMNS) as f64 * PREVIEW_CELL,
+        );
+        let _ = cr.fill().map_err(|e| error!("{e}"));
+
+        for i in 0..COLORS {
+            let (r, g, b) = color(i);
+            cr.set_source_rgb(r, g, b);
+            draw_glyph(
+                cr,
+                glyph,
+                (i % PREVIEW_COLUMNS) as f64 * PREVIEW_CELL + 4.0,
+                (i / PREVIEW_COLUMNS) as f64 * PREVIEW_CELL + 4.0,
+                size,
+            );
+        }
+    }
+
+    /// Pede um arquivo MIF ao usuário e substitui o charmap pelo conteúdo dele.
+    fn import_mif(&self) {
+        let dialog = gtk::FileDialog::builder()
+            .title("Import charmap")
+            .modal(true)
+            .build();
+
+        let editor = self.clone();
+        dialog.open(
+            self.root().and_downcast_ref::<gtk::Window>(),
+            gio::Cancellable::NONE,
+            move |file| {
+                let Some(path) = file.ok().and_then(|f| f.path()) else {
+                    return;
+                };
+
+                let charmap = std::fs::File::open(&path)
+                    .and_then(|f| Charmap::read_mif(std::io::BufReader::new(f)));
+
+                match charmap {
+                    Ok(charmap) => editor.set_charmap(charmap),
+                    Err(e) => SimulatorWindow::show_error(
+                        &editor,
+                        "Falha ao importar o charmap",
+                        &glib::markup_escape_text(&e.to_string()),
+                    ),
+                }
+            },
+        );
+    }
+
+    /// Pede um arquivo ao usuário e salva nele o charmap no formato MIF.
+    fn export_mif(&self) {
+        let dialog = gtk::FileDialog::builder()
+            .title("Export charmap")
+            .initial_name("charmap.mif")
+            .modal(true)
+            .build();
+
+        let editor = self.clone();
+        dialog.save(
+            self.root().and_downcast_ref::<gtk::Window>(),
+            gio::Cancellable::NONE,
+            move |file| {
+                let Some(path) = file.ok().and_then(|f| f.path()) else {
+                    return;
+                };
+
+                let result = std::fs::File::create(&path).and_then(|f| {
+                    editor
+                        .imp()
+                        .charmap
+                        .borrow()
+                        .borrow()
+                        .write_mif(&mut std::io::BufWriter::new(f))
+                });
+
+                if let Err(e) = result {
+                    SimulatorWindow::show_error(
+                        &editor,
+                        "Falha ao exportar o charmap",
+                        &glib::markup_escape_text(&e.to_string()),
+                    );
+                }
+            },
+        );
+    }
+}
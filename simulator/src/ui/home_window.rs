@@ -0,0 +1,181 @@
+mod imp {
+
+    use adw::glib;
+    use adw::prelude::*;
+    use adw::subclass::prelude::*;
+    use gtk::CompositeTemplate;
+
+    #[derive(Default, CompositeTemplate)]
+    #[template(resource = "/br/com/processador/home-window.ui")]
+    pub struct HomeWindow {
+        #[template_child]
+        pub list_recent: TemplateChild<gtk::ListBox>,
+        #[template_child]
+        pub list_examples: TemplateChild<gtk::ListBox>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for HomeWindow {
+        // `NAME` needs to match `class` attribute of template
+        const NAME: &'static str = "HomeWindow";
+        type Type = super::HomeWindow;
+        type ParentType = gtk::Box;
+
+        fn class_init(klass: &mut Self::Class) {
+            klass.bind_template();
+
+            klass.install_action("home.open-program", None, |home, _, _| home.open_program());
+            klass.install_action("home.open-charmap", None, |home, _, _| home.open_charmap());
+        }
+
+        fn instance_init(obj: &glib::subclass::InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    // Trait shared by all GObjects
+    impl ObjectImpl for HomeWindow {
+        fn constructed(&self) {
+            self.parent_constructed();
+            self.obj().fill_examples();
+            self.obj().update_recent();
+        }
+    }
+
+    // Trait shared by all widgets
+    impl WidgetImpl for HomeWindow {}
+
+    impl BoxImpl for HomeWindow {}
+}
+
+use std::path::{Path, PathBuf};
+
+use adw::prelude::*;
+use adw::subclass::prelude::*;
+use adw::{gio, glib};
+
+use crate::files::recent;
+
+use super::simulator_window::SimulatorWindow;
+
+/// Programas de exemplo distribuídos com o simulador: nome, descrição e código.
+const EXAMPLES: &[(&str, &str, &str)] = &[
+    (
+        "Hello, world!",
+        "Writes a message on the screen",
+        include_str!("../../resources/examples/hello.asm"),
+    ),
+    (
+        "Colors",
+        "Writes a character in each of the 16 screen colors",
+        include_str!("../../resources/examples/colors.asm"),
+    ),
+    (
+        "Fibonacci",
+        "Computes the Fibonacci sequence using a subroutine",
+        include_str!("../../resources/examples/fibonacci.asm"),
+    ),
+];
+
+glib::wrapper! {
+    pub struct HomeWindow(ObjectSubclass<imp::HomeWindow>)
+    @extends gtk::Box, gtk::Widget,
+    @implements gtk::Accessible, gtk::Buildable, gtk::ConstraintTarget, gtk::Orientable;
+}
+
+impl HomeWindow {
+    pub fn build() -> Self {
+        glib::Object::builder().build()
+    }
+
+    fn window(&self) -> Option<SimulatorWindow> {
+        self.root().and_downcast::<SimulatorWindow>()
+    }
+
+    fn fill_examples(&self) {
+        for &(name, description, source) in EXAMPLES {
+            let row = adw::ActionRow::builder()
+                .title(name)
+                .subtitle(description)
+                .activatable(true)
+                .build();
+            row.add_suffix(&gtk::Image::from_icon_name("go-next-symbolic"));
+            row.connect_activated(glib::clone!(@weak self as home => move |_| {
+                if let Some(window) = home.window() {
+                    window.open_source(source);
+                }
+            }));
+            self.imp().list_examples.append(&row);
+        }
+    }
+
+    /// Refaz a lista de projetos recentes.
+    pub fn update_recent(&self) {
+        let list = &self.imp().list_recent;
+        list.remove_all();
+
+        for path in recent::load() {
+            let title = path
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default();
+            let row = adw::ActionRow::builder()
+                .title(glib::markup_escape_text(&title).as_str())
+                .subtitle(glib::markup_escape_text(&path.display().to_string()).as_str())
+                .activatable(true)
+                .build();
+            row.add_suffix(&gtk::Image::from_icon_name("go-next-symbolic"));
+            row.connect_activated(glib::clone!(@weak self as home => move |_| {
+                home.open(&path);
+            }));
+            list.append(&row);
+        }
+    }
+
+    /// Abre `path` na janela do simulador, que o adiciona aos projetos recentes.
+    fn open(&self, path: &Path) {
+        if let Some(window) = self.window() {
+            window.open_file(path);
+        }
+    }
+
+    /// Pede ao usuário um arquivo que satisfaça `filter` e o abre.
+    fn choose_file(&self, title: &str, filter: &gtk::FileFilter) {
+        let filters = gio::ListStore::new::<gtk::FileFilter>();
+        filters.append(filter);
+
+        let dialog = gtk::FileDialog::builder()
+            .title(title)
+            .filters(&filters)
+            .default_filter(filter)
+            .modal(true)
+            .build();
+
+        let home = self.clone();
+        dialog.open(
+            self.root().and_downcast_ref::<gtk::Window>(),
+            gio::Cancellable::NONE,
+            move |file| {
+                let path: Option<PathBuf> = file.ok().and_then(|f| f.path());
+                if let Some(path) = path {
+                    home.open(&path);
+                }
+            },
+        );
+    }
+
+    fn open_program(&self) {
+        let filter = gtk::FileFilter::new();
+        filter.set_name(Some("Programs (.asm, .mif)"));
+        filter.add_suffix("asm");
+        filter.add_suffix("mif");
+        self.choose_file("Open program", &filter);
+    }
+
+    fn open_charmap(&self) {
+        let filter = gtk::FileFilter::new();
+        filter.set_name(Some("Charmaps (.mif)"));
+        filter.add_suffix("mif");
+        self.choose_file("Open charmap", &filter);
+    }
+}
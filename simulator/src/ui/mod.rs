@@ -1,4 +1,8 @@
+pub mod asm_editor;
+pub mod charmap_editor;
+pub mod home_window;
 pub mod mem_cell_row;
+pub mod number_format;
 pub mod processor_window;
 pub mod screen;
 pub mod simulator_window;
@@ -0,0 +1,202 @@
+use isa::{ParseWordError, Word};
+
+/// Formato de exibição dos valores do processador.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum NumberFormat {
+    Binary,
+    #[default]
+    Decimal,
+    SignedDecimal,
+    Hexadecimal,
+    Char,
+}
+
+impl NumberFormat {
+    pub const ALL: [NumberFormat; 5] = [
+        NumberFormat::Binary,
+        NumberFormat::Decimal,
+        NumberFormat::SignedDecimal,
+        NumberFormat::Hexadecimal,
+        NumberFormat::Char,
+    ];
+
+    /// Identificador usado nas ações da interface e no arquivo de preferências.
+    pub fn id(self) -> &'static str {
+        match self {
+            NumberFormat::Binary => "binary",
+            NumberFormat::Decimal => "decimal",
+            NumberFormat::SignedDecimal => "signed",
+            NumberFormat::Hexadecimal => "hexadecimal",
+            NumberFormat::Char => "char",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|f| f.id() == id)
+    }
+
+    /// Formata `w`. Caracteres não imprimíveis são mostrados em hexadecimal.
+    pub fn format(self, w: Word) -> String {
+        match self {
+            NumberFormat::Binary => format!("{:016b}", w.value()),
+            NumberFormat::Decimal => w.value().to_string(),
+            NumberFormat::SignedDecimal => (w.value() as i16).to_string(),
+            NumberFormat::Hexadecimal => format!("{:#06X}", w.value()),
+            NumberFormat::Char => match char::from_u32(w.value() as u32) {
+                Some(c) if c.is_ascii_graphic() || c == ' ' => format!("'{c}'"),
+                _ => NumberFormat::Hexadecimal.format(w),
+            },
+        }
+    }
+
+    /// Formata `w` de forma que o texto possa ser lido de volta por [`parse_word`], para os
+    /// campos editáveis. Difere de [`NumberFormat::format`] apenas no prefixo `0b` do binário.
+    pub fn literal(self, w: Word) -> String {
+        match self {
+            NumberFormat::Binary => format!("0b{}", self.format(w)),
+            _ => self.format(w),
+        }
+    }
+}
+
+/// Identificador das partes da interface que usam o formato global, no lugar de um
+/// [`NumberFormat::id`].
+pub const GLOBAL_ID: &str = "global";
+
+/// Texto com `w` em todos os formatos, usado nas dicas dos valores. O caractere só é incluído
+/// se for imprimível.
+pub fn tooltip(w: Word) -> String {
+    let mut parts: Vec<String> = [
+        NumberFormat::Hexadecimal,
+        NumberFormat::Decimal,
+        NumberFormat::SignedDecimal,
+        NumberFormat::Binary,
+    ]
+    .into_iter()
+    .map(|f| f.format(w))
+    .collect();
+
+    let c = NumberFormat::Char.format(w);
+    if c.starts_with('\'') {
+        parts.push(c);
+    }
+    parts.join("  ·  ")
+}
+
+/// Lê uma palavra digitada na interface: os formatos aceitos por [`Word`] ou um decimal
+/// negativo, em complemento de dois.
+pub fn parse_word(s: &str) -> Result<Word, ParseWordError> {
+    s.parse::<Word>().or_else(|e| {
+        s.trim()
+            .parse::<i16>()
+            .map(|v| Word(v as u16))
+            .map_err(|_| e)
+    })
+}
+
+/// Partes da interface que podem sobrescrever o formato global.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum View {
+    Registers,
+    Pointers,
+    InstructionRegister,
+    Memory,
+}
+
+impl View {
+    pub const ALL: [View; 4] = [
+        View::Registers,
+        View::Pointers,
+        View::InstructionRegister,
+        View::Memory,
+    ];
+
+    pub fn id(self) -> &'static str {
+        match self {
+            View::Registers => "registers",
+            View::Pointers => "pointers",
+            View::InstructionRegister => "ir",
+            View::Memory => "memory",
+        }
+    }
+}
+
+/// Formato global e os formatos próprios de cada [`View`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Formats {
+    pub global: NumberFormat,
+    overrides: [Option<NumberFormat>; View::ALL.len()],
+}
+
+impl Default for Formats {
+    /// Registradores em decimal, PC e SP em hexadecimal e IR e memória em binário, como nas
+    /// versões anteriores do simulador.
+    fn default() -> Self {
+        Self {
+            global: NumberFormat::Decimal,
+            overrides: [
+                None,
+                Some(NumberFormat::Hexadecimal),
+                Some(NumberFormat::Binary),
+                Some(NumberFormat::Binary),
+            ],
+        }
+    }
+}
+
+impl Formats {
+    /// Formato usado em `view`.
+    pub fn get(&self, view: View) -> NumberFormat {
+        self.overrides[view as usize].unwrap_or(self.global)
+    }
+
+    /// Formato próprio de `view`, ou [`None`] se ela usa o formato global.
+    pub fn view(&self, view: View) -> Option<NumberFormat> {
+        self.overrides[view as usize]
+    }
+
+    pub fn set_view(&mut self, view: View, format: Option<NumberFormat>) {
+        self.overrides[view as usize] = format;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_literal_round_trip() {
+        let words = [0, 1, 0x41, 0x7F, 0x1F, 0x8000, 0xFFFF].map(Word);
+        for format in NumberFormat::ALL {
+            for w in words {
+                assert_eq!(Ok(w), parse_word(&format.literal(w)), "{format:?} {w:?}");
+            }
+        }
+
+        assert_eq!("-1", NumberFormat::SignedDecimal.literal(Word(0xFFFF)));
+        assert_eq!("'A'", NumberFormat::Char.literal(Word(0x41)));
+        assert_eq!("0x000A", NumberFormat::Char.literal(Word(0x0A)));
+    }
+
+    #[test]
+    fn test_formats_overrides() {
+        let mut formats = Formats::default();
+        assert_eq!(NumberFormat::Decimal, formats.get(View::Registers));
+        assert_eq!(NumberFormat::Hexadecimal, formats.get(View::Pointers));
+        assert_eq!(None, formats.view(View::Registers));
+
+        formats.global = NumberFormat::Char;
+        assert_eq!(NumberFormat::Char, formats.get(View::Registers));
+        assert_eq!(NumberFormat::Binary, formats.get(View::Memory));
+
+        formats.set_view(View::Registers, Some(NumberFormat::SignedDecimal));
+        formats.set_view(View::Memory, None);
+        assert_eq!(NumberFormat::SignedDecimal, formats.get(View::Registers));
+        assert_eq!(
+            Some(NumberFormat::SignedDecimal),
+            formats.view(View::Registers)
+        );
+        assert_eq!(NumberFormat::Char, formats.get(View::Memory));
+        assert_eq!(None, formats.view(View::Memory));
+    }
+}
@@ -61,4 +61,29 @@ impl EntryRegister {
     pub fn set_text(&self, str: &str) {
         self.imp().entry.set_text(str);
     }
+
+    pub fn text(&self) -> glib::GString {
+        self.imp().entry.text()
+    }
+
+    pub fn set_editable(&self, editable: bool) {
+        self.imp().entry.set_editable(editable);
+    }
+
+    /// Marca o campo como inválido, mostrando `error` na dica. Com [`None`], a marcação é
+    /// removida.
+    pub fn set_error(&self, error: Option<&str>) {
+        let entry = &self.imp().entry;
+        match error {
+            Some(e) => entry.add_css_class("error"),
+            None => entry.remove_css_class("error"),
+        }
+        entry.set_tooltip_text(error);
+    }
+
+    /// Chama `f` quando o usuário confirma o valor digitado no campo (tecla *Enter*).
+    pub fn connect_value_activated<F: Fn(&Self) + 'static>(&self, f: F) {
+        let register = self.clone();
+        self.imp().entry.connect_activate(move |_| f(&register));
+    }
 }
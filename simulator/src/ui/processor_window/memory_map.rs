@@ -0,0 +1,226 @@
+mod imp {
+    use std::cell::{Cell, RefCell};
+    use std::rc::Rc;
+
+    use adw::glib;
+    use adw::prelude::*;
+    use adw::subclass::prelude::*;
+    use gtk::CompositeTemplate;
+
+    use super::Rgba;
+
+    #[derive(Default, CompositeTemplate)]
+    #[template(resource = "/br/com/processador/memory-map.ui")]
+    pub struct MemoryMap {
+        #[template_child]
+        pub drawing_map: TemplateChild<gtk::DrawingArea>,
+
+        // Cor de cada endereço, calculada na última atualização.
+        pub colors: Rc<RefCell<Vec<Rgba>>>,
+        // Ciclos de *clock* da atualização anterior à última e da última com ciclos diferentes.
+        pub cycles: Cell<(u64, u64)>,
+        pub on_activated: RefCell<Option<Rc<dyn Fn(usize)>>>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for MemoryMap {
+        // `NAME` needs to match `class` attribute of template
+        const NAME: &'static str = "MemoryMap";
+        type Type = super::MemoryMap;
+        type ParentType = gtk::Box;
+
+        fn class_init(klass: &mut Self::Class) {
+            klass.bind_template();
+        }
+
+        fn instance_init(obj: &glib::subclass::InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    // Trait shared by all GObjects
+    impl ObjectImpl for MemoryMap {
+        fn constructed(&self) {
+            self.parent_constructed();
+            let obj = self.obj();
+            let colors = self.colors.clone();
+
+            self.drawing_map.set_cursor_from_name(Some("pointer"));
+            self.drawing_map
+                .set_draw_func(move |_, cr, w, h| super::draw(cr, w, h, &colors.borrow()));
+
+            let click = gtk::GestureClick::new();
+            click.connect_released(glib::clone!(@weak obj as map => move |_, _, x, y| {
+                let Some(addr) = map.addr_at(x, y) else {
+                    return;
+                };
+                let f = map.imp().on_activated.borrow().clone();
+                if let Some(f) = f {
+                    f(addr);
+                }
+            }));
+            self.drawing_map.add_controller(click);
+
+            self.drawing_map.connect_query_tooltip(
+                glib::clone!(@weak obj as map => @default-return false,
+                    move |_, x, y, _, tooltip| {
+                        let Some(addr) = map.addr_at(x.into(), y.into()) else {
+                            return false;
+                        };
+                        tooltip.set_markup(Some(&format!(
+                            "<b>{:#06X}</b>  {}",
+                            addr,
+                            isa::memory::layout::data_area(addr)
+                        )));
+                        true
+                    }
+                ),
+            );
+        }
+    }
+
+    // Trait shared by all widgets
+    impl WidgetImpl for MemoryMap {}
+
+    impl BoxImpl for MemoryMap {}
+}
+
+use std::rc::Rc;
+
+use adw::glib;
+use adw::prelude::*;
+use adw::subclass::prelude::*;
+use log::error;
+
+use isa::memory::layout;
+use processor::MEMORY_SIZE;
+
+/// Endereços em cada linha do mapa.
+const COLUMNS: usize = 256;
+const ROWS: usize = MEMORY_SIZE / COLUMNS;
+
+type Rgba = (f64, f64, f64, f64);
+
+/// Cor de cada região de [`layout::REGIONS`], na mesma ordem.
+const REGION_COLORS: [(f64, f64, f64); layout::REGIONS.len()] = [
+    (0.21, 0.52, 0.89), // ADDR_PROG_AND_VAR
+    (0.57, 0.25, 0.67), // ADDR_STATIC_DATA
+    (0.60, 0.42, 0.27), // ADDR_DYNAMIC_DATA
+    (0.90, 0.65, 0.04), // ADDR_SYSTEM_CALL
+    (0.88, 0.11, 0.14), // ADDR_GAP_TOP_STACK
+    (0.75, 0.38, 0.80), // ADDR_STACK
+    (0.88, 0.11, 0.14), // ADDR_GAP_BOTTOM_STACK
+    (0.47, 0.46, 0.48), // ADDR_RX
+    (0.47, 0.46, 0.48), // ADDR_TX
+    (0.47, 0.46, 0.48), // ADDR_TIMER
+    (0.47, 0.46, 0.48), // ADDR_ARGS
+    (0.47, 0.46, 0.48), // ADDR_RETURN
+    (0.47, 0.46, 0.48), // ADDR_INTERRUPTIONS
+];
+
+const COLOR_EXECUTED: Rgba = (0.18, 0.76, 0.49, 1.0);
+const COLOR_WRITTEN: Rgba = (1.0, 0.47, 0.0, 1.0);
+
+/// Opacidade da cor da região nos endereços com valor zero.
+const ALPHA_ZERO: f64 = 0.3;
+
+glib::wrapper! {
+    pub struct MemoryMap(ObjectSubclass<imp::MemoryMap>)
+    @extends gtk::Box, gtk::Widget,
+    @implements gtk::Accessible, gtk::Buildable, gtk::ConstraintTarget, gtk::Orientable;
+}
+
+/// Desenha o mapa com as `colors` de cada endereço, juntando os endereços vizinhos de mesma
+/// cor de cada linha em um único retângulo.
+fn draw(cr: &cairo::Context, w: i32, h: i32, colors: &[Rgba]) {
+    let (cell_w, cell_h) = (w as f64 / COLUMNS as f64, h as f64 / ROWS as f64);
+
+    for (row, line) in colors.chunks(COLUMNS).enumerate() {
+        let mut start = 0;
+        for col in 1..=line.len() {
+            if col < line.len() && line[col] == line[start] {
+                continue;
+            }
+            let (r, g, b, a) = line[start];
+            cr.rectangle(
+                start as f64 * cell_w,
+                row as f64 * cell_h,
+                (col - start) as f64 * cell_w,
+                cell_h,
+            );
+            cr.set_source_rgba(r, g, b, a);
+            let _ = cr.fill().map_err(|e| error!("{e}"));
+            start = col;
+        }
+    }
+}
+
+impl MemoryMap {
+    pub fn build() -> Self {
+        glib::Object::builder().build()
+    }
+
+    /// Define a função chamada com o endereço clicado no mapa.
+    pub fn connect_activated<F: Fn(usize) + 'static>(&self, f: F) {
+        *self.imp().on_activated.borrow_mut() = Some(Rc::new(f));
+    }
+
+    /// Atualiza as cores do mapa com a memória de `p`. Cada endereço tem a cor da sua região,
+    /// clara quando o valor é diferente de zero, e é destacado quando foi executado ou escrito
+    /// desde a atualização anterior em que o processador executou instruções.
+    pub fn update(&self, p: &processor::Processor) {
+        let since = self.advance(p.cycles());
+        let colors = (0..MEMORY_SIZE)
+            .map(|addr| {
+                let Ok(usage) = p.usage(addr) else {
+                    return (0.0, 0.0, 0.0, 0.0);
+                };
+                if usage.written_since(since) {
+                    return COLOR_WRITTEN;
+                }
+                if usage.executed {
+                    return COLOR_EXECUTED;
+                }
+
+                let (r, g, b) = layout::region(addr)
+                    .map(|i| REGION_COLORS[i])
+                    .unwrap_or_default();
+                match usage.value.value() {
+                    0 => (r, g, b, ALPHA_ZERO),
+                    _ => (r, g, b, 1.0),
+                }
+            })
+            .collect();
+
+        *self.imp().colors.borrow_mut() = colors;
+        self.imp().drawing_map.queue_draw();
+    }
+
+    /// Registra os ciclos `now` da atualização e retorna o ciclo a partir do qual as escritas
+    /// são recentes. Atualizações sem instruções executadas mantêm o destaque anterior, e um
+    /// *reset* destaca todas as escritas.
+    fn advance(&self, now: u64) -> u64 {
+        let cycles = &self.imp().cycles;
+        let (since, last) = cycles.get();
+        let since = match now {
+            n if n == last => since,
+            n if n < last => 0,
+            _ => last,
+        };
+        cycles.set((since, now));
+        since
+    }
+
+    /// Retorna o endereço na posição (`x`, `y`) do mapa.
+    fn addr_at(&self, x: f64, y: f64) -> Option<usize> {
+        let area = &self.imp().drawing_map;
+        let (w, h) = (area.width() as f64, area.height() as f64);
+        if x < 0.0 || y < 0.0 || x >= w || y >= h {
+            return None;
+        }
+
+        let col = (x * COLUMNS as f64 / w) as usize;
+        let row = (y * ROWS as f64 / h) as usize;
+        Some(row * COLUMNS + col).filter(|&addr| addr < MEMORY_SIZE)
+    }
+}
@@ -3,6 +3,7 @@ mod imp {
     use std::borrow::Borrow;
     use std::cell::Cell;
     use std::cell::RefCell;
+    use std::collections::HashMap;
     use std::process::Output;
     use std::rc::Rc;
     use std::sync::Arc;
@@ -14,6 +15,8 @@ mod imp {
     use gtk::template_callbacks;
     use gtk::CompositeTemplate;
 
+    use crate::ui::number_format::NumberFormat;
+
     #[derive(Default, CompositeTemplate)]
     #[template(resource = "/br/com/processador/memory-view.ui")]
     pub struct MemoryView {
@@ -25,6 +28,11 @@ mod imp {
 
         pub memory_info: RefCell<Vec<super::MemoryValue>>,
         pub addr: Cell<isa::MemoryCell>,
+        pub format: Cell<NumberFormat>,
+
+        // Símbolos e linhas do código-fonte do programa carregado.
+        pub symbols: RefCell<HashMap<String, isa::MemoryCell>>,
+        pub source_lines: RefCell<HashMap<isa::MemoryCell, String>>,
     }
 
     #[glib::object_subclass]
@@ -60,6 +68,7 @@ mod imp {
 }
 
 use std::borrow::{Borrow, BorrowMut};
+use std::collections::HashMap;
 use std::rc::Rc;
 use std::sync::{Arc, Mutex};
 
@@ -68,8 +77,9 @@ use adw::subclass::prelude::*;
 use adw::{gio, glib};
 use cairo::glib::property::PropertySet;
 
-use crate::mem_row::MemoryCellRow;
-use crate::processor::instructions::InstructionDisplay;
+use crate::mem_row::{EditHandler, MemoryCellRow};
+use crate::processor::instructions::{symbol_at, InstructionDisplay};
+use crate::ui::number_format::NumberFormat;
 
 pub enum MemoryValue {
     Instruction,
@@ -101,110 +111,19 @@ impl MemoryView {
         loop {
             match addr {
                 a if isa::memory::layout::ADDR_PROG_AND_VAR.contains(&a) => {
-                    let inst = isa::Instruction::get_instruction(p.mem(a).unwrap());
-
-                    if let Ok(inst) = inst {
-                        match inst {
-                            isa::Instruction::LOAD
-                            | isa::Instruction::STORE
-                            | isa::Instruction::JZ
-                            | isa::Instruction::JC
-                            | isa::Instruction::JN
-                            | isa::Instruction::JMP
-                            | isa::Instruction::JEQ
-                            | isa::Instruction::JNE
-                            | isa::Instruction::JNZ
-                            | isa::Instruction::JNC
-                            | isa::Instruction::JGR
-                            | isa::Instruction::JLE
-                            | isa::Instruction::JEG
-                            | isa::Instruction::JEL
-                            | isa::Instruction::JOV
-                            | isa::Instruction::JNO
-                            | isa::Instruction::JDZ
-                            | isa::Instruction::CZ
-                            | isa::Instruction::CC
-                            | isa::Instruction::CN
-                            | isa::Instruction::CEQ
-                            | isa::Instruction::CNE
-                            | isa::Instruction::CNZ
-                            | isa::Instruction::CNC
-                            | isa::Instruction::CGR
-                            | isa::Instruction::CEG
-                            | isa::Instruction::CEL
-                            | isa::Instruction::COV
-                            | isa::Instruction::CNO
-                            | isa::Instruction::CDZ
-                            | isa::Instruction::CLE
-                            | isa::Instruction::CALL => {
-                                vec.push(MemoryValue::Instruction);
-                                addr += 1;
-                                vec.push(MemoryValue::Address);
-                            }
-
-                            isa::Instruction::LOADI
-                            | isa::Instruction::STOREI
-                            | isa::Instruction::MOV
-                            | isa::Instruction::INCHAR
-                            | isa::Instruction::OUTCHAR
-                            | isa::Instruction::ADD
-                            | isa::Instruction::SUB
-                            | isa::Instruction::ADDC
-                            | isa::Instruction::SUBC
-                            | isa::Instruction::MUL
-                            | isa::Instruction::DIV
-                            | isa::Instruction::INC
-                            | isa::Instruction::DEC
-                            | isa::Instruction::MOD
-                            | isa::Instruction::AND
-                            | isa::Instruction::OR
-                            | isa::Instruction::XOR
-                            | isa::Instruction::NOT
-                            | isa::Instruction::CMP
-                            | isa::Instruction::ROTL
-                            | isa::Instruction::ROTR
-                            | isa::Instruction::SHIFTL0
-                            | isa::Instruction::SHIFTL1
-                            | isa::Instruction::SHIFTR0
-                            | isa::Instruction::SHIFTR1
-                            | isa::Instruction::RTS
-                            | isa::Instruction::RTI
-                            | isa::Instruction::POP
-                            | isa::Instruction::PUSH
-                            | isa::Instruction::NOP
-                            | isa::Instruction::HALT
-                            | isa::Instruction::SETC
-                            | isa::Instruction::CLEARC
-                            | isa::Instruction::BREAKP => vec.push(MemoryValue::Instruction),
-
-                            isa::Instruction::LOADN => {
-                                vec.push(MemoryValue::Instruction);
-                                addr += 1;
-                                vec.push(MemoryValue::Data);
-                            }
-
-                            isa::Instruction::STOREN => {
-                                vec.push(MemoryValue::Instruction);
-                                addr += 1;
-                                vec.push(MemoryValue::Address);
-                                addr += 1;
-                                vec.push(MemoryValue::Data);
-                            }
-
-                            isa::Instruction::INPUT => {
-                                unimplemented!("A instrução INPUT não foi implementada!")
-                            }
-
-                            isa::Instruction::OUTPUT => {
-                                unimplemented!("A instrução OUTPUT não foi implementada!")
-                            }
-
-                            isa::Instruction::SOUND => {
-                                unimplemented!("A instrução OUTPUT não foi implementada!")
-                            }
+                    vec.push(MemoryValue::Instruction);
+
+                    // As palavras de operando seguem a instrução, na ordem do seu esquema.
+                    if let Ok(inst) =
+                        isa::Instruction::get_instruction(p.mem(a).unwrap().as_usize())
+                    {
+                        for operand in inst.operands().iter().filter(|o| o.is_word()) {
+                            addr += 1;
+                            vec.push(match operand {
+                                isa::OperandKind::Address => MemoryValue::Address,
+                                _ => MemoryValue::Data,
+                            });
                         }
-                    } else {
-                        vec.push(MemoryValue::Instruction);
                     }
                 }
 
@@ -229,8 +148,52 @@ impl MemoryView {
         }
     }
 
+    /// Define o formato dos valores da memória, aplicado na próxima atualização.
+    pub fn set_format(&self, format: NumberFormat) {
+        self.imp().format.set(format);
+    }
+
+    /// Define os símbolos do programa carregado, mostrados nos seus endereços e nos desvios.
+    pub fn set_symbols(&self, symbols: HashMap<String, isa::MemoryCell>) {
+        *self.imp().symbols.borrow_mut() = symbols;
+    }
+
+    /// Define as linhas do código-fonte de cada instrução. Com `lines` vazio, a coluna do
+    /// código-fonte é escondida.
+    pub fn set_source_lines(&self, lines: HashMap<isa::MemoryCell, String>) {
+        *self.imp().source_lines.borrow_mut() = lines;
+    }
+
+    /// Define a função chamada com o endereço de um desvio clicado em qualquer célula.
+    pub fn connect_target_activated<F: Fn(usize) + 'static>(&self, f: F) {
+        let f: Rc<dyn Fn(usize)> = Rc::new(f);
+        let mut child = self.imp().box_memory_cells.first_child();
+        while let Some(widget) = child {
+            if let Some(cell) = widget.downcast_ref::<MemoryCellRow>() {
+                cell.connect_target_activated(f.clone());
+            }
+            child = widget.next_sibling();
+        }
+    }
+
+    /// Define a função chamada ao editar qualquer célula da memória com um duplo clique.
+    pub fn connect_cell_edited<F: Fn(usize, &str) -> Result<(), String> + 'static>(&self, f: F) {
+        let f: Rc<EditHandler> = Rc::new(f);
+        let mut child = self.imp().box_memory_cells.first_child();
+        while let Some(widget) = child {
+            if let Some(cell) = widget.downcast_ref::<MemoryCellRow>() {
+                cell.connect_edited(f.clone());
+            }
+            child = widget.next_sibling();
+        }
+    }
+
     pub fn update(&self, addr: isa::MemoryCell, p: &processor::Processor) {
         let mut i = addr.clamp(0, processor::MEMORY_SIZE - 1);
+        let max_executions = p.profile().map(|profile| profile.max_executions());
+        let format = self.imp().format.get();
+        let symbols = self.imp().symbols.borrow();
+        let source_lines = self.imp().source_lines.borrow();
 
         if let Some(mut cell) = self
             .imp()
@@ -257,38 +220,66 @@ impl MemoryView {
                 {
                     MemoryValue::Instruction => {
                         let raw = p.mem(i).unwrap();
-                        let inst = isa::Instruction::get_instruction(raw);
+                        let inst = isa::Instruction::get_instruction(raw.as_usize());
 
                         if let Ok(inst) = inst {
-                            cell.update(Some(inst), i, &inst.display_row(i, &p), raw);
+                            cell.update(
+                                Some(inst),
+                                i,
+                                &inst.display_row(i, &p, &symbols),
+                                raw,
+                                format,
+                            );
                             cell.set_float_instruction("Instruction");
                         } else {
                             cell.add_css_class("error");
-                            cell.update(None, i, "Invalid Instruction", raw);
+                            cell.update(None, i, "Invalid Instruction", raw, format);
                             cell.set_float_instruction("Instruction");
                         }
                     }
                     MemoryValue::Address => {
+                        let raw = p.mem(i).unwrap();
+                        // O endereço é mostrado pelo símbolo, quando houver um.
+                        let target = match symbol_at(&symbols, raw.as_usize()) {
+                            Some(name) => name.to_string(),
+                            None => format.literal(raw),
+                        };
                         cell.update(
                             None,
                             i,
-                            &format!("#0x{:X}", p.mem(i).unwrap()),
-                            p.mem(i).unwrap(),
+                            &format!("#{}", glib::markup_escape_text(&target)),
+                            raw,
+                            format,
                         );
                         cell.set_float_instruction("Address");
                     }
                     MemoryValue::Data => {
+                        let raw = p.mem(i).unwrap();
                         cell.update(
                             None,
                             i,
-                            &format!("#0x{:X}", p.mem(i).unwrap()),
-                            p.mem(i).unwrap(),
+                            &format!("#{}", glib::markup_escape_text(&format.literal(raw))),
+                            raw,
+                            format,
                         );
                         cell.set_float_instruction("Data");
                     }
                 }
 
-                cell.set_float_address(&isa::memory::layout::data_area(i));
+                match symbol_at(&symbols, i) {
+                    Some(name) => cell.set_float_address(&format!("{}:", name)),
+                    None => cell.set_float_address(&isa::memory::layout::data_area(i)),
+                }
+                cell.set_tooltip_text(Some(&isa::memory::layout::data_area(i)));
+                cell.set_source_line(
+                    (!source_lines.is_empty())
+                        .then(|| source_lines.get(&i).map_or("", String::as_str)),
+                );
+                cell.set_heat(
+                    p.profile()
+                        .zip(max_executions)
+                        .map(|(profile, max)| (profile.executions(i), max)),
+                );
                 i = i.saturating_add(1).clamp(0, processor::MEMORY_SIZE - 1);
 
                 match cell.next_sibling().and_downcast::<MemoryCellRow>() {
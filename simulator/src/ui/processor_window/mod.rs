@@ -1,27 +1,36 @@
 pub mod entry_register;
+pub mod memory_map;
 pub mod memory_view;
 pub mod processor_screen;
+pub mod stack_view;
 
 mod imp {
 
     use std::borrow::Borrow;
     use std::borrow::BorrowMut;
     use std::cell::Cell;
+    use std::cell::OnceCell;
     use std::cell::RefCell;
+    use std::collections::HashMap;
     use std::rc::Rc;
 
     use crate::glib::property::PropertySet;
-    use adw::glib;
     use adw::prelude::*;
     use adw::subclass::prelude::*;
+    use adw::{gio, glib};
     use cairo::glib::closure_local;
     use gtk::CompositeTemplate;
 
     use crate::processor::ProcessorManager;
+    use crate::ui::number_format::Formats;
+    use processor::patch::PatchHistory;
+    use processor::runner::Frame;
 
     use super::entry_register;
+    use super::memory_map;
     use super::memory_view;
     use super::processor_screen;
+    use super::stack_view;
 
     #[derive(Default, CompositeTemplate)]
     #[template(resource = "/br/com/processador/processor-window.ui")]
@@ -65,23 +74,74 @@ mod imp {
         pub label_fr_8: TemplateChild<gtk::Label>,
         #[template_child]
         pub label_fr_9: TemplateChild<gtk::Label>,
+        #[template_child]
+        pub label_fr_10: TemplateChild<gtk::Label>,
+        #[template_child]
+        pub label_fr_11: TemplateChild<gtk::Label>,
+        #[template_child]
+        pub label_fr_12: TemplateChild<gtk::Label>,
+        #[template_child]
+        pub label_fr_13: TemplateChild<gtk::Label>,
+        #[template_child]
+        pub label_fr_14: TemplateChild<gtk::Label>,
+        #[template_child]
+        pub label_fr_15: TemplateChild<gtk::Label>,
 
         // Label do Program Counter
         #[template_child]
-        pub label_val_pc: TemplateChild<gtk::Label>,
+        pub label_val_pc: TemplateChild<gtk::EditableLabel>,
 
         // Label do Stack Pointer
         #[template_child]
-        pub label_val_sp: TemplateChild<gtk::Label>,
+        pub label_val_sp: TemplateChild<gtk::EditableLabel>,
 
         // Label do Instruction Register
         #[template_child]
         pub label_val_ir: TemplateChild<gtk::Label>,
 
+        // Velocidade efetiva e seleção do clock
+        #[template_child]
+        pub label_speed: TemplateChild<gtk::Label>,
+        #[template_child]
+        pub dropdown_clock: TemplateChild<gtk::DropDown>,
+
+        // Controles de execução
+        #[template_child]
+        pub toggle_run: TemplateChild<gtk::ToggleButton>,
+        #[template_child]
+        pub toggle_debug: TemplateChild<gtk::ToggleButton>,
+        #[template_child]
+        pub button_next: TemplateChild<gtk::Button>,
+        #[template_child]
+        pub button_step_over: TemplateChild<gtk::Button>,
+        #[template_child]
+        pub button_step_out: TemplateChild<gtk::Button>,
+
         #[template_child]
         pub memory_view: TemplateChild<memory_view::MemoryView>,
+        #[template_child]
+        pub processor_screen: TemplateChild<processor_screen::ProcessorScreen>,
+        #[template_child]
+        pub stack_view: TemplateChild<stack_view::StackView>,
+        #[template_child]
+        pub memory_map: TemplateChild<memory_map::MemoryMap>,
 
         pub processor_manager: RefCell<Rc<ProcessorManager>>,
+
+        // Símbolos do programa carregado pelo montador.
+        pub symbols: RefCell<HashMap<String, isa::MemoryCell>>,
+
+        // Alterações da memória feitas pelo memory-view, que podem ser desfeitas.
+        pub patches: RefCell<PatchHistory>,
+
+        // Formatos de exibição dos valores, escolhidos pelas ações `format.*`.
+        pub formats: Cell<Formats>,
+
+        // Canal dos estados publicados durante a execução e se ela está em andamento.
+        pub frames: OnceCell<async_channel::Sender<Frame>>,
+        pub running: Cell<bool>,
+        // Ação `protection.mode`, sincronizada ao carregar um estado.
+        pub protection_action: OnceCell<gio::SimpleAction>,
     }
 
     #[glib::object_subclass]
@@ -95,8 +155,31 @@ mod imp {
             // memory_view::MemoryView::ensure_type();
             processor_screen::ProcessorScreen::ensure_type();
             entry_register::EntryRegister::ensure_type();
+            stack_view::StackView::ensure_type();
+            memory_map::MemoryMap::ensure_type();
             klass.bind_template();
             klass.bind_template_callbacks();
+
+            klass.install_action("processor.save-state", None, |pw, _, _| pw.save_state());
+            klass.install_action("processor.load-state", None, |pw, _, _| pw.load_state());
+            klass.install_action("processor.toggle-profiling", None, |pw, _, _| {
+                pw.toggle_profiling()
+            });
+            klass.install_action("processor.export-flat-profile", None, |pw, _, _| {
+                pw.export_profile("profile.txt", processor::profiler::Profile::write_flat)
+            });
+            klass.install_action("processor.export-collapsed-profile", None, |pw, _, _| {
+                pw.export_profile(
+                    "profile.folded",
+                    processor::profiler::Profile::write_collapsed,
+                )
+            });
+            klass.install_action("processor.undo-patch", None, |pw, _, _| {
+                pw.edit_patches(processor::patch::PatchHistory::undo)
+            });
+            klass.install_action("processor.redo-patch", None, |pw, _, _| {
+                pw.edit_patches(processor::patch::PatchHistory::redo)
+            });
         }
 
         fn instance_init(obj: &glib::subclass::InitializingObject<Self>) {
@@ -106,6 +189,42 @@ mod imp {
 
     #[gtk::template_callbacks]
     impl ProcessorWindow {
+        #[template_callback]
+        fn toggle_run_toggled(&self, button: gtk::ToggleButton) {
+            if button.is_active() {
+                self.obj().run();
+            }
+        }
+
+        #[template_callback]
+        fn toggle_debug_toggled(&self, button: gtk::ToggleButton) {
+            if button.is_active() {
+                self.processor_manager.borrow().stop();
+            }
+        }
+
+        #[template_callback]
+        fn button_next_clicked(&self, _button: gtk::Button) {
+            self.obj().step();
+        }
+
+        #[template_callback]
+        fn button_step_over_clicked(&self, _button: gtk::Button) {
+            let pw = self.obj();
+            pw.start(|pm, frames| pm.step_over(frames));
+        }
+
+        #[template_callback]
+        fn button_step_out_clicked(&self, _button: gtk::Button) {
+            let pw = self.obj();
+            pw.start(|pm, frames| pm.step_out(frames));
+        }
+
+        #[template_callback]
+        fn button_restart_clicked(&self, _button: gtk::Button) {
+            self.obj().restart();
+        }
+
         #[template_callback]
         fn button_up_clicked(&self, _button: gtk::Button) {
             let index = self.memory_view.borrow().imp().addr.get().saturating_sub(1);
@@ -198,11 +317,94 @@ mod imp {
                     }),
                 );
 
-            // Retirar depois
-            if let Ok(mut p) = self.processor_manager.borrow().processor.lock() {
-                p.set_mem(4, 0b1110010000000000);
-                p.set_mem(1, 0b0111111111111111);
+            // Velocidade do clock
+            let labels: Vec<String> = super::CLOCK_PRESETS
+                .iter()
+                .map(|c| super::format_clock(*c))
+                .collect();
+            let labels: Vec<&str> = labels.iter().map(|s| s.as_str()).collect();
+            self.dropdown_clock
+                .set_model(Some(&gtk::StringList::new(&labels)));
+            let runner = self.processor_manager.borrow().runner.clone();
+            self.dropdown_clock.connect_selected_notify(move |dropdown| {
+                if let Some(clock) = super::CLOCK_PRESETS.get(dropdown.selected() as usize) {
+                    runner.set_clock(*clock);
+                }
+            });
+
+            // Edição dos registradores com o processador parado
+            for (i, entry) in pw.register_entries().into_iter().enumerate() {
+                entry.set_name(&format!("<b>R{}</b>", i));
+                entry.connect_value_activated(glib::clone!(@weak pw => move |entry| {
+                    pw.edit_register(i, entry);
+                }));
+            }
+            self.label_val_pc
+                .connect_editing_notify(glib::clone!(@weak pw => move |label| {
+                    if !label.is_editing() {
+                        pw.edit_pointer(label, processor::Processor::pc, processor::Processor::set_pc);
+                    }
+                }));
+            self.label_val_sp
+                .connect_editing_notify(glib::clone!(@weak pw => move |label| {
+                    if !label.is_editing() {
+                        pw.edit_pointer(label, processor::Processor::sp, processor::Processor::set_sp);
+                    }
+                }));
+            for (i, label) in pw.flag_labels().into_iter().enumerate() {
+                let click = gtk::GestureClick::new();
+                click.connect_released(glib::clone!(@weak pw => move |_, _, _, _| {
+                    pw.toggle_flag(i);
+                }));
+                label.add_controller(click);
+            }
+
+            // Edição da memória com o processador parado
+            self.memory_view.connect_cell_edited(
+                glib::clone!(@weak pw => @default-return Ok(()), move |addr, text| {
+                    pw.edit_memory(addr, text)
+                }),
+            );
+            pw.update_patch_actions();
+
+            // Desvios clicados no memory-view
+            self.memory_view
+                .connect_target_activated(glib::clone!(@weak pw => move |addr| {
+                    pw.show_memory(addr);
+                }));
+
+            // Endereços clicados no mapa da memória
+            self.memory_map
+                .connect_activated(glib::clone!(@weak pw => move |addr| {
+                    pw.show_memory(addr);
+                }));
 
+            // Formatos de exibição
+            pw.setup_format_actions();
+            pw.setup_protection_action();
+
+            // Estados publicados durante a execução e o seu resultado
+            let (frames, frames_recv) = async_channel::bounded(1);
+            let _ = self.frames.set(frames);
+            glib::spawn_future_local(glib::clone!(@weak pw => async move {
+                while let Ok(frame) = frames_recv.recv().await {
+                    pw.update_frame(&frame);
+                    pw.update_ui();
+                }
+            }));
+
+            let status = self
+                .processor_manager
+                .borrow()
+                .status_notification_recv
+                .clone();
+            glib::spawn_future_local(glib::clone!(@weak pw => async move {
+                while let Ok(error) = status.recv().await {
+                    pw.finished(error);
+                }
+            }));
+
+            if let Ok(p) = self.processor_manager.borrow().processor.lock() {
                 self.memory_view.setup(&p);
             }
 
@@ -217,12 +419,50 @@ mod imp {
 }
 
 use std::borrow::Borrow;
+use std::collections::HashMap;
 use std::thread;
 
 use adw::prelude::*;
 use adw::subclass::prelude::*;
 use adw::{gio, glib};
 
+use gtk::MessageType;
+use processor::errors::ProcessorError;
+use processor::protection::ProtectionMode;
+use processor::runner::{ClockSpeed, Frame};
+
+use crate::files;
+use crate::processor::ProcessorManager;
+use crate::ui::number_format::{self, Formats, NumberFormat, View};
+
+use super::simulator_window::SimulatorWindow;
+use entry_register::EntryRegister;
+
+/// Velocidades de clock disponíveis na interface.
+const CLOCK_PRESETS: [ClockSpeed; 5] = [
+    ClockSpeed::Unlimited,
+    ClockSpeed::Hz(1_000),
+    ClockSpeed::Hz(1_000_000),
+    ClockSpeed::Hz(10_000_000),
+    ClockSpeed::Hz(50_000_000),
+];
+
+/// Formata uma frequência em Hz com o prefixo adequado.
+fn format_hz(hz: f64) -> String {
+    match hz {
+        hz if hz >= 1e6 => format!("{:.1} MHz", hz / 1e6),
+        hz if hz >= 1e3 => format!("{:.1} kHz", hz / 1e3),
+        hz => format!("{:.0} Hz", hz),
+    }
+}
+
+fn format_clock(clock: ClockSpeed) -> String {
+    match clock {
+        ClockSpeed::Unlimited => "Unlimited".to_string(),
+        ClockSpeed::Hz(hz) => format_hz(hz as f64),
+    }
+}
+
 glib::wrapper! {
     pub struct ProcessorWindow(ObjectSubclass<imp::ProcessorWindow>)
     @extends gtk::Box, gtk::Widget,
@@ -234,119 +474,705 @@ impl ProcessorWindow {
         glib::Object::builder().build()
     }
 
-    // fn run_mode(&self) {
-    //     let pm = self.imp().processor_manager.borrow().clone();
-    //     thread::spawn(move || {});
-    // }
+    /// Mostra um erro do processador na janela principal do simulador.
+    fn show_processor_error(&self, error: ProcessorError) {
+        if let Some(window) = self.root().and_downcast::<SimulatorWindow>() {
+            window.show_error_dialog_processor(error);
+        }
+    }
 
-    fn debug_mode(&self) {
-        match self.imp().processor_manager.borrow().processor.lock() {
-            Ok(mut p) => {
-                if let Err(e) = p.instruction_cicle() {
-                    todo!()
-                }
-                self.update_ui();
+    /// Habilita os controles de execução passo a passo apenas com o processador parado.
+    fn set_running(&self, running: bool) {
+        let imp = self.imp();
+        imp.running.set(running);
+        imp.button_next.set_sensitive(!running);
+        imp.button_step_over.set_sensitive(!running);
+        imp.button_step_out.set_sensitive(!running);
+
+        // Os registradores só podem ser editados com o processador parado.
+        for entry in self.register_entries() {
+            entry.set_editable(!running);
+        }
+        imp.label_val_pc.set_editable(!running);
+        imp.label_val_sp.set_editable(!running);
+    }
+
+    /// Inicia uma execução em outra *thread* com `f`, caso nenhuma esteja em andamento. O fim
+    /// dela é tratado por [`ProcessorWindow::finished`].
+    fn start(&self, f: impl FnOnce(&ProcessorManager, async_channel::Sender<Frame>)) {
+        if self.imp().running.get() {
+            return;
+        }
+        self.set_running(true);
+
+        let frames = self
+            .imp()
+            .frames
+            .get()
+            .expect("Esperado o canal de estados do processador")
+            .clone();
+        f(&self.imp().processor_manager.borrow(), frames);
+    }
+
+    /// Executa o processador continuamente até `HALT`, `BREAKP`, um erro ou uma pausa.
+    fn run(&self) {
+        self.start(|pm, frames| pm.run(frames));
+    }
+
+    /// Executa uma única instrução.
+    fn step(&self) {
+        if self.imp().running.get() {
+            return;
+        }
+
+        let result = self.imp().processor_manager.borrow().step();
+        match result {
+            Ok(frame) => {
+                self.update_frame(&frame);
+                self.show_warnings();
             }
-            Err(_) => todo!(),
+            Err(e) => self.show_processor_error(e),
+        }
+        self.update_ui();
+    }
+
+    /// Interrompe a execução e reinicia o processador a partir do programa carregado.
+    fn restart(&self) {
+        let result = self.imp().processor_manager.borrow().restart();
+        match result {
+            Ok(true) => (),
+            Ok(false) => SimulatorWindow::show_error(
+                self,
+                "Nenhum programa carregado",
+                "Carregue um programa antes de reiniciar o processador.",
+            ),
+            Err(e) => self.show_processor_error(e),
         }
+        self.clear_patches();
+        self.imp().toggle_debug.set_active(true);
+        self.update_ui();
+    }
+
+    /// Trata o fim de uma execução iniciada por [`ProcessorWindow::start`].
+    fn finished(&self, error: Option<ProcessorError>) {
+        self.set_running(false);
+        self.imp().toggle_debug.set_active(true);
+        self.update_ui();
+
+        match error {
+            Some(e) => self.show_processor_error(e),
+            None => self.show_warnings(),
+        }
+    }
+
+    /// Mostra as violações de proteção detectadas desde a última chamada e as descarta.
+    fn show_warnings(&self) {
+        /// Número máximo de avisos listados; os demais são apenas contados.
+        const MAX_SHOWN: usize = 5;
+
+        let message = {
+            let manager = self.imp().processor_manager.borrow();
+            let Ok(mut p) = manager.processor.lock() else {
+                return;
+            };
+            let warnings = p.warnings();
+            if warnings.is_empty() {
+                return;
+            }
+
+            let mut lines: Vec<String> = warnings
+                .iter()
+                .take(MAX_SHOWN)
+                .map(|w| {
+                    format!(
+                        "<tt>{:#06X}</tt> (ciclo {}) — {}",
+                        w.pc,
+                        w.cycle,
+                        glib::markup_escape_text(&w.violation.to_string())
+                    )
+                })
+                .collect();
+            if warnings.len() > MAX_SHOWN {
+                lines.push(format!("e mais {} avisos.", warnings.len() - MAX_SHOWN));
+            }
+            p.clear_warnings();
+            lines.join("\n")
+        };
+
+        if let Some(window) = self.root().and_downcast::<SimulatorWindow>() {
+            window.show_info(
+                MessageType::Warning,
+                "Violações de proteção da memória",
+                &message,
+            );
+        }
+    }
+
+    /// Campos dos registradores R0-R7, na ordem dos índices.
+    fn register_entries(&self) -> [&EntryRegister; processor::NUM_REGISTERS] {
+        let imp = self.imp();
+        [
+            &imp.entry_r0,
+            &imp.entry_r1,
+            &imp.entry_r2,
+            &imp.entry_r3,
+            &imp.entry_r4,
+            &imp.entry_r5,
+            &imp.entry_r6,
+            &imp.entry_r7,
+        ]
+    }
+
+    /// Labels dos *bits* do *Flag Register*, na ordem dos índices.
+    fn flag_labels(&self) -> [&gtk::Label; isa::BITS_ADDRESS] {
+        let imp = self.imp();
+        [
+            &imp.label_fr_0,
+            &imp.label_fr_1,
+            &imp.label_fr_2,
+            &imp.label_fr_3,
+            &imp.label_fr_4,
+            &imp.label_fr_5,
+            &imp.label_fr_6,
+            &imp.label_fr_7,
+            &imp.label_fr_8,
+            &imp.label_fr_9,
+            &imp.label_fr_10,
+            &imp.label_fr_11,
+            &imp.label_fr_12,
+            &imp.label_fr_13,
+            &imp.label_fr_14,
+            &imp.label_fr_15,
+        ]
     }
 
     /// Atualiza o conteúdo dos registradores (**UI**) com base nos seus valores no processador.
     fn update_registers(&self, p: &processor::Processor) {
         let imp = self.imp();
+        let formats = imp.formats.get();
+
         // Registradores 0-7
-        imp.entry_r0.set_text(&p.reg(0).unwrap().to_string());
-        imp.entry_r1.set_text(&p.reg(1).unwrap().to_string());
-        imp.entry_r2.set_text(&p.reg(2).unwrap().to_string());
-        imp.entry_r3.set_text(&p.reg(3).unwrap().to_string());
-        imp.entry_r4.set_text(&p.reg(4).unwrap().to_string());
-        imp.entry_r5.set_text(&p.reg(5).unwrap().to_string());
-        imp.entry_r6.set_text(&p.reg(6).unwrap().to_string());
-        imp.entry_r7.set_text(&p.reg(7).unwrap().to_string());
+        let format = formats.get(View::Registers);
+        for (i, entry) in self.register_entries().into_iter().enumerate() {
+            let value = p.reg(i).unwrap();
+            entry.set_text(&format.literal(value));
+            entry.set_tooltip_text(Some(&number_format::tooltip(value)));
+            entry.set_error(None);
+        }
 
         // PC, SP e IR
-        imp.label_val_pc.set_text(&format!("{:#06X}", p.pc()));
-        imp.label_val_sp.set_text(&format!("{:#06X}", p.sp()));
-        imp.label_val_ir.set_text(&format!("{:016b}", p.ir()));
+        let format = formats.get(View::Pointers);
+        for (label, addr) in [(&imp.label_val_pc, p.pc()), (&imp.label_val_sp, p.sp())] {
+            let value = isa::Word::truncate(addr);
+            label.set_text(&format.literal(value));
+            label.remove_css_class("error");
+            label.set_tooltip_text(Some(&number_format::tooltip(value)));
+        }
+        imp.label_val_ir
+            .set_text(&formats.get(View::InstructionRegister).format(p.ir()));
+        imp.label_val_ir
+            .set_tooltip_text(Some(&number_format::tooltip(p.ir())));
 
         // FR
-        imp.label_fr_0.set_text(
-            &usize::from(
-                p.fr(isa::FlagIndex::GREATER)
-                    .expect("Esperado o índice da flag GREATER"),
-            )
-            .to_string(),
-        );
-        imp.label_fr_1.set_text(
-            &usize::from(
-                p.fr(isa::FlagIndex::LESSER)
-                    .expect("Esperado o índice da flag LESSER"),
-            )
-            .to_string(),
-        );
-        imp.label_fr_2.set_text(
-            &usize::from(
-                p.fr(isa::FlagIndex::EQUAL)
-                    .expect("Esperado o índice da flag EQUAL"),
-            )
-            .to_string(),
-        );
-        imp.label_fr_3.set_text(
-            &usize::from(
-                p.fr(isa::FlagIndex::ZERO)
-                    .expect("Esperado o índice da flag ZERO"),
-            )
-            .to_string(),
-        );
-        imp.label_fr_4.set_text(
-            &usize::from(
-                p.fr(isa::FlagIndex::CARRY)
-                    .expect("Esperado o índice da flag CARRY"),
-            )
-            .to_string(),
-        );
-        imp.label_fr_5.set_text(
-            &usize::from(
-                p.fr(isa::FlagIndex::ARITHMETIC_OVERFLOW)
-                    .expect("Esperado o índice da flag ARITHMETIC_OVERFLOW"),
-            )
-            .to_string(),
+        for (i, label) in self.flag_labels().into_iter().enumerate() {
+            label.set_text(
+                &usize::from(p.fr(i).expect("Esperado um índice de flag válido")).to_string(),
+            );
+        }
+    }
+
+    /// Aplica `edit` ao processador, que deve estar parado, e atualiza a **UI**.
+    ///
+    /// # Erros
+    ///
+    /// Retorna a descrição do erro caso o processador esteja em execução ou recuse a edição.
+    fn edit_processor(
+        &self,
+        edit: impl FnOnce(&mut processor::Processor) -> Result<(), ProcessorError>,
+    ) -> Result<(), String> {
+        if self.imp().running.get() {
+            return Err("Pause o processador antes de editá-lo.".to_string());
+        }
+
+        let result = match self.imp().processor_manager.borrow().processor.lock() {
+            Ok(mut p) => edit(&mut p).map_err(|e| e.to_string()),
+            Err(_) => Err("Falha ao acessar o processador!".to_string()),
+        };
+        if result.is_ok() {
+            self.update_ui();
+        }
+        result
+    }
+
+    /// Escreve no registrador `n` o valor digitado em `entry`.
+    fn edit_register(&self, n: usize, entry: &EntryRegister) {
+        let result = number_format::parse_word(&entry.text())
+            .map_err(|e| e.to_string())
+            .and_then(|v| self.edit_processor(|p| p.set_reg(n, v)));
+        entry.set_error(result.err().as_deref());
+    }
+
+    /// Escreve no PC ou no SP, com `set`, o valor digitado em `label`. Caso o processador recuse
+    /// o endereço, o valor anterior, lido com `get`, é mantido.
+    fn edit_pointer(
+        &self,
+        label: &gtk::EditableLabel,
+        get: fn(&processor::Processor) -> isa::MemoryCell,
+        set: fn(&mut processor::Processor, isa::MemoryCell) -> Result<(), ProcessorError>,
+    ) {
+        let result = number_format::parse_word(&label.text())
+            .map_err(|e| e.to_string())
+            .and_then(|v| {
+                self.edit_processor(|p| {
+                    let old = get(p);
+                    set(p, v.as_usize()).inspect_err(|_| {
+                        let _ = set(p, old);
+                    })
+                })
+            });
+
+        if let Err(e) = result {
+            label.add_css_class("error");
+            label.set_tooltip_text(Some(&e));
+        }
+    }
+
+    /// Inverte o *bit* `i` do *Flag Register*.
+    fn toggle_flag(&self, i: usize) {
+        if let Err(e) = self.edit_processor(|p| {
+            let v = p.fr(i)?;
+            p.set_fr(i, !v)
+        }) {
+            SimulatorWindow::show_error(self, "Falha ao alterar a flag", &e);
+        }
+    }
+
+    /// Escreve na memória, a partir de `addr`, o valor ou a instrução *assembly* em `text`. Os
+    /// rótulos da instrução são resolvidos com os símbolos do programa carregado.
+    ///
+    /// # Erros
+    ///
+    /// Retorna a descrição do erro de montagem ou do processador.
+    fn edit_memory(&self, addr: isa::MemoryCell, text: &str) -> Result<(), String> {
+        let imp = self.imp();
+        let words: Vec<isa::Word> = match number_format::parse_word(text) {
+            Ok(word) => vec![word],
+            Err(_) => assembler::patch::assemble_patch(text, addr, &imp.symbols.borrow())
+                .map_err(|e| e.to_string())?
+                .into_iter()
+                .map(isa::Word::truncate)
+                .collect(),
+        };
+
+        let top = imp.memory_view.imp().addr.get();
+        self.edit_processor(|p| {
+            imp.patches.borrow_mut().apply(p, addr, &words)?;
+            imp.memory_view.update_memory_info(p);
+            Ok(())
+        })?;
+        self.update_patch_actions();
+        self.show_memory(top);
+        Ok(())
+    }
+
+    /// Desfaz ou refaz, com `f`, uma alteração da memória e mostra o endereço alterado.
+    fn edit_patches(
+        &self,
+        f: fn(
+            &mut processor::patch::PatchHistory,
+            &mut processor::Processor,
+        ) -> Result<Option<isa::MemoryCell>, ProcessorError>,
+    ) {
+        let imp = self.imp();
+        let mut addr = None;
+        let result = self.edit_processor(|p| {
+            addr = f(&mut imp.patches.borrow_mut(), p)?;
+            imp.memory_view.update_memory_info(p);
+            Ok(())
+        });
+
+        match result {
+            Ok(()) => {
+                if let Some(addr) = addr {
+                    self.show_memory(addr);
+                }
+            }
+            Err(e) => SimulatorWindow::show_error(self, "Falha ao alterar a memória", &e),
+        }
+        self.update_patch_actions();
+    }
+
+    /// Descarta as alterações da memória, que não se aplicam mais ao programa carregado.
+    fn clear_patches(&self) {
+        self.imp().patches.borrow_mut().clear();
+        self.update_patch_actions();
+    }
+
+    /// Habilita as ações de desfazer e refazer conforme o histórico de alterações.
+    fn update_patch_actions(&self) {
+        let patches = self.imp().patches.borrow();
+        self.action_set_enabled("processor.undo-patch", patches.can_undo());
+        self.action_set_enabled("processor.redo-patch", patches.can_redo());
+    }
+
+    /// Mostra a memória a partir de `addr` no memory-view.
+    fn show_memory(&self, addr: isa::MemoryCell) {
+        if let Ok(p) = self.imp().processor_manager.borrow().processor.lock() {
+            self.imp().memory_view.update(addr, &p);
+        }
+    }
+
+    /// Cria as ações `format.global` e `format.<parte>`, com os formatos salvos como estado
+    /// inicial. As partes aceitam também [`number_format::GLOBAL_ID`] para seguir o global.
+    fn setup_format_actions(&self) {
+        let formats = files::formats::load();
+        self.imp().formats.set(formats);
+        let actions = gio::SimpleActionGroup::new();
+
+        let global = gio::SimpleAction::new_stateful(
+            "global",
+            Some(glib::VariantTy::STRING),
+            &formats.global.id().to_variant(),
         );
-        imp.label_fr_6.set_text(
-            &usize::from(
-                p.fr(isa::FlagIndex::DIV_BY_ZERO)
-                    .expect("Esperado o índice da flag DIV_BY_ZERO"),
-            )
-            .to_string(),
+        global.connect_activate(glib::clone!(@weak self as pw => move |action, value| {
+            let Some(format) = value.and_then(|v| v.str()).and_then(NumberFormat::from_id) else {
+                return;
+            };
+            action.set_state(&format.id().to_variant());
+            pw.update_formats(|f| f.global = format);
+        }));
+        actions.add_action(&global);
+
+        for view in View::ALL {
+            let state = formats
+                .view(view)
+                .map_or(number_format::GLOBAL_ID, NumberFormat::id);
+            let action = gio::SimpleAction::new_stateful(
+                view.id(),
+                Some(glib::VariantTy::STRING),
+                &state.to_variant(),
+            );
+            action.connect_activate(glib::clone!(@weak self as pw => move |action, value| {
+                let Some(id) = value.and_then(|v| v.str()) else {
+                    return;
+                };
+                action.set_state(&id.to_variant());
+                pw.update_formats(|f| f.set_view(view, NumberFormat::from_id(id)));
+            }));
+            actions.add_action(&action);
+        }
+
+        self.insert_action_group("format", Some(&actions));
+    }
+
+    /// Cria a ação `protection.mode`, que altera o [`ProtectionMode`] do processador.
+    fn setup_protection_action(&self) {
+        let actions = gio::SimpleActionGroup::new();
+        let mode = gio::SimpleAction::new_stateful(
+            "mode",
+            Some(glib::VariantTy::STRING),
+            &ProtectionMode::default().name().to_variant(),
         );
-        imp.label_fr_7.set_text(
-            &usize::from(
-                p.fr(isa::FlagIndex::STACK_OVERFLOW)
-                    .expect("Esperado o índice da flag STACK_OVERFLOW"),
-            )
-            .to_string(),
+        mode.connect_activate(glib::clone!(@weak self as pw => move |action, value| {
+            let Some(mode) = value
+                .and_then(|v| v.str())
+                .and_then(|id| id.parse::<ProtectionMode>().ok())
+            else {
+                return;
+            };
+            action.set_state(&mode.name().to_variant());
+            match pw.imp().processor_manager.borrow().processor.lock() {
+                Ok(mut p) => p.set_protection(mode),
+                Err(_) => SimulatorWindow::show_error(&pw, "Poison Error", "Falha ao acessar o processador!"),
+            }
+        }));
+        actions.add_action(&mode);
+        let _ = self.imp().protection_action.set(mode);
+
+        self.insert_action_group("protection", Some(&actions));
+    }
+
+    /// Altera os formatos de exibição com `f`, salva-os e atualiza a **UI**.
+    fn update_formats(&self, f: impl FnOnce(&mut Formats)) {
+        let mut formats = self.imp().formats.get();
+        f(&mut formats);
+        self.imp().formats.set(formats);
+        files::formats::save(&formats);
+
+        let top = self.imp().memory_view.imp().addr.get();
+        self.update_ui();
+        self.show_memory(top);
+    }
+
+    /// Atualiza o estado da ação `protection.mode` com o `mode` do processador.
+    fn sync_protection_action(&self, mode: ProtectionMode) {
+        if let Some(action) = self.imp().protection_action.get() {
+            action.set_state(&mode.name().to_variant());
+        }
+    }
+
+    /// Pede um arquivo ao usuário e salva nele o estado completo do processador.
+    fn save_state(&self) {
+        let dialog = gtk::FileDialog::builder()
+            .title("Save state")
+            .initial_name("state.snapshot")
+            .modal(true)
+            .build();
+
+        let pw = self.clone();
+        dialog.save(
+            self.root().and_downcast_ref::<gtk::Window>(),
+            gio::Cancellable::NONE,
+            move |file| {
+                let Some(path) = file.ok().and_then(|f| f.path()) else {
+                    return;
+                };
+
+                let result = match pw.imp().processor_manager.borrow().processor.lock() {
+                    Ok(p) => std::fs::File::create(&path)
+                        .map_err(|e| e.into())
+                        .and_then(|f| p.write_snapshot(&mut std::io::BufWriter::new(f))),
+                    Err(_) => {
+                        return SimulatorWindow::show_error(
+                            &pw,
+                            "Poison Error",
+                            "Falha ao acessar o processador!",
+                        )
+                    }
+                };
+
+                if let Err(e) = result {
+                    SimulatorWindow::show_error(
+                        &pw,
+                        "Falha ao salvar o estado",
+                        &glib::markup_escape_text(&e.to_string()),
+                    );
+                }
+            },
         );
-        imp.label_fr_8.set_text(
-            &usize::from(
-                p.fr(isa::FlagIndex::STACK_UNDERFLOW)
-                    .expect("Esperado o índice da flag STACK_UNDERFLOW"),
-            )
-            .to_string(),
+    }
+
+    /// Pede um arquivo ao usuário e restaura o estado do processador a partir dele. O estado
+    /// atual só é substituído se o arquivo for lido sem erros.
+    fn load_state(&self) {
+        let dialog = gtk::FileDialog::builder()
+            .title("Load state")
+            .modal(true)
+            .build();
+
+        let pw = self.clone();
+        dialog.open(
+            self.root().and_downcast_ref::<gtk::Window>(),
+            gio::Cancellable::NONE,
+            move |file| {
+                let Some(path) = file.ok().and_then(|f| f.path()) else {
+                    return;
+                };
+
+                let restored = std::fs::File::open(&path)
+                    .map_err(|e| e.into())
+                    .and_then(|f| processor::Processor::read_snapshot(std::io::BufReader::new(f)));
+
+                match restored {
+                    Ok(restored) => {
+                        pw.imp().processor_manager.borrow().stop();
+                        match pw.imp().processor_manager.borrow().processor.lock() {
+                            Ok(mut p) => {
+                                *p = restored;
+                                pw.imp().memory_view.update_memory_info(&p);
+                                pw.sync_protection_action(p.protection());
+                            }
+                            Err(_) => {
+                                return SimulatorWindow::show_error(
+                                    &pw,
+                                    "Poison Error",
+                                    "Falha ao acessar o processador!",
+                                )
+                            }
+                        }
+                        pw.clear_patches();
+                        pw.update_ui();
+                    }
+                    Err(e) => SimulatorWindow::show_error(
+                        &pw,
+                        "Falha ao carregar o estado",
+                        &glib::markup_escape_text(&e.to_string()),
+                    ),
+                }
+            },
         );
-        imp.label_fr_9.set_text(
-            &usize::from(
-                p.fr(isa::FlagIndex::NEGATIVE)
-                    .expect("Esperado o índice da flag NEGATIVE"),
-            )
-            .to_string(),
+    }
+
+    /// Ativa ou desativa o perfil de execução. Com o perfil ativo, o memory-view mostra o número
+    /// de execuções de cada endereço.
+    fn toggle_profiling(&self) {
+        match self.imp().processor_manager.borrow().processor.lock() {
+            Ok(mut p) => match p.profile() {
+                Some(_) => {
+                    p.stop_profiling();
+                }
+                None => p.start_profiling(),
+            },
+            Err(_) => {
+                return SimulatorWindow::show_error(
+                    self,
+                    "Poison Error",
+                    "Falha ao acessar o processador!",
+                )
+            }
+        }
+        self.update_ui();
+    }
+
+    /// Pede um arquivo ao usuário e escreve nele o perfil de execução com `write`.
+    fn export_profile(
+        &self,
+        initial_name: &str,
+        write: fn(
+            &processor::profiler::Profile,
+            &mut std::io::BufWriter<std::fs::File>,
+            Option<&std::collections::HashMap<String, isa::MemoryCell>>,
+        ) -> std::io::Result<()>,
+    ) {
+        let dialog = gtk::FileDialog::builder()
+            .title("Export profile")
+            .initial_name(initial_name)
+            .modal(true)
+            .build();
+
+        let pw = self.clone();
+        dialog.save(
+            self.root().and_downcast_ref::<gtk::Window>(),
+            gio::Cancellable::NONE,
+            move |file| {
+                let Some(path) = file.ok().and_then(|f| f.path()) else {
+                    return;
+                };
+
+                let result = match pw.imp().processor_manager.borrow().processor.lock() {
+                    Ok(p) => match p.profile() {
+                        Some(profile) => std::fs::File::create(&path).and_then(|f| {
+                            write(
+                                profile,
+                                &mut std::io::BufWriter::new(f),
+                                Some(&pw.imp().symbols.borrow()),
+                            )
+                        }),
+                        None => {
+                            return SimulatorWindow::show_error(
+                                &pw,
+                                "Perfil desativado",
+                                "Ative o perfil de execução antes de exportá-lo.",
+                            )
+                        }
+                    },
+                    Err(_) => {
+                        return SimulatorWindow::show_error(
+                            &pw,
+                            "Poison Error",
+                            "Falha ao acessar o processador!",
+                        )
+                    }
+                };
+
+                if let Err(e) = result {
+                    SimulatorWindow::show_error(
+                        &pw,
+                        "Falha ao exportar o perfil",
+                        &glib::markup_escape_text(&e.to_string()),
+                    );
+                }
+            },
         );
     }
 
-    /// Atualiza a **UI** (registradores, memory-view).
+    /// Interrompe a execução e reinicia o processador com a memória da imagem ligada pelo
+    /// montador. As linhas de cada instrução são lidas de `sources`, o código de cada módulo.
+    pub fn load_image(&self, image: &assembler::linker::Image, sources: &HashMap<String, String>) {
+        let memory: Vec<isa::Word> = image
+            .memory
+            .iter()
+            .map(|&cell| isa::Word::truncate(cell))
+            .collect();
+
+        let lines = image
+            .lines
+            .iter()
+            .filter_map(|(&addr, location)| {
+                let text = sources
+                    .get(&location.module)?
+                    .lines()
+                    .nth(location.line.checked_sub(1)?)?;
+                Some((addr, format!("{:>4}  {}", location.line, text.trim())))
+            })
+            .collect();
+
+        self.load_program(&memory, image.symbols.clone(), lines);
+    }
+
+    /// Reinicia o processador com a `memory` de um programa e os seus `symbols`.
+    pub fn load_memory(&self, memory: &[isa::Word], symbols: HashMap<String, isa::MemoryCell>) {
+        self.load_program(memory, symbols, HashMap::new());
+    }
+
+    /// Reinicia o processador com a `memory` de um programa, os seus `symbols` e a linha do
+    /// código-fonte de cada instrução.
+    fn load_program(
+        &self,
+        memory: &[isa::Word],
+        symbols: HashMap<String, isa::MemoryCell>,
+        lines: HashMap<isa::MemoryCell, String>,
+    ) {
+        self.imp().processor_manager.borrow().stop();
+        match self.imp().processor_manager.borrow().processor.lock() {
+            Ok(mut p) => {
+                if let Err(e) = p.reset(memory) {
+                    return SimulatorWindow::show_error(
+                        self,
+                        "Falha ao carregar o programa",
+                        &glib::markup_escape_text(&e.to_string()),
+                    );
+                }
+                self.imp().memory_view.update_memory_info(&p);
+            }
+            Err(_) => {
+                return SimulatorWindow::show_error(
+                    self,
+                    "Poison Error",
+                    "Falha ao acessar o processador!",
+                )
+            }
+        }
+        self.imp().memory_view.set_symbols(symbols.clone());
+        self.imp().memory_view.set_source_lines(lines);
+        *self.imp().symbols.borrow_mut() = symbols;
+        self.clear_patches();
+
+        self.update_ui();
+    }
+
+    /// Atualiza a velocidade efetiva com base em um estado publicado durante a execução.
+    pub fn update_frame(&self, frame: &Frame) {
+        self.imp().label_speed.set_text(&format_hz(frame.speed));
+        self.imp()
+            .label_speed
+            .set_tooltip_text(Some(&format!("{} cycles", frame.cycles)));
+    }
+
+    /// Atualiza a **UI** (registradores, memory-view, pilha e mapa da memória).
     pub fn update_ui(&self) {
+        self.imp()
+            .memory_view
+            .set_format(self.imp().formats.get().get(View::Memory));
         if let Ok(p) = self.imp().processor_manager.borrow().processor.lock() {
             self.update_registers(&p);
             self.imp().memory_view.update(p.pc(), &p);
+            self.imp().stack_view.update(
+                &p,
+                &self.imp().symbols.borrow(),
+                self.imp().formats.get().get(View::Memory),
+            );
+            self.imp().memory_map.update(&p);
         }
     }
 }
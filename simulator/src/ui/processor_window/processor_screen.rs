@@ -79,6 +79,8 @@ mod imp {
     impl DrawingAreaImpl for ProcessorScreen {}
 }
 
+use std::{cell::RefCell, rc::Rc};
+
 use adw::glib;
 use cairo::glib::subclass::types::ObjectSubclassIsExt;
 use glib::Object;
@@ -86,6 +88,7 @@ use gtk::prelude::WidgetExt;
 use log::{debug, error};
 use processor::modules::video::Pixelmap;
 
+use crate::files::charmap::Charmap;
 use crate::processor::RunMode;
 
 glib::wrapper! {
@@ -99,6 +102,11 @@ impl ProcessorScreen {
         Object::builder().build()
     }
 
+    /// Retorna o charmap usado para desenhar a tela, compartilhado com quem o edita.
+    pub fn charmap(&self) -> Rc<RefCell<Charmap>> {
+        self.imp().charmap.clone()
+    }
+
     pub fn set_pixelmap(&self, pixelmap: processor::modules::video::Pixelmap, index: usize) {
         *self.imp().pixbuf.borrow_mut().get_mut(index).unwrap() = pixelmap;
     }
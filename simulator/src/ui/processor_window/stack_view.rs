@@ -0,0 +1,133 @@
+mod imp {
+
+    use adw::glib;
+    use adw::prelude::*;
+    use adw::subclass::prelude::*;
+    use gtk::CompositeTemplate;
+
+    #[derive(Default, CompositeTemplate)]
+    #[template(resource = "/br/com/processador/stack-view.ui")]
+    pub struct StackView {
+        #[template_child]
+        pub label_stack_status: TemplateChild<gtk::Label>,
+
+        #[template_child]
+        pub list_stack: TemplateChild<gtk::ListBox>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for StackView {
+        // `NAME` needs to match `class` attribute of template
+        const NAME: &'static str = "StackView";
+        type Type = super::StackView;
+        type ParentType = gtk::Box;
+
+        fn class_init(klass: &mut Self::Class) {
+            klass.bind_template();
+        }
+
+        fn instance_init(obj: &glib::subclass::InitializingObject<Self>) {
+            obj.init_template();
+        }
+    }
+
+    // Trait shared by all GObjects
+    impl ObjectImpl for StackView {}
+
+    // Trait shared by all widgets
+    impl WidgetImpl for StackView {}
+
+    impl BoxImpl for StackView {}
+}
+
+use std::collections::HashMap;
+
+use adw::glib;
+use adw::prelude::*;
+use adw::subclass::prelude::*;
+
+use isa::MemoryCell;
+use processor::stack::{SlotKind, StackSlot};
+
+use crate::processor::instructions::symbol_at;
+use crate::ui::number_format::{self, NumberFormat};
+
+/// Número máximo de palavras mostradas, a partir do topo da pilha.
+const MAX_SLOTS: usize = 128;
+
+glib::wrapper! {
+    pub struct StackView(ObjectSubclass<imp::StackView>)
+    @extends gtk::Box, gtk::Widget,
+    @implements gtk::Accessible, gtk::Buildable, gtk::ConstraintTarget, gtk::Orientable;
+}
+
+/// Descrição, com *markup* do GTK, da origem de uma palavra da pilha.
+fn describe(slot: &StackSlot, symbols: &HashMap<String, MemoryCell>) -> String {
+    match slot.kind {
+        SlotKind::ReturnAddress(frame) => {
+            let target = symbol_at(symbols, frame.target)
+                .map(str::to_string)
+                .unwrap_or_else(|| format!("{:#06X}", frame.target));
+            format!("Return from <b>{}</b>", target)
+        }
+        SlotKind::Register(r) => format!("Pushed <b>R{}</b>", r),
+        SlotKind::Flags => "Pushed <b>FR</b>".to_string(),
+        SlotKind::Unknown => "Data".to_string(),
+    }
+}
+
+impl StackView {
+    pub fn build() -> Self {
+        glib::Object::builder().build()
+    }
+
+    /// Mostra a pilha de `p`, do topo à base, com os valores no `format` e os endereços de
+    /// retorno identificados pelos `symbols`. Palavras nas áreas de proteção ao redor da pilha
+    /// são destacadas.
+    pub fn update(
+        &self,
+        p: &processor::Processor,
+        symbols: &HashMap<String, MemoryCell>,
+        format: NumberFormat,
+    ) {
+        let imp = self.imp();
+        imp.list_stack.remove_all();
+
+        let stack = p.stack();
+        let status = match stack.len() {
+            0 => "Empty stack".to_string(),
+            n if n > MAX_SLOTS => format!("{} words, showing the top {}", n, MAX_SLOTS),
+            1 => "1 word".to_string(),
+            n => format!("{} words", n),
+        };
+        imp.label_stack_status.set_text(&status);
+
+        for slot in stack.iter().take(MAX_SLOTS) {
+            let value = gtk::Label::builder()
+                .label(format.format(slot.value))
+                .tooltip_text(number_format::tooltip(slot.value))
+                .css_classes(["monospace"])
+                .build();
+
+            let row = adw::ActionRow::builder()
+                .title(describe(slot, symbols))
+                .subtitle(format!("{:#06X}", slot.addr))
+                .build();
+            row.add_suffix(&value);
+
+            if slot.in_gap() {
+                let error = match slot.addr < *isa::memory::layout::ADDR_STACK.start() {
+                    true => "Stack overflow",
+                    false => "Stack underflow",
+                };
+                row.add_css_class("error");
+                row.set_tooltip_text(Some(&format!(
+                    "{} into {}",
+                    error,
+                    isa::memory::layout::data_area(slot.addr)
+                )));
+            }
+            imp.list_stack.append(&row);
+        }
+    }
+}
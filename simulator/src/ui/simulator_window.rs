@@ -4,7 +4,11 @@ mod imp {
     use adw::prelude::*;
     use adw::subclass::prelude::*;
     use gtk::CompositeTemplate;
-    use processor::errors::ProcessorError;
+
+    use super::super::asm_editor::AsmEditor;
+    use super::super::charmap_editor::CharmapEditor;
+    use super::super::home_window::HomeWindow;
+    use super::super::processor_window::ProcessorWindow;
 
     #[derive(Default, CompositeTemplate)]
     #[template(resource = "/br/com/processador/simulator-window.ui")]
@@ -16,6 +20,8 @@ mod imp {
         #[template_child]
         pub action_row_info: TemplateChild<adw::ActionRow>,
 
+        #[template_child]
+        pub stack_toolbar: TemplateChild<adw::ViewStack>,
         #[template_child]
         pub stack_home_page: TemplateChild<adw::ViewStackPage>,
         #[template_child]
@@ -24,6 +30,15 @@ mod imp {
         pub stack_asm_page: TemplateChild<adw::ViewStackPage>,
         #[template_child]
         pub stack_charmap_page: TemplateChild<adw::ViewStackPage>,
+
+        #[template_child]
+        pub home_window: TemplateChild<HomeWindow>,
+        #[template_child]
+        pub processor_window: TemplateChild<ProcessorWindow>,
+        #[template_child]
+        pub asm_editor: TemplateChild<AsmEditor>,
+        #[template_child]
+        pub charmap_editor: TemplateChild<CharmapEditor>,
     }
 
     #[glib::object_subclass]
@@ -34,7 +49,10 @@ mod imp {
         type ParentType = gtk::ApplicationWindow;
 
         fn class_init(klass: &mut Self::Class) {
-            super::super::processor_window::ProcessorWindow::ensure_type();
+            HomeWindow::ensure_type();
+            ProcessorWindow::ensure_type();
+            AsmEditor::ensure_type();
+            CharmapEditor::ensure_type();
             klass.bind_template();
             klass.bind_template_callbacks();
         }
@@ -56,8 +74,8 @@ mod imp {
     impl ObjectImpl for SimulatorWindow {
         fn constructed(&self) {
             self.parent_constructed();
-            self.obj()
-                .show_error_dialog_processor(ProcessorError::StackOverflow(40000));
+            self.charmap_editor
+                .attach(&self.processor_window.imp().processor_screen);
         }
     }
 
@@ -69,12 +87,19 @@ mod imp {
     impl ApplicationWindowImpl for SimulatorWindow {}
 }
 
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+
 use adw::prelude::*;
 use adw::subclass::prelude::*;
 use adw::{gio, glib};
 use gtk::MessageType;
 use processor::errors::ProcessorError;
 
+use crate::files::{self, charmap::Charmap, recent};
+
 glib::wrapper! {
     pub struct SimulatorWindow(ObjectSubclass<imp::SimulatorWindow>)
     @extends gtk::Window, gtk::Widget,
@@ -94,6 +119,83 @@ impl SimulatorWindow {
         glib::Object::builder().property("application", app).build()
     }
 
+    /// Mostra `page` na janela.
+    pub fn show_page(&self, page: Page) {
+        let imp = self.imp();
+        let page = match page {
+            Page::Home => &imp.stack_home_page,
+            Page::Processor => &imp.stack_processor_page,
+            Page::AsmEditor => &imp.stack_asm_page,
+            Page::CharmapEditor => &imp.stack_charmap_page,
+        };
+        imp.stack_toolbar.set_visible_child(&page.child());
+    }
+
+    /// Carrega a imagem ligada pelo montador no processador e mostra a página do processador.
+    /// `sources` guarda o código de cada módulo, mostrado ao lado das instruções.
+    pub fn load_program(
+        &self,
+        image: &assembler::linker::Image,
+        sources: &HashMap<String, String>,
+    ) {
+        self.imp().processor_window.load_image(image, sources);
+        self.show_page(Page::Processor);
+    }
+
+    /// Monta o código `source` no editor e carrega o programa no processador. Caso haja erros
+    /// de montagem, mostra o editor com o erro marcado.
+    pub fn open_source(&self, source: &str) {
+        let editor = &self.imp().asm_editor;
+        editor.set_source(source);
+        if !editor.assemble_and_load() {
+            self.show_page(Page::AsmEditor);
+        }
+    }
+
+    /// Abre o arquivo `path`: um código *assembly* (`.asm`), um programa montado ou um
+    /// charmap (`.mif`). Programas são carregados no processador e charmaps na tela dele.
+    pub fn open_file(&self, path: &Path) {
+        let name = path.to_string_lossy();
+        let extension = path
+            .extension()
+            .map(|e| e.to_string_lossy().to_ascii_lowercase());
+
+        let result = match extension.as_deref() {
+            Some("asm") => std::fs::read_to_string(path).map(|source| self.open_source(&source)),
+            Some("mif") if files::is_charmap(&name) => File::open(path)
+                .and_then(|f| Charmap::read_mif(BufReader::new(f)))
+                .map(|charmap| {
+                    self.imp().charmap_editor.set_charmap(charmap);
+                    self.show_page(Page::Processor);
+                }),
+            Some("mif") => files::program(&name).map(|memory| {
+                self.imp()
+                    .processor_window
+                    .load_memory(&memory, Default::default());
+                self.show_page(Page::Processor);
+            }),
+            _ => {
+                return self.show_info(
+                    MessageType::Error,
+                    "Arquivo não suportado",
+                    "Abra um código <b>.asm</b> ou um arquivo <b>.mif</b>.",
+                )
+            }
+        };
+
+        match result {
+            Ok(()) => {
+                recent::add(path);
+                self.imp().home_window.update_recent();
+            }
+            Err(e) => self.show_info(
+                MessageType::Error,
+                "Falha ao abrir o arquivo",
+                &glib::markup_escape_text(&e.to_string()),
+            ),
+        }
+    }
+
     pub fn needs_attention(&self, page: Page, needs_attention: bool) {
         match page {
             Page::Home => self
@@ -126,65 +228,109 @@ impl SimulatorWindow {
             .set_subtitle(&format!("{}", subtitle));
     }
 
+    /// Mostra um erro na janela principal do simulador que contém `widget`. O `subtitle` é
+    /// interpretado como *markup*; textos externos devem passar por
+    /// [`glib::markup_escape_text`].
+    pub fn show_error(widget: &impl IsA<gtk::Widget>, title: &str, subtitle: &str) {
+        if let Some(window) = widget.root().and_downcast::<SimulatorWindow>() {
+            window.show_info(MessageType::Error, title, subtitle);
+        }
+    }
+
     pub fn close_info(&self) {
         self.imp().revealer_info_top.set_reveal_child(false);
     }
 
+    /// Mostra um erro do processador, precedido pelo endereço e pela instrução que falhou e
+    /// seguido da pilha de chamadas com os símbolos do programa carregado.
     pub fn show_error_dialog_processor(&self, error: ProcessorError) {
         self.needs_attention(Page::Processor, true);
-        match error {
-            ProcessorError::SegmentationFault { .. } => self.show_info(
-                MessageType::Error,
-                "Segmentation fault",
-                "O registrador <b>PC</b> está apontando para um endereço de uma região da memória não permitida.",
-            ),
+        let (title, description) = error_info(error.root());
+        match error.context() {
+            Some(context) => {
+                let mut message = format!(
+                    "<tt>{}</tt> — {}",
+                    glib::markup_escape_text(&context.to_string()),
+                    description
+                );
+                if !context.backtrace.frames.is_empty() {
+                    let symbols = self.imp().processor_window.imp().symbols.borrow();
+                    message.push_str(&format!(
+                        "\n\n<tt>{}</tt>",
+                        glib::markup_escape_text(&context.backtrace.render(Some(&symbols)))
+                    ));
+                }
+                self.show_info(MessageType::Error, &title, &message)
+            }
+            None => self.show_info(MessageType::Error, &title, &description),
+        }
+    }
+}
 
-            ProcessorError::StackOverflow(_) => self.show_info(
-                MessageType::Error,
-                "Stack Overflow",
-                "O registrador <b>SP</b> está apontando para um endereço menor que o topo da pilha."
-            ),
+/// Retorna o título e a descrição, com *markup* do GTK, de um erro do processador.
+pub fn error_info(error: &ProcessorError) -> (String, String) {
+    match error {
+        ProcessorError::SegmentationFault { .. } => (
+            "Segmentation fault".to_string(),
+            "O registrador <b>PC</b> está apontando para um endereço de uma região da memória não permitida.".to_string(),
+        ),
 
-            ProcessorError::StackUnderflow(_) => self.show_info(
-                MessageType::Error,
-                "Stack Underflow",
-                "O registrador <b>SP</b> está apontando para um endereço maior que a base da pilha."
-            ),
+        ProcessorError::StackOverflow(_) => (
+            "Stack Overflow".to_string(),
+            "O registrador <b>SP</b> está apontando para um endereço menor que o topo da pilha.".to_string(),
+        ),
 
-            ProcessorError::InvalidAddress(addr) => self.show_info(
-                MessageType::Error,
-                "Endereço inválido",
-                &format!("O endereço {:06x} não é um endereço válido.", addr)
-            ),
+        ProcessorError::StackUnderflow(_) => (
+            "Stack Underflow".to_string(),
+            "O registrador <b>SP</b> está apontando para um endereço maior que a base da pilha.".to_string(),
+        ),
 
-            ProcessorError::InvalidInstruction(inst) => self.show_info(
-                MessageType::Error,
-                "Instrução inválida",
-                &format!(
-                    "O valor {:016b} não corresponde a nenhuma instrução válida. \
-                    Verifique se o arquivo <b>.mif</b> está correto ou se o \
-                    conjunto de instruções utilizado é compatível com a versão do simulador.",
-                    inst
-                ),
-            ),
+        ProcessorError::InvalidAddress(addr) => (
+            "Endereço inválido".to_string(),
+            format!("O endereço {:06x} não é um endereço válido.", addr),
+        ),
 
-            ProcessorError::InvalidRegister(reg) => self.show_info(
-                MessageType::Error,
-                "Registrador inválido",
-                &format!("O registrador {} não existe.", reg)
+        ProcessorError::InvalidInstruction(inst) => (
+            "Instrução inválida".to_string(),
+            format!(
+                "O valor {:016b} não corresponde a nenhuma instrução válida. \
+                Verifique se o arquivo <b>.mif</b> está correto ou se o \
+                conjunto de instruções utilizado é compatível com a versão do simulador.",
+                inst
             ),
+        ),
 
-            ProcessorError::InvalidFlag(bit) => self.show_info(
-                MessageType::Error,
-                "<i>Flag</i> inválida",
-                &format!("O <i>bit</i> {} não representa nenhuma <i>flag</i> do <i>Flag Register</i>.", bit)
-            ),
+        ProcessorError::UnimplementedInstruction(instruction) => (
+            "Instrução não suportada".to_string(),
+            format!("A instrução <b>{}</b> ainda não é suportada pelo simulador.", instruction),
+        ),
 
-            ProcessorError::Generic { title, description } => self.show_info(
-                MessageType::Error,
-                &title,
-                &description
-            ),
+        ProcessorError::InvalidRegister(reg) => (
+            "Registrador inválido".to_string(),
+            format!("O registrador {} não existe.", reg),
+        ),
+
+        ProcessorError::InvalidFlag(bit) => (
+            "<i>Flag</i> inválida".to_string(),
+            format!("O <i>bit</i> {} não representa nenhuma <i>flag</i> do <i>Flag Register</i>.", bit),
+        ),
+
+        ProcessorError::InvalidScreenPosition(index) => (
+            "Posição inválida na tela".to_string(),
+            format!("A posição {} está fora da tela.", index),
+        ),
+
+        ProcessorError::InvalidColor(code) => (
+            "Cor inválida".to_string(),
+            format!("O código {} não representa nenhuma cor mapeada.", code),
+        ),
+
+        ProcessorError::Protection(violation) => {
+            ("Violação de memória".to_string(), violation.to_string())
         }
+
+        ProcessorError::Generic { title, description } => (title.clone(), description.clone()),
+
+        ProcessorError::Context { error, .. } => error_info(error),
     }
 }
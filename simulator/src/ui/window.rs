@@ -303,6 +303,7 @@ mod imp {
 use std::error::Error;
 use std::ops::RangeBounds;
 
+use super::simulator_window::error_info;
 use crate::mem_row::MemoryCellRow;
 use crate::processor::RunMode;
 use adw::prelude::*;
@@ -671,68 +672,12 @@ impl Window {
     }
 
     pub fn show_error_dialog_processor(&self, error: ProcessorError) {
-        match error {
-            ProcessorError::SegmentationFault { .. } => self.show_info(
-                MessageType::Error,
-                "Segmentation fault",
-                "O registrador <b>PC</b> está apontando para um endereço de uma região da memória não permitida.",
-            ),
-
-            ProcessorError::StackOverflow(_) => self.show_info(
-                MessageType::Error,
-                "Stack Overflow",
-                "O registrador <b>SP</b> está apontando para um endereço menor que o topo da pilha."
-            ),
-
-            ProcessorError::StackUnderflow(_) => self.show_info(
-                MessageType::Error,
-                "Stack Underflow",
-                "O registrador <b>SP</b> está apontando para um endereço maior que a base da pilha."
-            ),
-
-            ProcessorError::InvalidAddress(addr) => self.show_info(
-                MessageType::Error,
-                "Endereço inválido",
-                &format!("O endereço {:06x} não é um endereço válido.", addr)
-            ),
-
-            ProcessorError::InvalidInstruction(inst) => self.show_info(
-                MessageType::Error,
-                "Instrução inválida",
-                &format!(
-                    "O valor {:016b} não corresponde a nenhuma instrução válida. \
-                    Verifique se o arquivo <b>.mif</b> está correto ou se o \
-                    conjunto de instruções utilizado é compatível com a versão do simulador.",
-                    inst
-                ),
-            ),
-
-            ProcessorError::InvalidRegister(reg) => self.show_info(
-                MessageType::Error,
-                "Registrador inválido",
-                &format!("O registrador {} não existe.", reg)
-            ),
-
-            ProcessorError::InvalidFlag(bit) => self.show_info(
-                MessageType::Error,
-                "<i>Flag</i> inválida",
-                &format!("O <i>bit</i> {} não representa nenhuma <i>flag</i> do <i>Flag Register</i>.", bit)
-            ),
-
-            ProcessorError::Generic { title, description } => self.show_info(
-                MessageType::Error,
-                &title,
-                &description
-            ),
-        }
+        let (title, description) = error_info(error.root());
+        self.show_info(MessageType::Error, &title, &description);
     }
 }
 
-pub enum NumberFormat {
-    Binary,
-    Decimal,
-    Hexadecimal,
-}
+pub use super::number_format::NumberFormat;
 
 pub struct WindowData {
     pub processor_manager: ProcessorManager,